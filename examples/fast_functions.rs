@@ -0,0 +1,36 @@
+extern crate mini_v8;
+
+use mini_v8::{MiniV8, Result, Value};
+use std::time::Instant;
+
+// Compares the cost of a million calls to the same function created in each of the ways that
+// `MiniV8` offers. Run with `cargo run --release --example fast_functions`.
+fn main() {
+    let mv8 = MiniV8::new();
+    let closure = mv8.create_function(|inv| {
+        let (a, b): (f64, f64) = inv.args.into(&inv.mv8)?;
+        Ok(a + b)
+    });
+    mv8.global().set("closure", closure).unwrap();
+    mv8.global().set("fast", mv8.create_function_fast(add)).unwrap();
+    mv8.global().set("fastF64", mv8.create_function_fast_f64(|a, b| a + b)).unwrap();
+
+    for name in &["closure", "fast", "fastF64"] {
+        let source = format!(r#"
+            (() => {{
+                let total = 0;
+                for (let i = 0; i < 1000000; i++) total = {}(total, 1);
+                return total;
+            }})()
+        "#, name);
+        let start = Instant::now();
+        let total: f64 = mv8.eval(source).unwrap();
+        println!("{:>8}: {:?} ({})", name, start.elapsed(), total);
+    }
+}
+
+fn add(mv8: &MiniV8, args: &[Value]) -> Result<Value> {
+    let a = args.first().cloned().unwrap_or(Value::Undefined).coerce_number(mv8)?;
+    let b = args.get(1).cloned().unwrap_or(Value::Undefined).coerce_number(mv8)?;
+    Ok(Value::Number(a + b))
+}
@@ -0,0 +1,37 @@
+extern crate mini_v8;
+extern crate tracing_subscriber;
+
+use mini_v8::{MiniV8, Function, Script, ScriptOrigin};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+fn main() {
+    // Report every span as it closes, along with how long it was open.
+    tracing_subscriber::fmt()
+        .with_max_level(tracing_subscriber::filter::LevelFilter::TRACE)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    let mv8 = MiniV8::new();
+    let square = mv8.create_function(|inv| {
+        let (x,): (f64,) = inv.args.into(&inv.mv8)?;
+        Ok(x * x)
+    });
+    mv8.global().set("square", square).unwrap();
+
+    let sum_squares: Function = mv8.eval(Script {
+        source: r#"
+            (function sumSquares(n) {
+                let sum = 0;
+                for (let i = 0; i < n; i++) sum += square(i);
+                return sum;
+            })
+        "#.to_owned(),
+        origin: Some(ScriptOrigin { name: "sum_squares.js".to_owned(), ..Default::default() }),
+        ..Default::default()
+    }).unwrap();
+    let result: f64 = sum_squares.call((4,)).unwrap();
+    assert_eq!(result, 14.0);
+
+    // Allocate enough garbage to trigger a few collections:
+    let _: () = mv8.eval("for (let i = 0; i < 100000; i++) { [i, {i}]; }").unwrap();
+}
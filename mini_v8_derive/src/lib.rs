@@ -0,0 +1,173 @@
+//! Derive macros for `mini_v8::ToValue` and `mini_v8::FromValue`, re-exported by `mini_v8` with
+//! the `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Generics, Type};
+
+/// Derives `ToValue` for a struct with named fields, which converts to a plain object with a
+/// property for each field, named after the field.
+///
+/// A newtype struct (a struct with a single unnamed field, e.g. `struct Meters(f64)`) converts
+/// transparently, exactly like its field does.
+#[proc_macro_derive(ToValue)]
+pub fn derive_to_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let shape = match struct_shape(&input) {
+        Ok(shape) => shape,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let generics = add_bounds(input.generics.clone(), quote!(::mini_v8::ToValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields = match shape {
+        Shape::Named(fields) => fields,
+        Shape::Newtype(_) => return quote! {
+            impl #impl_generics ::mini_v8::ToValue for #name #ty_generics #where_clause {
+                fn to_value(self, mv8: &::mini_v8::MiniV8) -> ::mini_v8::Result<::mini_v8::Value> {
+                    ::mini_v8::ToValue::to_value(self.0, mv8)
+                }
+            }
+        }.into(),
+    };
+    let sets = fields.iter().map(|(ident, _)| {
+        let key = ident.to_string();
+        quote!(object.set(#key, self.#ident)?;)
+    });
+    quote! {
+        impl #impl_generics ::mini_v8::ToValue for #name #ty_generics #where_clause {
+            fn to_value(self, mv8: &::mini_v8::MiniV8) -> ::mini_v8::Result<::mini_v8::Value> {
+                let object = mv8.create_object();
+                #(#sets)*
+                ::std::result::Result::Ok(::mini_v8::Value::Object(object))
+            }
+        }
+    }.into()
+}
+
+/// Derives `FromValue` for a struct with named fields, which converts from an object by reading
+/// a property for each field, named after the field.
+///
+/// Fields of type `Option<T>` may be missing (or `undefined`). Any other missing field, or a
+/// field whose value can't be converted, fails the conversion with
+/// `Error::FromJsConversionError`, whose `to` names the field, e.g. `"Point.x"`.
+///
+/// A newtype struct (a struct with a single unnamed field, e.g. `struct Meters(f64)`) converts
+/// transparently, exactly like its field does (including its errors).
+#[proc_macro_derive(FromValue)]
+pub fn derive_from_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let shape = match struct_shape(&input) {
+        Ok(shape) => shape,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let generics = add_bounds(input.generics.clone(), quote!(::mini_v8::FromValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fields = match shape {
+        Shape::Named(fields) => fields,
+        Shape::Newtype(ty) => return quote! {
+            impl #impl_generics ::mini_v8::FromValue for #name #ty_generics #where_clause {
+                fn from_value(
+                    value: ::mini_v8::Value,
+                    mv8: &::mini_v8::MiniV8,
+                ) -> ::mini_v8::Result<Self> {
+                    <#ty as ::mini_v8::FromValue>::from_value(value, mv8).map(#name)
+                }
+            }
+        }.into(),
+    };
+    let type_name = name.to_string();
+    let gets = fields.iter().map(|(ident, ty)| {
+        let key = ident.to_string();
+        let target = format!("{}.{}", type_name, key);
+        let missing = if is_option(ty) {
+            quote!()
+        } else {
+            quote! {
+                if value.is_undefined() {
+                    return ::std::result::Result::Err(::mini_v8::Error::FromJsConversionError {
+                        from: "undefined",
+                        to: #target,
+                    });
+                }
+            }
+        };
+        quote! {
+            #ident: {
+                let value: ::mini_v8::Value = object.get(#key)?;
+                #missing
+                <#ty as ::mini_v8::FromValue>::from_value(value, mv8)
+                    .map_err(|error| rename_conversion_error(error, #target))?
+            },
+        }
+    });
+    quote! {
+        impl #impl_generics ::mini_v8::FromValue for #name #ty_generics #where_clause {
+            fn from_value(
+                value: ::mini_v8::Value,
+                mv8: &::mini_v8::MiniV8,
+            ) -> ::mini_v8::Result<Self> {
+                // Names the type (or the field) that couldn't be converted in conversion errors,
+                // unless the error already names a field of a nested struct (e.g. `Inner.x`),
+                // which is more specific:
+                fn rename_conversion_error(
+                    error: ::mini_v8::Error,
+                    to: &'static str,
+                ) -> ::mini_v8::Error {
+                    match error {
+                        ::mini_v8::Error::FromJsConversionError { from, to: inner }
+                            if !inner.contains('.') =>
+                        {
+                            ::mini_v8::Error::FromJsConversionError { from, to }
+                        },
+                        error => error,
+                    }
+                }
+
+                let object = <::mini_v8::Object as ::mini_v8::FromValue>::from_value(value, mv8)
+                    .map_err(|error| rename_conversion_error(error, #type_name))?;
+                ::std::result::Result::Ok(#name { #(#gets)* })
+            }
+        }
+    }.into()
+}
+
+// The kinds of structs that the derive macros support.
+enum Shape<'a> {
+    Named(Vec<(&'a syn::Ident, &'a Type)>),
+    Newtype(&'a Type),
+}
+
+fn struct_shape(input: &DeriveInput) -> Result<Shape<'_>, Error> {
+    match &input.data {
+        Data::Struct(syn::DataStruct { fields: Fields::Named(fields), .. }) => {
+            return Ok(Shape::Named(fields.named.iter()
+                .map(|field| (field.ident.as_ref().unwrap(), &field.ty))
+                .collect()));
+        },
+        Data::Struct(syn::DataStruct { fields: Fields::Unnamed(fields), .. })
+            if fields.unnamed.len() == 1 => return Ok(Shape::Newtype(&fields.unnamed[0].ty)),
+        _ => {},
+    }
+    Err(Error::new_spanned(
+        &input.ident,
+        "only structs with named fields and newtype structs are supported",
+    ))
+}
+
+// Requires every type parameter to implement the derived trait.
+fn add_bounds(mut generics: Generics, bound: TokenStream2) -> Generics {
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
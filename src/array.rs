@@ -1,6 +1,8 @@
 use crate::*;
+use std::cmp::Ordering;
 use std::fmt;
 use std::marker::PhantomData;
+use std::ops::Range;
 
 #[derive(Clone)]
 pub struct Array {
@@ -23,6 +25,9 @@ impl Array {
     /// Get the value using the given array index. Returns `Value::Undefined` if no element at the
     /// index exists.
     ///
+    /// Array indices range from 0 to 2^32-2. As in JavaScript, `u32::MAX` is not an array index,
+    /// and refers to an ordinary property with the key `"4294967295"` instead.
+    ///
     /// Returns an error if `FromValue::from_value` fails for the element.
     pub fn get<V: FromValue>(&self, index: u32) -> Result<V> {
         self.mv8.try_catch(|scope| {
@@ -35,6 +40,10 @@ impl Array {
 
     /// Sets an array element using the given index and value.
     ///
+    /// Array indices range from 0 to 2^32-2. As in JavaScript, `u32::MAX` is not an array index:
+    /// Setting it sets an ordinary property with the key `"4294967295"`, and does not affect the
+    /// array's length.
+    ///
     /// Returns an error if `ToValue::to_value` fails for the value.
     pub fn set<V: ToValue>(&self, index: u32, value: V) -> Result<()> {
         let value = value.to_value(&self.mv8)?;
@@ -46,7 +55,7 @@ impl Array {
         })
     }
 
-    /// Returns the number of elements in the array.
+    /// Returns the number of elements in the array, which is at most 2^32-1.
     pub fn len(&self) -> u32 {
         self.mv8.scope(|scope| v8::Local::new(scope, self.handle.clone()).length())
     }
@@ -57,7 +66,67 @@ impl Array {
         self.set(self.len(), value)
     }
 
-    /// Returns an iterator over the array's indexable values.
+    /// Returns a new array containing a shallow copy of the elements from `start` up to (but not
+    /// including) `end`.
+    ///
+    /// Unlike JavaScript's `Array.prototype.slice`, the range is strictly checked: Returns an
+    /// `Error::InvalidRange` if `start > end` or if `end` is greater than the array's length. See
+    /// `Array::slice_js` for JavaScript's semantics.
+    pub fn slice(&self, start: u32, end: u32) -> Result<Array> {
+        self.check_range(start, end)?;
+        self.slice_inner(Value::Number(start as f64), Value::Number(end as f64))
+    }
+
+    /// Returns a new array containing a shallow copy of a portion of the array, exactly like
+    /// JavaScript's `Array.prototype.slice`: Negative indices count back from the end of the array,
+    /// out-of-range indices are clamped, and an `end` of `None` slices through to the end of the
+    /// array.
+    pub fn slice_js(&self, start: i64, end: Option<i64>) -> Result<Array> {
+        let end = end.map(|e| Value::Number(e as f64)).unwrap_or(Value::Undefined);
+        self.slice_inner(Value::Number(start as f64), end)
+    }
+
+    /// Converts the elements within the given range of indices into a `Vec`, without converting
+    /// any of the array's other elements.
+    ///
+    /// Returns an `Error::InvalidRange` if the range is out of bounds (see `Array::slice`), or an
+    /// error if `FromValue::from_value` fails for any of the elements.
+    pub fn copy_range_to_vec<V: FromValue>(&self, range: Range<u32>) -> Result<Vec<V>> {
+        self.check_range(range.start, range.end)?;
+        range.map(|i| self.get(i)).collect()
+    }
+
+    /// Sorts the array in place with a Rust comparator, using JavaScript's `Array.prototype.sort`.
+    /// As in JavaScript, the sort is stable, and elements are moved rather than copied, so object
+    /// elements keep their identity. Holes and `undefined` elements are moved to the end of the
+    /// array without being passed to the comparator.
+    ///
+    /// If the comparator returns an error, the sort is aborted (leaving the array partially
+    /// sorted) and the error is returned.
+    pub fn sort_with<F>(&self, mut cmp: F) -> Result<()>
+    where
+        F: FnMut(&Value, &Value) -> Result<Ordering>,
+    {
+        let intrinsics = self.mv8.intrinsics();
+        let mut state = SortState { mv8: self.mv8.clone(), cmp: &mut cmp, error: None };
+        let result = self.mv8.try_catch(|scope| {
+            // The comparator is only ever called by the sort below, which doesn't keep it around
+            // afterwards, so it is safe for it to point to `state` on the stack:
+            let ext = v8::External::new(scope, &mut state as *mut SortState as _);
+            let compare = v8::Function::builder(compare).data(ext.into()).build(scope).unwrap();
+            let sort = v8::Local::new(scope, intrinsics.array_sort.clone());
+            let array = v8::Local::new(scope, self.handle.clone());
+            sort.call(scope, array.into(), &[compare.into()]);
+            self.mv8.exception(scope)
+        });
+        match state.error {
+            Some(e) => Err(e),
+            None => result,
+        }
+    }
+
+    /// Returns an iterator over the array's indexable values. Holes in sparse arrays are read as
+    /// `Value::Undefined`, as in JavaScript.
     pub fn elements<V: FromValue>(self) -> Elements<V> {
         Elements {
             array: self,
@@ -68,13 +137,78 @@ impl Array {
     }
 }
 
+impl Array {
+    fn check_range(&self, start: u32, end: u32) -> Result<()> {
+        let len = self.len();
+        if start > end || end > len {
+            return Err(Error::InvalidRange { start, end, len });
+        }
+        Ok(())
+    }
+
+    fn slice_inner(&self, start: Value, end: Value) -> Result<Array> {
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.try_catch(|scope| {
+            let slice = v8::Local::new(scope, intrinsics.array_slice.clone());
+            let array = v8::Local::new(scope, self.handle.clone());
+            let args = [start.to_v8_value(scope), end.to_v8_value(scope)];
+            let result = slice.call(scope, array.into(), &args);
+            self.mv8.exception(scope)?;
+            Ok(Value::from_v8_value(&self.mv8, scope, result.unwrap()))
+        }).and_then(|v| v.into(&self.mv8))
+    }
+}
+
+struct SortState<'a> {
+    mv8: MiniV8,
+    cmp: &'a mut dyn FnMut(&Value, &Value) -> Result<Ordering>,
+    // The comparator's error, if it has returned one. This takes precedence over the exception
+    // that is thrown to abort the sort.
+    error: Option<Error>,
+}
+
+fn compare(
+    scope: &mut v8::HandleScope,
+    fca: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let ext = v8::Local::<v8::External>::try_from(fca.data()).unwrap();
+    let state = unsafe { &mut *(ext.value() as *mut SortState) };
+    let mv8 = state.mv8.clone();
+    mv8.enter_callback(scope, |scope| {
+        let a = Value::from_v8_value(&mv8, scope, fca.get(0));
+        let b = Value::from_v8_value(&mv8, scope, fca.get(1));
+        match (state.cmp)(&a, &b) {
+            Ok(ordering) => rv.set(v8::Integer::new(scope, ordering as i32).into()),
+            Err(e) => {
+                state.error = Some(e);
+                let message = create_string(scope, "sort comparator failed");
+                let exception = v8::Exception::error(scope, message);
+                scope.throw_exception(exception);
+            },
+        }
+    });
+}
+
+impl IntoIterator for &Array {
+    type Item = Result<Value>;
+    type IntoIter = Elements<Value>;
+
+    fn into_iter(self) -> Elements<Value> {
+        self.clone().elements()
+    }
+}
+
 impl fmt::Debug for Array {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let len = self.len();
+        let object = self.clone().into_object();
         write!(f, "[")?;
         for i in 0..len {
-            match self.get::<Value>(i) {
-                Ok(v) => write!(f, "{:?}", v)?,
+            match object.lookup_data_property(i) {
+                Ok((_, Some(v))) => write!(f, "{:?}", v)?,
+                Ok((PropertyKind::Accessor, None)) => write!(f, "<accessor>")?,
+                Ok((_, None)) => write!(f, "{:?}", Value::Undefined)?,
                 Err(_) => write!(f, "?")?,
             };
             if i + 1 < len {
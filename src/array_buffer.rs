@@ -0,0 +1,143 @@
+use crate::*;
+use std::cell::Cell;
+use std::fmt;
+
+/// Reference to a JavaScript `ArrayBuffer`, a fixed-length block of raw binary data.
+///
+/// `ArrayBuffer`s are JavaScript objects, so they are converted to `Value::Object`, and can be
+/// converted back from it with `FromValue`.
+#[derive(Clone)]
+pub struct ArrayBuffer {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::ArrayBuffer>,
+}
+
+impl ArrayBuffer {
+    /// Consumes the buffer and downgrades it to a JavaScript object.
+    pub fn into_object(self) -> Object {
+        self.mv8.clone().scope(|scope| {
+            let object: v8::Local<v8::Object> = v8::Local::new(scope, self.handle.clone()).into();
+            Object {
+                mv8: self.mv8,
+                handle: v8::Global::new(scope, object),
+            }
+        })
+    }
+
+    /// Returns the length of the buffer in bytes, which is zero if the buffer has been detached
+    /// (e.g. transferred with `structuredClone`).
+    pub fn len(&self) -> usize {
+        self.mv8.scope(|scope| v8::Local::new(scope, self.handle.clone()).byte_length())
+    }
+
+    /// Returns `true` if the buffer is empty (or detached).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies the contents of the buffer into a new `Vec`.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.mv8.scope(|scope| {
+            let buffer = v8::Local::new(scope, self.handle.clone());
+            let len = buffer.byte_length();
+            let store = buffer.get_backing_store();
+            store[..len].iter().map(Cell::get).collect()
+        })
+    }
+}
+
+impl fmt::Debug for ArrayBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<array buffer of {} bytes>", self.len())
+    }
+}
+
+/// A sequence of bytes, which converts to a JavaScript `Uint8Array` and from any JavaScript
+/// `ArrayBuffer` or view of one (a typed array such as `Uint8Array`, or a `DataView`).
+///
+/// `Vec<u8>` already converts to and from JavaScript arrays of numbers, so binary data is wrapped
+/// in `Bytes` instead to be passed as a buffer. Converting to JavaScript reuses the `Vec`'s
+/// allocation, while converting from JavaScript copies the bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Consumes the `Bytes` and returns the underlying `Vec`.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Bytes {
+        Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a [u8]> for Bytes {
+    fn from(bytes: &'a [u8]) -> Bytes {
+        Bytes(bytes.to_vec())
+    }
+}
+
+impl ToValue for ArrayBuffer {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::Object(self.into_object()).to_value(mv8)
+    }
+}
+
+impl FromValue for ArrayBuffer {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<ArrayBuffer> {
+        let object = match value {
+            Value::Object(object) => object,
+            value => return Err(Error::from_js_conversion(value.type_name(), "ArrayBuffer")),
+        };
+        mv8.scope(|scope| {
+            let object = v8::Local::new(scope, object.handle);
+            match v8::Local::<v8::ArrayBuffer>::try_from(object) {
+                Ok(buffer) => Ok(ArrayBuffer {
+                    mv8: mv8.clone(),
+                    handle: v8::Global::new(scope, buffer),
+                }),
+                Err(_) => Err(Error::from_js_conversion("object", "ArrayBuffer")),
+            }
+        })
+    }
+}
+
+impl ToValue for Bytes {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        let len = self.0.len();
+        mv8.scope(|scope| {
+            let store = v8::ArrayBuffer::new_backing_store_from_vec(self.0).make_shared();
+            let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+            let array = v8::Uint8Array::new(scope, buffer, 0, len)
+                .ok_or_else(|| Error::ToJsConversionError { from: "Bytes", to: "Uint8Array" })?;
+            Ok(Value::from_v8_value(mv8, scope, array.into()))
+        })
+    }
+}
+
+impl FromValue for Bytes {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<Bytes> {
+        let object = match value {
+            Value::Object(object) => object,
+            value => return Err(Error::from_js_conversion(value.type_name(), "Bytes")),
+        };
+        mv8.scope(|scope| {
+            let object = v8::Local::new(scope, object.handle);
+            if let Ok(view) = v8::Local::<v8::ArrayBufferView>::try_from(object) {
+                let mut bytes = vec![0; view.byte_length()];
+                let len = view.copy_contents(&mut bytes);
+                bytes.truncate(len);
+                Ok(Bytes(bytes))
+            } else if let Ok(buffer) = v8::Local::<v8::ArrayBuffer>::try_from(object) {
+                let len = buffer.byte_length();
+                let store = buffer.get_backing_store();
+                Ok(Bytes(store[..len].iter().map(Cell::get).collect()))
+            } else {
+                Err(Error::from_js_conversion("object", "Bytes"))
+            }
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use crate::*;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Converts the output of an async function's future, which has to wait for the `MiniV8` that
+// polls the future.
+type Output = Box<dyn FnOnce(&MiniV8) -> Result<Value>>;
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = Result<Output>>>>,
+    resolver: v8::Global<v8::PromiseResolver>,
+}
+
+// The futures of the calls to async functions that haven't completed yet, stored in an isolate
+// slot, so that they are dropped (i.e. cancelled) along with the isolate.
+#[derive(Default)]
+pub(crate) struct AsyncTasks(RefCell<Vec<Task>>);
+
+impl MiniV8 {
+    /// Wraps a Rust function or closure that returns a future, creating a callable JavaScript
+    /// function handle to it. Calling the function from JavaScript calls `func` right away and
+    /// returns a promise, which is fulfilled with the future's output (converted with `ToValue`)
+    /// or rejected with its error (converted with `Error::to_value`) once the future completes.
+    ///
+    /// The futures are polled by `MiniV8::poll_pending`, which the embedder's executor has to
+    /// drive for the promises to ever settle. Futures that haven't completed when the `MiniV8` is
+    /// dropped are dropped along with it, without settling their promises.
+    ///
+    /// The future should only hold Rust values: Arguments are best converted before the future
+    /// is created, and the `MiniV8` (or any value that belongs to it) shouldn't be moved into the
+    /// future, which would keep the `MiniV8` alive until the future completes.
+    #[track_caller]
+    pub fn create_async_function<F, Fut, R>(&self, func: F) -> Function
+    where
+        F: Fn(Invocation) -> Fut + 'static,
+        Fut: Future<Output = Result<R>> + 'static,
+        R: ToValue + 'static,
+    {
+        self.create_function(move |inv| {
+            let mv8 = inv.mv8.clone();
+            let future = func(inv);
+            Ok(mv8.spawn_promise(async move {
+                let value = future.await?;
+                Ok(Box::new(move |mv8: &MiniV8| value.to_value(mv8)) as Output)
+            }))
+        })
+    }
+
+    /// Polls the futures of the pending calls to functions created with
+    /// `MiniV8::create_async_function`, settles the promises of those that have completed, and
+    /// runs pending microtasks so that JavaScript awaiting the promises continues (which may call
+    /// more async functions, whose futures are polled as well).
+    ///
+    /// Returns `Poll::Ready` once no futures are pending, and `Poll::Pending` otherwise, in which
+    /// case the task of `cx` is woken when a future can make progress. This is meant to be driven
+    /// by an executor running on the thread that owns this `MiniV8`, e.g. with
+    /// `futures::future::poll_fn(|cx| mv8.poll_pending(cx)).await`, after running JavaScript that
+    /// calls async functions.
+    pub fn poll_pending(&self, cx: &mut Context) -> Poll<()> {
+        loop {
+            let tasks = self.async_tasks(|tasks| tasks.0.take());
+            let mut pending = Vec::new();
+            let mut completed = Vec::new();
+            for mut task in tasks {
+                match task.future.as_mut().poll(cx) {
+                    Poll::Ready(output) => completed.push((task.resolver, output)),
+                    Poll::Pending => pending.push(task),
+                }
+            }
+            // Keep the tasks in the order they were spawned in, including any spawned by the
+            // futures while they were polled:
+            self.async_tasks(|tasks| {
+                let mut tasks = tasks.0.borrow_mut();
+                pending.append(&mut tasks);
+                *tasks = pending;
+            });
+            if completed.is_empty() {
+                break;
+            }
+            for (resolver, output) in completed {
+                self.settle_promise(resolver, output.and_then(|output| output(self)));
+            }
+            self.run_microtasks();
+        }
+        if self.async_tasks(|tasks| tasks.0.borrow().is_empty()) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn spawn_promise<Fut>(&self, future: Fut) -> Promise
+    where
+        Fut: Future<Output = Result<Output>> + 'static,
+    {
+        let (promise, resolver) = self.scope(|scope| {
+            let resolver = v8::PromiseResolver::new(scope).unwrap();
+            let promise = resolver.get_promise(scope);
+            (v8::Global::new(scope, promise), v8::Global::new(scope, resolver))
+        });
+        let task = Task { future: Box::pin(future), resolver };
+        self.async_tasks(|tasks| tasks.0.borrow_mut().push(task));
+        Promise { mv8: self.clone(), handle: promise }
+    }
+
+    fn settle_promise(&self, resolver: v8::Global<v8::PromiseResolver>, result: Result<Value>) {
+        let (fulfilled, value) = match result {
+            Ok(value) => (true, value),
+            Err(error) => (false, error.to_value(self)),
+        };
+        self.scope(|scope| {
+            let resolver = v8::Local::new(scope, resolver);
+            let value = value.to_v8_value(scope);
+            if fulfilled {
+                resolver.resolve(scope, value);
+            } else {
+                resolver.reject(scope, value);
+            }
+        });
+    }
+}
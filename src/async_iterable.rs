@@ -0,0 +1,171 @@
+use crate::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// The sending end of an async iterable created with `MiniV8::create_async_channel`.
+///
+/// Dropping the sender closes the channel.
+pub struct AsyncSender<T: ToValue> {
+    mv8: MiniV8,
+    channel: Rc<RefCell<Channel>>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ToValue> AsyncSender<T> {
+    /// Sends an item, which settles the promise of a pending `next()` call, or is buffered until
+    /// the next call to `next()`. An `Err` item rejects the promise of its `next()` call.
+    ///
+    /// Promise reactions only run at the next microtask checkpoint, e.g. once the next evaluation
+    /// has completed, so sending an item runs no JavaScript other than a `then` getter that a
+    /// script may have defined on `Object.prototype`.
+    pub fn send(&self, item: Result<T>) {
+        let item = item.and_then(|value| value.to_value(&self.mv8));
+        // Settling a promise can run JavaScript (e.g. when resolving with a thenable) that calls
+        // `next()` again, so the channel must not be borrowed while settling:
+        let mut channel = self.channel.borrow_mut();
+        match channel.pending.pop_front() {
+            Some(resolver) => {
+                drop(channel);
+                settle(&self.mv8, &resolver, Some(item));
+            },
+            None => channel.buffer.push_back(item),
+        }
+    }
+
+    /// Closes the channel, so that `next()` reports that iteration is done once all buffered items
+    /// have been received.
+    pub fn close(&self) {
+        let pending = {
+            let mut channel = self.channel.borrow_mut();
+            channel.closed = true;
+            std::mem::take(&mut channel.pending)
+        };
+        for resolver in pending {
+            settle(&self.mv8, &resolver, None);
+        }
+    }
+}
+
+impl<T: ToValue> Drop for AsyncSender<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T: ToValue> fmt::Debug for AsyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<async sender>")
+    }
+}
+
+#[derive(Default)]
+struct Channel {
+    buffer: VecDeque<Result<Value>>,
+    // The resolvers of `next()` calls that are waiting for items, in the order of the calls.
+    pending: VecDeque<v8::Global<v8::PromiseResolver>>,
+    closed: bool,
+}
+
+impl MiniV8 {
+    /// Creates an object implementing JavaScript's async iteration protocols, which produces the
+    /// items of `source`, so that scripts can iterate over it with `for await`.
+    ///
+    /// Each call to the object's `next()` method takes the next item from `source`, returning a
+    /// promise that is fulfilled with the item (or rejected with the error, for an `Err` item),
+    /// and that reports that iteration is done once `source` is exhausted.
+    pub fn create_async_iterable<T, I>(&self, source: I) -> Object
+    where
+        T: ToValue,
+        I: Iterator<Item = Result<T>> + 'static,
+    {
+        let mut source = source.fuse();
+        let next = self.create_function_mut(move |inv| {
+            let item = source.next().map(|item| item.and_then(|value| value.to_value(&inv.mv8)));
+            let resolver = create_resolver(&inv.mv8);
+            settle(&inv.mv8, &resolver, item);
+            Ok(promise(&inv.mv8, &resolver))
+        });
+        create_async_iterator(self, next)
+    }
+
+    /// Creates an object implementing JavaScript's async iteration protocols (as with
+    /// `MiniV8::create_async_iterable`), which produces the items sent through the returned
+    /// `AsyncSender`. Calls to `next()` that are made before their items have been sent return
+    /// promises that stay pending until then.
+    pub fn create_async_channel<T: ToValue>(&self) -> (Object, AsyncSender<T>) {
+        let channel = Rc::new(RefCell::new(Channel::default()));
+        let next_channel = channel.clone();
+        let next = self.create_function(move |inv| {
+            let resolver = create_resolver(&inv.mv8);
+            let mut channel = next_channel.borrow_mut();
+            let item = match channel.buffer.pop_front() {
+                Some(item) => Some(item),
+                None if channel.closed => None,
+                None => {
+                    channel.pending.push_back(resolver.clone());
+                    return Ok(promise(&inv.mv8, &resolver));
+                },
+            };
+            drop(channel);
+            settle(&inv.mv8, &resolver, item);
+            Ok(promise(&inv.mv8, &resolver))
+        });
+        let sender = AsyncSender { mv8: self.clone(), channel, _phantom: PhantomData };
+        (create_async_iterator(self, next), sender)
+    }
+}
+
+fn create_async_iterator(mv8: &MiniV8, next: Function) -> Object {
+    let iterator = mv8.create_object();
+    iterator.set("next", next).unwrap();
+    let this = mv8.create_function(|inv| Ok(inv.this));
+    mv8.scope(|scope| {
+        let iterator = v8::Local::new(scope, iterator.handle.clone());
+        let key = v8::Symbol::get_async_iterator(scope);
+        let this = v8::Local::new(scope, this.handle.clone());
+        iterator.set(scope, key.into(), this.into());
+    });
+    iterator
+}
+
+fn create_resolver(mv8: &MiniV8) -> v8::Global<v8::PromiseResolver> {
+    mv8.scope(|scope| {
+        let resolver = v8::PromiseResolver::new(scope).unwrap();
+        v8::Global::new(scope, resolver)
+    })
+}
+
+fn promise(mv8: &MiniV8, resolver: &v8::Global<v8::PromiseResolver>) -> Value {
+    mv8.scope(|scope| {
+        let promise = v8::Local::new(scope, resolver.clone()).get_promise(scope);
+        Value::from_v8_value(mv8, scope, promise.into())
+    })
+}
+
+// Settles the promise of a `next()` call with an item, or with the end of iteration for `None`.
+fn settle(mv8: &MiniV8, resolver: &v8::Global<v8::PromiseResolver>, item: Option<Result<Value>>) {
+    let (result, fulfill) = match item {
+        Some(Ok(value)) => (iterator_result(mv8, value, false), true),
+        Some(Err(e)) => (e.to_value(mv8), false),
+        None => (iterator_result(mv8, Value::Undefined, true), true),
+    };
+    mv8.scope(|scope| {
+        let resolver = v8::Local::new(scope, resolver.clone());
+        let result = result.to_v8_value(scope);
+        if fulfill {
+            resolver.resolve(scope, result);
+        } else {
+            resolver.reject(scope, result);
+        }
+    });
+}
+
+fn iterator_result(mv8: &MiniV8, value: Value, done: bool) -> Value {
+    let result = mv8.create_object();
+    result.set("value", value).unwrap();
+    result.set("done", done).unwrap();
+    Value::Object(result)
+}
@@ -0,0 +1,145 @@
+use crate::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::string::String as StdString;
+
+/// A CommonJS module, as produced by the loader given to `MiniV8::install_commonjs`.
+pub enum ModuleSource {
+    /// JavaScript source text, evaluated as the body of a CommonJS module.
+    Script {
+        /// The module's resolved path, which identifies the module in the module cache.
+        path: PathBuf,
+        /// The module's source text.
+        source: StdString,
+    },
+    /// A pre-built object of exports, typically implemented in Rust.
+    Exports {
+        /// The module's resolved path, which identifies the module in the module cache.
+        path: PathBuf,
+        /// The module's exports.
+        exports: Object,
+    },
+}
+
+impl ModuleSource {
+    fn path(&self) -> &Path {
+        match self {
+            ModuleSource::Script { path, .. } | ModuleSource::Exports { path, .. } => path,
+        }
+    }
+}
+
+type Loader = Box<dyn Fn(&str, &Path) -> Result<ModuleSource>>;
+
+struct CommonJs {
+    loader: Loader,
+    cache: RefCell<HashMap<PathBuf, Object>>,
+    // The resolved paths that the loader has returned, by referrer and specifier:
+    resolved: RefCell<HashMap<(PathBuf, StdString), PathBuf>>,
+}
+
+// The parameters of the function that every module's source is wrapped in, as in Node.js.
+const WRAPPER_PARAMS: [&str; 5] = ["exports", "require", "module", "__filename", "__dirname"];
+
+impl MiniV8 {
+    /// Installs CommonJS-style `require`, `module`, and `exports` globals.
+    ///
+    /// The first call to `require(specifier)` from a given module calls `loader` with the
+    /// specifier and the resolved path of the requiring module (or an empty path if `require` was
+    /// called from outside of a module). The loader alone decides how specifiers are resolved and
+    /// where modules come from, and identifies each module by the resolved path it returns in
+    /// `ModuleSource`.
+    ///
+    /// Modules are cached by their resolved path: A module's source is only evaluated the first
+    /// time it is required, and subsequent requires return the same `module.exports`. The loader
+    /// is only called once for each specifier and referrer that resolve to a cached module, so
+    /// requiring a module again doesn't produce its `ModuleSource` again. As in Node.js, the
+    /// source is evaluated as the body of a function with the parameters `exports`, `require`,
+    /// `module`, `__filename`, and `__dirname`, with `this` set to `module.exports`. A module is
+    /// cached before it is evaluated, so circular requires receive the partially populated
+    /// exports of the module that is still being evaluated. A module that throws during its
+    /// evaluation is removed from the cache, and requiring it again calls the loader again.
+    ///
+    /// Errors returned by the loader are thrown as exceptions from `require`.
+    pub fn install_commonjs<F>(&self, loader: F) -> Result<()>
+    where
+        F: Fn(&str, &Path) -> Result<ModuleSource> + 'static,
+    {
+        let commonjs = Rc::new(CommonJs {
+            loader: Box::new(loader),
+            cache: Default::default(),
+            resolved: Default::default(),
+        });
+        let module = create_module(self, ".")?;
+        let global = self.global();
+        global.set("require", create_require(self, &commonjs, PathBuf::new()))?;
+        global.set("exports", module.get::<_, Value>("exports")?)?;
+        global.set("module", module)
+    }
+}
+
+fn create_module(mv8: &MiniV8, id: &str) -> Result<Object> {
+    let module = mv8.create_object();
+    module.set("id", id)?;
+    module.set("exports", mv8.create_object())?;
+    module.set("loaded", false)?;
+    Ok(module)
+}
+
+fn create_require(mv8: &MiniV8, commonjs: &Rc<CommonJs>, referrer: PathBuf) -> Function {
+    let commonjs = commonjs.clone();
+    mv8.create_function(move |inv| {
+        let (specifier,): (StdString,) = inv.args.into(&inv.mv8)?;
+        require(&inv.mv8, &commonjs, &referrer, &specifier)
+    })
+}
+
+fn require(
+    mv8: &MiniV8,
+    commonjs: &Rc<CommonJs>,
+    referrer: &Path,
+    specifier: &str,
+) -> Result<Value> {
+    let request = (referrer.to_path_buf(), specifier.to_owned());
+    let resolved = commonjs.resolved.borrow().get(&request).cloned();
+    let cached = resolved.and_then(|path| commonjs.cache.borrow().get(&path).cloned());
+    if let Some(module) = cached {
+        return module.get("exports");
+    }
+
+    let source = (commonjs.loader)(specifier, referrer)?;
+    let path = source.path().to_path_buf();
+    commonjs.resolved.borrow_mut().insert(request, path.clone());
+    let cached = commonjs.cache.borrow().get(&path).cloned();
+    if let Some(module) = cached {
+        return module.get("exports");
+    }
+
+    let filename = path.to_string_lossy().into_owned();
+    let module = create_module(mv8, &filename)?;
+    commonjs.cache.borrow_mut().insert(path.clone(), module.clone());
+
+    let result = match source {
+        ModuleSource::Exports { exports, .. } => module.set("exports", exports),
+        ModuleSource::Script { source, .. } => {
+            let origin = ScriptOrigin { name: filename.clone(), ..Default::default() };
+            mv8.compile_function(&source, &WRAPPER_PARAMS, Some(origin)).and_then(|wrapper| {
+                let exports: Value = module.get("exports")?;
+                let require = create_require(mv8, commonjs, path.clone());
+                let dirname = path.parent().unwrap_or(Path::new("")).to_string_lossy().into_owned();
+                let args = (exports.clone(), require, module.clone(), filename, dirname);
+                wrapper.call_method(exports, args)
+            })
+        },
+    };
+
+    if let Err(e) = result {
+        commonjs.cache.borrow_mut().remove(&path);
+        return Err(e);
+    }
+
+    module.set("loaded", true)?;
+    module.get("exports")
+}
@@ -0,0 +1,96 @@
+use crate::*;
+use std::fmt;
+use std::time::Duration;
+
+/// A JavaScript script that has been compiled with `MiniV8::compile` or
+/// `MiniV8::compile_with_cache`, and can be run any number of times without being parsed again.
+///
+/// A compiled script isn't bound to the global context, so every run sees the global context's
+/// current state, like `MiniV8::eval` does.
+#[derive(Clone)]
+pub struct CompiledScript {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::UnboundScript>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl CompiledScript {
+    /// Runs the script and returns its result, honoring the timeout of the `Script` it was
+    /// compiled from.
+    pub fn run<R: FromValue>(&self) -> Result<R> {
+        self.mv8.with_timeout(self.timeout, || {
+            self.mv8.try_catch(|scope| {
+                let script = v8::Local::new(scope, self.handle.clone());
+                let result = script.bind_to_current_context(scope).run(scope);
+                self.mv8.exception(scope)?;
+                Ok(Value::from_v8_value(&self.mv8, scope, result.unwrap()))
+            })
+        })?.into(&self.mv8)
+    }
+
+    /// Serializes V8's code cache for the script, which can be persisted and passed to
+    /// `MiniV8::compile_with_cache` (in this process or another one) to skip parsing the script
+    /// again.
+    ///
+    /// The cache only holds the functions that have been compiled so far, so producing it after
+    /// running the script also covers the functions that were called during the run. The cache
+    /// is only accepted by the same V8 version with the same flags, and only for the same source.
+    pub fn cache_data(&self) -> Vec<u8> {
+        self.mv8.scope(|scope| {
+            let script = v8::Local::new(scope, self.handle.clone());
+            script.create_code_cache().map(|cache| cache.to_vec()).unwrap_or_default()
+        })
+    }
+}
+
+impl fmt::Debug for CompiledScript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<compiled script>")
+    }
+}
+
+impl MiniV8 {
+    /// Compiles a JavaScript script without running it. Returns an error if the script contains a
+    /// syntax error.
+    pub fn compile<S: Into<Script>>(&self, script: S) -> Result<CompiledScript> {
+        self.compile_inner(script.into(), &[])
+    }
+
+    /// Compiles a JavaScript script like `MiniV8::compile`, consuming a code cache produced by
+    /// `CompiledScript::cache_data` for the same source to skip parsing it.
+    ///
+    /// If V8 rejects the cache (e.g. because it was produced by another V8 version, or for another
+    /// source, or is corrupt), the script is silently compiled from scratch instead.
+    pub fn compile_with_cache<S: Into<Script>>(
+        &self,
+        script: S,
+        cache: &[u8],
+    ) -> Result<CompiledScript> {
+        self.compile_inner(script.into(), cache)
+    }
+
+    fn compile_inner(&self, script: Script, cache: &[u8]) -> Result<CompiledScript> {
+        use v8::script_compiler::{CachedData, CompileOptions, NoCacheReason, Source};
+
+        let handle = self.try_catch(|scope| -> Result<_> {
+            let source = create_string(scope, &script.source);
+            let origin = script.origin.as_ref().map(|o| create_origin(scope, o, false));
+            let (source, options) = if cache.is_empty() {
+                (Source::new(source, origin.as_ref()), CompileOptions::NoCompileOptions)
+            } else {
+                let cache = CachedData::new(cache);
+                let source = Source::new_with_cached_data(source, origin.as_ref(), cache);
+                (source, CompileOptions::ConsumeCodeCache)
+            };
+            let unbound = v8::script_compiler::compile_unbound_script(
+                scope,
+                source,
+                options,
+                NoCacheReason::NoReason,
+            );
+            self.exception(scope)?;
+            Ok(v8::Global::new(scope, unbound.unwrap()))
+        })?;
+        Ok(CompiledScript { mv8: self.clone(), handle, timeout: script.timeout })
+    }
+}
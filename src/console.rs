@@ -0,0 +1,158 @@
+use crate::*;
+use std::rc::Rc;
+use std::slice;
+use std::string::String as StdString;
+
+/// The level of a message logged with the `console` installed by `MiniV8::init_console`, which is
+/// named after the `console` method that logged it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConsoleLevel {
+    /// `console.log`.
+    Log,
+    /// `console.info`.
+    Info,
+    /// `console.warn`.
+    Warn,
+    /// `console.error`.
+    Error,
+    /// `console.debug`.
+    Debug,
+    /// `console.trace`.
+    Trace,
+}
+
+const METHODS: [(&str, ConsoleLevel); 6] = [
+    ("log", ConsoleLevel::Log),
+    ("info", ConsoleLevel::Info),
+    ("warn", ConsoleLevel::Warn),
+    ("error", ConsoleLevel::Error),
+    ("debug", ConsoleLevel::Debug),
+    ("trace", ConsoleLevel::Trace),
+];
+
+impl MiniV8 {
+    /// Installs `console.log`, `console.info`, `console.warn`, `console.error`, `console.debug`
+    /// and `console.trace` on the global `console` object (which is created if it doesn't exist),
+    /// replacing any previously installed methods. Each method calls `sink` with its level and the
+    /// arguments that it was called with, which can be turned into a message with
+    /// `MiniV8::format_console`.
+    ///
+    /// `sink` is given the `MiniV8` rather than capturing a clone of it, which would keep the
+    /// `MiniV8` alive forever.
+    pub fn init_console<F>(&self, sink: F) -> Result<()>
+    where
+        F: Fn(&MiniV8, ConsoleLevel, Vec<Value>) + 'static,
+    {
+        let sink = Rc::new(sink);
+        let global = self.global();
+        let console = match global.get("console")? {
+            Value::Object(console) => console,
+            _ => {
+                let console = self.create_object();
+                global.set("console", console.clone())?;
+                console
+            },
+        };
+        for &(name, level) in &METHODS {
+            let sink = sink.clone();
+            let method = self.create_function(move |inv| {
+                sink(&inv.mv8, level, inv.args.into_vec());
+                Ok(Value::Undefined)
+            });
+            console.set(name, method)?;
+        }
+        Ok(())
+    }
+
+    /// Installs a `console` with `MiniV8::init_console` that logs its messages with the `log`
+    /// crate under the `console` target, at the level corresponding to the method (`console.log`
+    /// logs at the info level).
+    #[cfg(feature = "log")]
+    pub fn init_console_default(&self) -> Result<()> {
+        self.init_console(|mv8, level, args| {
+            let level = match level {
+                ConsoleLevel::Log | ConsoleLevel::Info => log::Level::Info,
+                ConsoleLevel::Warn => log::Level::Warn,
+                ConsoleLevel::Error => log::Level::Error,
+                ConsoleLevel::Debug => log::Level::Debug,
+                ConsoleLevel::Trace => log::Level::Trace,
+            };
+            log::log!(target: "console", level, "{}", mv8.format_console(&args));
+        })
+    }
+
+    /// Formats the arguments of a `console` method into a message the way browsers do: If the
+    /// first argument is a string and there are more arguments, its `%s`, `%d`, `%i`, `%f`, `%o`,
+    /// `%O` and `%c` specifiers are substituted with the following arguments (`%c`'s CSS is
+    /// dropped), and `%%` becomes `%`. The remaining arguments are appended, separated by spaces.
+    ///
+    /// Strings are included as they are, arrays and objects are rendered as JSON where possible
+    /// (so that `{a: 1}` isn't rendered as `[object Object]`), and other values are coerced to
+    /// strings.
+    pub fn format_console(&self, args: &[Value]) -> StdString {
+        let mut rest = args.iter();
+        let mut parts = Vec::new();
+        if let (Some(Value::String(format)), true) = (args.first(), args.len() > 1) {
+            rest.next();
+            parts.push(self.substitute_console(&format.to_string(), &mut rest));
+        }
+        parts.extend(rest.map(|arg| self.render_console(arg)));
+        parts.join(" ")
+    }
+
+    fn substitute_console(&self, format: &str, args: &mut slice::Iter<Value>) -> StdString {
+        let mut output = StdString::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+            let substitution = match chars.peek() {
+                Some('%') => Some("%".to_owned()),
+                Some(&specifier @ ('s' | 'd' | 'i' | 'f' | 'o' | 'O' | 'c')) => {
+                    args.next().map(|arg| match specifier {
+                        'd' | 'i' => self.render_console_number(arg, true),
+                        'f' => self.render_console_number(arg, false),
+                        'c' => StdString::new(),
+                        _ => self.render_console(arg),
+                    })
+                },
+                _ => None,
+            };
+            match substitution {
+                Some(substitution) => {
+                    chars.next();
+                    output.push_str(&substitution);
+                },
+                // Specifiers without a matching argument are left as they are:
+                None => output.push('%'),
+            }
+        }
+        output
+    }
+
+    fn render_console_number(&self, value: &Value, integer: bool) -> StdString {
+        let number = value.coerce_number(self).unwrap_or(f64::NAN);
+        let number = if integer { number.trunc() } else { number };
+        self.render_console(&Value::Number(number))
+    }
+
+    fn render_console(&self, value: &Value) -> StdString {
+        match value {
+            Value::String(string) => return string.to_string(),
+            Value::Symbol(symbol) => return format!("{:?}", symbol),
+            Value::Array(_) | Value::Object(_) => {
+                // Objects that can't be serialized (e.g. circular ones) fall back to coercion:
+                if let Ok(json) = self.json_stringify(value.clone()) {
+                    return json;
+                }
+            },
+            _ => {},
+        }
+        match value.coerce_string(self) {
+            Ok(string) => string.to_string(),
+            Err(_) => format!("[{}]", value.type_name()),
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use crate::*;
 use std::collections::{BTreeMap, HashMap, BTreeSet, HashSet};
+use std::ffi::OsString;
 use std::hash::{BuildHasher, Hash};
+use std::path::PathBuf;
+use std::result::Result as StdResult;
 use std::string::String as StdString;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 impl ToValue for Value {
-    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        mv8.assert_owns(&self)?;
         Ok(self)
     }
 }
@@ -28,6 +32,7 @@ impl FromValue for () {
     }
 }
 
+/// Converts `None` to `null`.
 impl<T: ToValue> ToValue for Option<T> {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
         match self {
@@ -37,6 +42,8 @@ impl<T: ToValue> ToValue for Option<T> {
     }
 }
 
+/// Converts both `undefined` and `null` to `None`, so that missing properties and elements are
+/// read as `None` as well.
 impl<T: FromValue> FromValue for Option<T> {
     fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
         match value {
@@ -46,9 +53,20 @@ impl<T: FromValue> FromValue for Option<T> {
     }
 }
 
+/// Converts an `Err` into `Error::Value`, so that a callback that returns a `Result` like this
+/// throws its error value (e.g. a structured error object) rather than returning it.
+impl<T: ToValue, E: ToValue> ToValue for StdResult<T, E> {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        match self {
+            Ok(value) => value.to_value(mv8),
+            Err(error) => Err(Error::Value(error.to_value(mv8)?)),
+        }
+    }
+}
+
 impl ToValue for String {
-    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
-        Ok(Value::String(self))
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::String(self).to_value(mv8)
     }
 }
 
@@ -58,9 +76,24 @@ impl FromValue for String {
     }
 }
 
+impl ToValue for Symbol {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::Symbol(self).to_value(mv8)
+    }
+}
+
+impl FromValue for Symbol {
+    fn from_value(value: Value, _mv8: &MiniV8) -> Result<Symbol> {
+        match value {
+            Value::Symbol(s) => Ok(s),
+            value => Err(Error::from_js_conversion(value.type_name(), "Symbol")),
+        }
+    }
+}
+
 impl ToValue for Array {
-    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
-        Ok(Value::Array(self))
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::Array(self).to_value(mv8)
     }
 }
 
@@ -74,8 +107,8 @@ impl FromValue for Array {
 }
 
 impl ToValue for Function {
-    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
-        Ok(Value::Function(self))
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::Function(self).to_value(mv8)
     }
 }
 
@@ -89,8 +122,8 @@ impl FromValue for Function {
 }
 
 impl ToValue for Object {
-    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
-        Ok(Value::Object(self))
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Value::Object(self).to_value(mv8)
     }
 }
 
@@ -110,11 +143,7 @@ where
     S: BuildHasher,
 {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
-        let object = mv8.create_object();
-        for (k, v) in self.into_iter() {
-            object.set(k, v)?;
-        }
-        Ok(Value::Object(object))
+        mv8.create_object_from(self).map(Value::Object)
     }
 }
 
@@ -138,11 +167,7 @@ where
     V: ToValue,
 {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
-        let object = mv8.create_object();
-        for (k, v) in self.into_iter() {
-            object.set(k, v)?;
-        }
-        Ok(Value::Object(object))
+        mv8.create_object_from(self).map(Value::Object)
     }
 }
 
@@ -161,11 +186,7 @@ where
 
 impl<V: ToValue> ToValue for BTreeSet<V> {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
-        let array = mv8.create_array();
-        for v in self.into_iter() {
-            array.push(v)?;
-        }
-        Ok(Value::Array(array))
+        mv8.create_array_from(self).map(Value::Array)
     }
 }
 
@@ -180,11 +201,7 @@ impl<V: FromValue + Ord> FromValue for BTreeSet<V> {
 
 impl<V: ToValue> ToValue for HashSet<V> {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
-        let array = mv8.create_array();
-        for v in self.into_iter() {
-            array.push(v)?;
-        }
-        Ok(Value::Array(array))
+        mv8.create_array_from(self).map(Value::Array)
     }
 }
 
@@ -199,11 +216,7 @@ impl<V: FromValue + Hash + Eq> FromValue for HashSet<V> {
 
 impl<V: ToValue> ToValue for Vec<V> {
     fn to_value(self, mv8: &MiniV8) -> Result<Value> {
-        let array = mv8.create_array();
-        for v in self.into_iter() {
-            array.push(v)?;
-        }
-        Ok(Value::Array(array))
+        mv8.create_array_from(self).map(Value::Array)
     }
 }
 
@@ -216,6 +229,31 @@ impl<V: FromValue> FromValue for Vec<V> {
     }
 }
 
+impl<V: ToValue + Clone> ToValue for &[V] {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        mv8.create_array_from(self.iter().cloned()).map(Value::Array)
+    }
+}
+
+impl<V: ToValue, const N: usize> ToValue for [V; N] {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        mv8.create_array_from(self).map(Value::Array)
+    }
+}
+
+impl<V: FromValue, const N: usize> FromValue for [V; N] {
+    fn from_value(value: Value, _mv8: &MiniV8) -> Result<Self> {
+        match value {
+            Value::Array(a) => {
+                let elements = a.elements().collect::<Result<Vec<V>>>()?;
+                elements.try_into()
+                    .map_err(|_| Error::from_js_conversion("array", "fixed-size array"))
+            },
+            value => Err(Error::from_js_conversion(value.type_name(), "fixed-size array")),
+        }
+    }
+}
+
 impl ToValue for bool {
     fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
         Ok(Value::Boolean(self))
@@ -246,6 +284,83 @@ impl<'a> ToValue for &'a str {
     }
 }
 
+/// Converts to a string holding the single character.
+impl ToValue for char {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        Ok(Value::String(mv8.create_string(self.encode_utf8(&mut [0; 4]))))
+    }
+}
+
+/// Converts from a string holding exactly one code point (which may be made up of two UTF-16 code
+/// units, e.g. `'😊'`). Other values are coerced to strings first.
+impl FromValue for char {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+        let units = value.coerce_string(mv8)?.to_utf16();
+        let mut chars = char::decode_utf16(units);
+        match (chars.next(), chars.next()) {
+            (Some(Ok(c)), None) => Ok(c),
+            _ => Err(Error::from_js_conversion("string", "char")),
+        }
+    }
+}
+
+impl ToValue for OsString {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        os_string_to_value(self, mv8, "OsString")
+    }
+}
+
+impl FromValue for OsString {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+        os_string_from_value(value, mv8, "OsString")
+    }
+}
+
+impl ToValue for PathBuf {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        os_string_to_value(self.into_os_string(), mv8, "PathBuf")
+    }
+}
+
+impl FromValue for PathBuf {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+        os_string_from_value(value, mv8, "PathBuf").map(PathBuf::from)
+    }
+}
+
+// On Windows, OS strings are (potentially ill-formed) UTF-16 like JavaScript strings, so they are
+// converted losslessly. Elsewhere they are converted through UTF-8, and only if they are valid.
+#[cfg(windows)]
+fn os_string_to_value(value: OsString, mv8: &MiniV8, _from: &'static str) -> Result<Value> {
+    use std::os::windows::ffi::OsStrExt;
+    let units: Vec<u16> = value.encode_wide().collect();
+    Ok(Value::String(mv8.create_string_utf16(&units)))
+}
+
+#[cfg(not(windows))]
+fn os_string_to_value(value: OsString, mv8: &MiniV8, from: &'static str) -> Result<Value> {
+    match value.to_str() {
+        Some(string) => Ok(Value::String(mv8.create_string(string))),
+        None => Err(Error::ToJsConversionError { from, to: "string" }),
+    }
+}
+
+#[cfg(windows)]
+fn os_string_from_value(value: Value, mv8: &MiniV8, _to: &'static str) -> Result<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+    Ok(OsString::from_wide(&value.coerce_string(mv8)?.to_utf16()))
+}
+
+#[cfg(not(windows))]
+fn os_string_from_value(value: Value, mv8: &MiniV8, to: &'static str) -> Result<OsString> {
+    let string = value.coerce_string(mv8)?.to_rust_string();
+    string.map(OsString::from).map_err(|_| Error::from_js_conversion("string", to))
+}
+
+// The largest integer `n` such that `n` and `n + 1` are both exactly representable as numbers.
+// Larger numbers may have been rounded from another integer, so they are rejected as integers.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
 macro_rules! convert_number {
     ($prim_ty: ty) => {
         impl ToValue for $prim_ty {
@@ -262,40 +377,148 @@ macro_rules! convert_number {
     }
 }
 
-convert_number!(i8);
-convert_number!(u8);
-convert_number!(i16);
-convert_number!(u16);
-convert_number!(i32);
-convert_number!(u32);
-convert_number!(i64);
-convert_number!(u64);
-convert_number!(isize);
-convert_number!(usize);
+// Converting to an integer type is an error unless the number (after coercion) is an integer
+// within both the type's range and the range of safe integers, so that no value is silently
+// truncated, wrapped, saturated, or rounded. `Lossy` opts out of the check.
+macro_rules! convert_integer {
+    ($prim_ty: ty) => {
+        impl ToValue for $prim_ty {
+            fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
+                Ok(Value::Number(self as f64))
+            }
+        }
+
+        impl FromValue for $prim_ty {
+            fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+                let min = (<$prim_ty>::MIN as f64).max(-MAX_SAFE_INTEGER);
+                let max = (<$prim_ty>::MAX as f64).min(MAX_SAFE_INTEGER);
+                let number = value.coerce_number(mv8)?;
+                // The fractional part of NaN and the infinities is NaN:
+                if number.fract() != 0.0 || number < min || number > max {
+                    return Err(Error::from_js_conversion(value.type_name(), stringify!($prim_ty)));
+                }
+                Ok(number as $prim_ty)
+            }
+        }
+
+        impl FromValue for Lossy<$prim_ty> {
+            fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+                Ok(Lossy(value.coerce_number(mv8)? as $prim_ty))
+            }
+        }
+    }
+}
+
+impl<T: ToValue> ToValue for Lossy<T> {
+    fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+        self.0.to_value(mv8)
+    }
+}
+
+convert_integer!(i8);
+convert_integer!(u8);
+convert_integer!(i16);
+convert_integer!(u16);
+convert_integer!(i32);
+convert_integer!(u32);
+convert_integer!(i64);
+convert_integer!(u64);
+convert_integer!(isize);
+convert_integer!(usize);
 convert_number!(f32);
 convert_number!(f64);
 
+// The largest number of milliseconds from the epoch that a JavaScript `Date` can hold, in either
+// direction.
+const MAX_TIME: f64 = 8.64e15;
+
+// Clips a number of milliseconds from the epoch to the range of a JavaScript `Date`, as
+// JavaScript's `TimeClip` does: times outside of the range are invalid (NaN), and the rest are
+// truncated to whole milliseconds (with negative zero becoming zero).
+fn time_clip(millis: f64) -> f64 {
+    if millis.is_finite() && millis.abs() <= MAX_TIME { millis.trunc() + 0.0 } else { f64::NAN }
+}
+
+fn duration_millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0
+}
+
+pub(crate) fn date_from_system_time(time: SystemTime) -> Value {
+    let millis = match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => duration_millis(after),
+        Err(before) => -duration_millis(before.duration()),
+    };
+    Value::Date(time_clip(millis))
+}
+
+pub(crate) fn system_time_from_date(millis: f64) -> Option<SystemTime> {
+    if millis.is_nan() {
+        return None;
+    }
+    let duration = Duration::from_millis(millis.abs() as u64);
+    match millis < 0.0 {
+        true => UNIX_EPOCH.checked_sub(duration),
+        false => UNIX_EPOCH.checked_add(duration),
+    }
+}
+
+/// Converts to a `Date` that is the duration after the epoch.
 impl ToValue for Duration {
     fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
-        Ok(Value::Date((self.as_secs() as f64) + (self.as_nanos() as f64) / 1_000_000_000.0))
+        Ok(Value::Date(time_clip(duration_millis(self))))
     }
 }
 
+/// Converts from a `Date` to the duration since the epoch. Dates before the epoch and invalid
+/// dates can't be converted.
 impl FromValue for Duration {
     fn from_value(value: Value, _mv8: &MiniV8) -> Result<Duration> {
         match value {
-            Value::Date(timestamp) => {
-                let secs = timestamp / 1000.0;
-                let nanos = ((secs - secs.floor()) * 1_000_000.0).round() as u32;
-                Ok(Duration::new(secs as u64, nanos))
-            },
+            Value::Date(millis) if millis >= 0.0 => Ok(Duration::from_millis(millis as u64)),
             value => Err(Error::from_js_conversion(value.type_name(), "Duration")),
         }
     }
 }
 
+impl ToValue for Millis {
+    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
+        Ok(Value::Number(duration_millis(self.0)))
+    }
+}
+
+impl FromValue for Millis {
+    fn from_value(value: Value, _mv8: &MiniV8) -> Result<Millis> {
+        // Negative, NaN, infinite, and overly large numbers fail to convert to a `Duration`:
+        match value {
+            Value::Number(millis) => Duration::try_from_secs_f64(millis / 1000.0)
+                .map(Millis)
+                .map_err(|_| Error::from_js_conversion("number", "Millis")),
+            value => Err(Error::from_js_conversion(value.type_name(), "Millis")),
+        }
+    }
+}
+
+impl ToValue for SystemTime {
+    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
+        Ok(date_from_system_time(self))
+    }
+}
+
+/// Converts from a `Date`, which may be before the epoch. Invalid dates can't be converted.
+impl FromValue for SystemTime {
+    fn from_value(value: Value, _mv8: &MiniV8) -> Result<SystemTime> {
+        match value.as_system_time() {
+            Some(time) => Ok(time),
+            None => Err(Error::from_js_conversion(value.type_name(), "SystemTime")),
+        }
+    }
+}
+
 impl ToValues for Values {
-    fn to_values(self, _mv8: &MiniV8) -> Result<Values> {
+    fn to_values(self, mv8: &MiniV8) -> Result<Values> {
+        for value in self.iter() {
+            mv8.assert_owns(value)?;
+        }
         Ok(self)
     }
 }
@@ -413,6 +636,44 @@ macro_rules! impl_tuple {
     )
 }
 
+// Tuples convert to and from JavaScript arrays. Missing elements are read as `Value::Undefined`
+// and extra elements are ignored, as with function arguments.
+macro_rules! impl_tuple_value {
+    ($($name:ident),*) => (
+        impl<$($name),*> ToValue for ($($name,)*)
+        where
+            $($name: ToValue,)*
+        {
+            #[allow(non_snake_case)]
+            fn to_value(self, mv8: &MiniV8) -> Result<Value> {
+                let ($($name,)*) = self;
+                let array = mv8.create_array();
+                $(array.push($name)?;)*
+                Ok(Value::Array(array))
+            }
+        }
+
+        impl<$($name),*> FromValue for ($($name,)*)
+        where
+            $($name: FromValue,)*
+        {
+            #[allow(non_snake_case, unused_assignments)]
+            fn from_value(value: Value, mv8: &MiniV8) -> Result<Self> {
+                let array = match value {
+                    Value::Array(array) => array,
+                    value => return Err(Error::from_js_conversion(value.type_name(), "tuple")),
+                };
+                let mut index = 0;
+                Ok(($({
+                    let $name = array.get::<Value>(index)?;
+                    index += 1;
+                    FromValue::from_value($name, mv8)?
+                },)*))
+            }
+        }
+    )
+}
+
 impl_tuple!(A);
 impl_tuple!(A, B);
 impl_tuple!(A, B, C);
@@ -429,3 +690,44 @@ impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+impl_tuple_value!(A);
+impl_tuple_value!(A, B);
+impl_tuple_value!(A, B, C);
+impl_tuple_value!(A, B, C, D);
+impl_tuple_value!(A, B, C, D, E);
+impl_tuple_value!(A, B, C, D, E, F);
+impl_tuple_value!(A, B, C, D, E, F, G);
+impl_tuple_value!(A, B, C, D, E, F, G, H);
+
+macro_rules! impl_from_arg {
+    ($expected: expr, $accepts: pat, $($ty: ty),*) => {
+        $(
+            impl FromArg for $ty {
+                const EXPECTED: &'static str = $expected;
+
+                fn accepts_strict(value: &Value) -> bool {
+                    matches!(value, $accepts)
+                }
+            }
+        )*
+    }
+}
+
+impl_from_arg!("a string", Value::String(_), String, StdString);
+impl_from_arg!("a number", Value::Number(_), i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+impl_from_arg!("a number", Value::Number(_), f32, f64);
+impl_from_arg!("a boolean", Value::Boolean(_), bool);
+impl_from_arg!("a symbol", Value::Symbol(_), Symbol);
+impl_from_arg!("an array", Value::Array(_), Array);
+impl_from_arg!("a function", Value::Function(_), Function);
+impl_from_arg!("an object", Value::Object(_), Object);
+impl_from_arg!("any value", _, Value);
+
+impl<T: FromArg> FromArg for Option<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+
+    fn accepts_strict(value: &Value) -> bool {
+        value.is_undefined() || value.is_null() || T::accepts_strict(value)
+    }
+}
@@ -0,0 +1,299 @@
+use crate::*;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::ptr;
+use std::rc::Rc;
+use std::string::String as StdString;
+use v8::inspector::{
+    ChannelBase, ChannelImpl, StringBuffer, StringView, V8Inspector, V8InspectorClientBase,
+    V8InspectorClientImpl, V8InspectorClientTrustLevel, V8InspectorSession,
+};
+
+/// The granularity of the coverage collected after `MiniV8::start_coverage`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverageMode {
+    /// Count how many times each function is called.
+    PreciseCount,
+    /// Count how many times each block (e.g. each branch of an `if` statement) within each
+    /// function is executed, in addition to how many times each function is called.
+    BlockCount,
+}
+
+/// The coverage of a single script or module, as returned by `MiniV8::take_coverage`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptCoverage {
+    /// V8's identifier for the script.
+    pub script_id: StdString,
+    /// The name of the script's origin (see `ScriptOrigin`), or the module's specifier.
+    pub url: StdString,
+    /// The coverage of each function in the script, including the script's top level.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+/// The coverage of a single function. See `ScriptCoverage`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionCoverage {
+    /// The function's name, which is empty for anonymous functions and the script's top level.
+    pub function_name: StdString,
+    /// The ranges of the function's source code and their execution counts. The first range
+    /// covers the whole function. Any later ranges are nested within it, and take precedence over
+    /// the ranges that they are nested in.
+    pub ranges: Vec<CoverageRange>,
+    /// Whether the ranges include block coverage (see `CoverageMode::BlockCount`).
+    pub is_block_coverage: bool,
+}
+
+/// A range of source code and the number of times it has been executed. See `FunctionCoverage`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoverageRange {
+    /// The start of the range, as an offset into the script's source in UTF-16 code units.
+    pub start_offset: usize,
+    /// The (exclusive) end of the range, as an offset into the script's source in UTF-16 code
+    /// units.
+    pub end_offset: usize,
+    /// The number of times that the range has been executed.
+    pub count: u64,
+}
+
+impl ScriptCoverage {
+    /// Converts the coverage into the JSON format used by the DevTools protocol (a
+    /// `Profiler.ScriptCoverage` object), which is the format understood by common coverage tools
+    /// (e.g. `v8-to-istanbul` or `c8`).
+    pub fn to_json(&self) -> StdString {
+        let mut json = StdString::new();
+        write!(json, "{{\"scriptId\":{},\"url\":{},\"functions\":[", quote(&self.script_id),
+            quote(&self.url)).unwrap();
+        for (i, function) in self.functions.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(json, "{{\"functionName\":{},\"ranges\":[", quote(&function.function_name))
+                .unwrap();
+            for (j, range) in function.ranges.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                write!(json, "{{\"startOffset\":{},\"endOffset\":{},\"count\":{}}}",
+                    range.start_offset, range.end_offset, range.count).unwrap();
+            }
+            write!(json, "],\"isBlockCoverage\":{}}}", function.is_block_coverage).unwrap();
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn quote(value: &str) -> StdString {
+    let mut quoted = StdString::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl MiniV8 {
+    /// Starts collecting coverage of all JavaScript that runs from now on, including scripts and
+    /// modules that are evaluated later. If coverage is already being collected, it is restarted
+    /// with the given mode, discarding the coverage collected so far.
+    pub fn start_coverage(&self, mode: CoverageMode) {
+        self.stop_coverage();
+        let session = self.scope(|scope| CoverageSession::new(scope, mode));
+        *self.coverage().borrow_mut() = Some(session);
+    }
+
+    /// Returns the coverage collected since coverage was started (or since coverage was last
+    /// taken), resetting all execution counts to zero. Returns an empty list if coverage is not
+    /// being collected.
+    pub fn take_coverage(&self) -> Vec<ScriptCoverage> {
+        let coverage = self.coverage();
+        let mut coverage = coverage.borrow_mut();
+        let session = match *coverage {
+            Some(ref mut session) => session,
+            None => return Vec::new(),
+        };
+        let response = session.send("Profiler.takePreciseCoverage", "{}");
+        self.scope(|scope| parse_coverage(scope, &response)).unwrap_or_default()
+    }
+
+    /// Stops collecting coverage, releasing all of the engine's coverage bookkeeping. Does nothing
+    /// if coverage is not being collected.
+    pub fn stop_coverage(&self) {
+        let session = self.coverage().borrow_mut().take();
+        if let Some(mut session) = session {
+            session.send("Profiler.stopPreciseCoverage", "{}");
+            session.send("Profiler.disable", "{}");
+        }
+    }
+}
+
+// The coverage session (if any), stored in an isolate slot.
+pub(crate) type CoverageSlot = Rc<RefCell<Option<CoverageSession>>>;
+
+const CONTEXT_GROUP_ID: i32 = 1;
+
+// An inspector session that drives V8's precise coverage through the DevTools protocol, which is
+// the only way that V8 exposes it to embedders.
+pub(crate) struct CoverageSession {
+    // The session and the inspector must be dropped before the client and the channel that they
+    // point to, which are boxed so that they never move.
+    session: v8::UniqueRef<V8InspectorSession>,
+    _inspector: v8::UniqueRef<V8Inspector>,
+    channel: Box<Channel>,
+    _client: Box<Client>,
+    next_id: i32,
+}
+
+impl CoverageSession {
+    fn new(scope: &mut v8::HandleScope, mode: CoverageMode) -> CoverageSession {
+        let mut client = Box::new(Client { base: V8InspectorClientBase::new::<Client>() });
+        let mut channel = Box::new(Channel {
+            base: ChannelBase::new::<Channel>(),
+            response: None,
+        });
+        let mut inspector = V8Inspector::create(scope, &mut *client);
+        let context = scope.get_current_context();
+        let name = StringView::empty();
+        inspector.context_created(context, CONTEXT_GROUP_ID, name, StringView::empty());
+        let session = inspector.connect(
+            CONTEXT_GROUP_ID,
+            &mut *channel,
+            StringView::empty(),
+            V8InspectorClientTrustLevel::FullyTrusted,
+        );
+        let mut session = CoverageSession {
+            session,
+            _inspector: inspector,
+            channel,
+            _client: client,
+            next_id: 0,
+        };
+        session.send("Profiler.enable", "{}");
+        let detailed = mode == CoverageMode::BlockCount;
+        let params = format!("{{\"callCount\":true,\"detailed\":{}}}", detailed);
+        session.send("Profiler.startPreciseCoverage", &params);
+        session
+    }
+
+    // Sends a protocol message, returning the response. V8 handles these messages synchronously.
+    fn send(&mut self, method: &str, params: &str) -> StdString {
+        self.next_id += 1;
+        let message = format!("{{\"id\":{},\"method\":{},\"params\":{}}}", self.next_id,
+            quote(method), params);
+        self.session.dispatch_protocol_message(StringView::from(message.as_bytes()));
+        self.channel.response.take().unwrap_or_default()
+    }
+}
+
+fn parse_coverage(scope: &mut v8::HandleScope, response: &str) -> Option<Vec<ScriptCoverage>> {
+    let response = create_string(scope, response);
+    let response: v8::Local<v8::Object> = v8::json::parse(scope, response)?.try_into().ok()?;
+    let result: v8::Local<v8::Object> = get(scope, response, "result")?.try_into().ok()?;
+    let scripts: v8::Local<v8::Array> = get(scope, result, "result")?.try_into().ok()?;
+    let mut list = Vec::with_capacity(scripts.length() as usize);
+    for i in 0..scripts.length() {
+        let script: v8::Local<v8::Object> = scripts.get_index(scope, i)?.try_into().ok()?;
+        let functions: v8::Local<v8::Array> = get(scope, script, "functions")?.try_into().ok()?;
+        let mut function_list = Vec::with_capacity(functions.length() as usize);
+        for j in 0..functions.length() {
+            let function: v8::Local<v8::Object> = functions.get_index(scope, j)?.try_into().ok()?;
+            let ranges: v8::Local<v8::Array> = get(scope, function, "ranges")?.try_into().ok()?;
+            let mut range_list = Vec::with_capacity(ranges.length() as usize);
+            for k in 0..ranges.length() {
+                let range: v8::Local<v8::Object> = ranges.get_index(scope, k)?.try_into().ok()?;
+                range_list.push(CoverageRange {
+                    start_offset: get_number(scope, range, "startOffset")? as usize,
+                    end_offset: get_number(scope, range, "endOffset")? as usize,
+                    count: get_number(scope, range, "count")? as u64,
+                });
+            }
+            function_list.push(FunctionCoverage {
+                function_name: get_string(scope, function, "functionName")?,
+                ranges: range_list,
+                is_block_coverage: get(scope, function, "isBlockCoverage")?.boolean_value(scope),
+            });
+        }
+        list.push(ScriptCoverage {
+            script_id: get_string(scope, script, "scriptId")?,
+            url: get_string(scope, script, "url")?,
+            functions: function_list,
+        });
+    }
+    Some(list)
+}
+
+fn get<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+    key: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let key = create_string(scope, key);
+    object.get(scope, key.into())
+}
+
+fn get_number(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    key: &str,
+) -> Option<f64> {
+    get(scope, object, key)?.number_value(scope)
+}
+
+fn get_string(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    key: &str,
+) -> Option<StdString> {
+    Some(get(scope, object, key)?.to_rust_string_lossy(scope))
+}
+
+struct Client {
+    base: V8InspectorClientBase,
+}
+
+impl V8InspectorClientImpl for Client {
+    fn base(&self) -> &V8InspectorClientBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut V8InspectorClientBase {
+        &mut self.base
+    }
+
+    unsafe fn base_ptr(this: *const Self) -> *const V8InspectorClientBase {
+        ptr::addr_of!((*this).base)
+    }
+}
+
+struct Channel {
+    base: ChannelBase,
+    response: Option<StdString>,
+}
+
+impl ChannelImpl for Channel {
+    fn base(&self) -> &ChannelBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ChannelBase {
+        &mut self.base
+    }
+
+    unsafe fn base_ptr(this: *const Self) -> *const ChannelBase {
+        ptr::addr_of!((*this).base)
+    }
+
+    fn send_response(&mut self, _call_id: i32, mut message: v8::UniquePtr<StringBuffer>) {
+        self.response = message.as_mut().map(|m| m.string().to_string());
+    }
+
+    fn send_notification(&mut self, _message: v8::UniquePtr<StringBuffer>) {}
+
+    fn flush_protocol_notifications(&mut self) {}
+}
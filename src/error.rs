@@ -1,7 +1,11 @@
 use crate::*;
+use std::cell::RefCell;
 use std::error::Error as StdError;
+use std::ffi::c_void;
 use std::fmt;
+use std::rc::Rc;
 use std::result::Result as StdResult;
+use std::string::String as StdString;
 
 /// `std::result::Result` specialized for this crate's `Error` type.
 pub type Result<T> = StdResult<T, Error>;
@@ -23,53 +27,406 @@ pub enum Error {
         /// Name of the Rust type that could not be created.
         to: &'static str,
     },
+    /// An argument passed to a function created with `MiniV8::create_function_typed` could not be
+    /// converted to the type of its parameter.
+    InvalidArgument {
+        /// The (zero-based) position of the argument.
+        index: usize,
+        /// The name of the parameter, if it was given.
+        name: Option<StdString>,
+        /// A description of what the parameter accepts, e.g. "a string".
+        expected: &'static str,
+        /// Name of the JavaScript type of the argument.
+        found: &'static str,
+    },
+    /// A range of array indices was invalid or extended past the end of the array.
+    InvalidRange {
+        /// The start of the range.
+        start: u32,
+        /// The (exclusive) end of the range.
+        end: u32,
+        /// The length of the array at the time the range was checked.
+        len: u32,
+    },
+    /// Multiple property keys matched a key under a normalization (see `Object::get_normalized`).
+    AmbiguousKey {
+        /// The key that was looked up.
+        key: StdString,
+        /// The keys that all matched `key`, in property order.
+        matches: Vec<StdString>,
+    },
+    /// An operation of a patch applied with `Object::apply_patch` failed, and the patch was rolled
+    /// back.
+    PatchFailed {
+        /// The (zero-based) position of the operation within the patch.
+        index: usize,
+        /// The path (or the `from` path) of the operation that failed.
+        path: StdString,
+        /// Why the operation failed, e.g. "path does not exist".
+        reason: &'static str,
+    },
+    /// A path passed to `Value::pluck_path` was malformed.
+    InvalidPath {
+        /// The path.
+        path: StdString,
+        /// The byte offset within the path at which it is malformed.
+        offset: usize,
+    },
+    /// A value was used with a `MiniV8` other than the one that it belongs to (see
+    /// `MiniV8::assert_owns` and `MiniV8::transfer`).
+    ForeignValue {
+        /// The id of the context that the value was used with.
+        context: u64,
+        /// The id of the context that the value belongs to.
+        origin: u64,
+    },
     /// An evaluation timeout occurred.
     Timeout,
+    /// JavaScript execution was terminated with `InterruptHandle::terminate`.
+    Terminated,
+    /// JavaScript execution was terminated because the heap limit set with
+    /// `MiniV8Builder::max_heap_size` was reached.
+    OutOfMemory,
     /// A mutable callback has triggered JavaScript code that has called the same mutable callback
     /// again.
     ///
     /// This is an error because a mutable callback can only be borrowed mutably once.
     RecursiveMutCallback,
+    /// Too many Rust functions were being called at once, each from within JavaScript called by
+    /// the previous one (see `MiniV8::set_max_reentrancy_depth`).
+    ReentrancyLimit,
     /// An evaluation timeout was specified from within a Rust function embedded in V8.
     InvalidTimeout,
+    /// A promise, or a module's evaluation, was still pending after all pending jobs had been run,
+    /// e.g. because it awaits something that only Rust can provide (see `MiniV8::eval_async` and
+    /// `MiniV8::eval_module`).
+    NotSettled,
+    /// A `MiniV8` or a snapshot was to be created after V8 had been disposed (see `dispose`).
+    Disposed,
+    /// A snapshot passed to `MiniV8::from_snapshot` was produced by another V8 version (or with
+    /// other V8 flags), or is corrupt.
+    InvalidSnapshot,
+    /// `MiniV8::create_snapshot` failed, e.g. because its initialization script threw.
+    SnapshotFailed(StdString),
+    /// An event channel's `emit` function was called after the channel was closed (see
+    /// `MiniV8::create_event_channel`).
+    ChannelClosed,
+    /// An event channel's `emit` function was called while the channel's buffer was full, and
+    /// the channel's backpressure is `Backpressure::Error`.
+    ChannelFull,
+    /// A value passed to `MiniV8::transfer` has arrays or objects nested more than `limit` levels
+    /// deep.
+    DepthLimit {
+        /// The deepest nesting that is copied.
+        limit: usize,
+    },
+    /// A value could not be converted with `MiniV8::to_value_serde` or `MiniV8::from_value_serde`,
+    /// e.g. because it didn't have the shape that the Rust type expects.
+    Serde(StdString),
+    /// `IsolatePool::exec` was called from within a closure that the same pool is running, which
+    /// would wait on itself forever.
+    PoolReentrancy,
     /// A custom error that occurs during runtime.
     ///
-    /// This can be used for returning user-defined errors from callbacks.
-    ExternalError(Box<dyn StdError + 'static>),
-    /// An exception that occurred within the JavaScript environment.
+    /// This can be used for returning user-defined errors from callbacks. See `Error::external`
+    /// and `Error::downcast_ref`.
+    ExternalError(Box<dyn StdError + Send + Sync + 'static>),
+    /// An exception that occurred within the JavaScript environment. Use `Error::js_error` to read
+    /// the name, message, and stack trace of an error object.
     Value(Value),
 }
 
 impl Error {
+    /// Wraps a custom error (or anything that converts into a boxed error, such as a `String`) in
+    /// an `Error::ExternalError`.
+    pub fn external<E>(error: E) -> Error
+    where
+        E: Into<Box<dyn StdError + Send + Sync + 'static>>,
+    {
+        Error::ExternalError(error.into())
+    }
+
+    /// Returns a reference to the custom error held by an `Error::ExternalError` if it is of the
+    /// type `T`, or `None` otherwise.
+    pub fn downcast_ref<T: StdError + 'static>(&self) -> Option<&T> {
+        match self {
+            Error::ExternalError(error) => error.downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+
     /// Normalizes an error into a JavaScript value.
+    ///
+    /// Errors other than `Error::Value` become genuine JavaScript errors (instances of `Error`, or
+    /// of `TypeError` or `RangeError` where appropriate) with the error's message, and a stack
+    /// trace of the JavaScript that was running when the conversion happened. `Error::Value`
+    /// becomes the exception that it holds, so rethrowing it throws the very same value.
+    ///
+    /// The JavaScript error of an `Error::ExternalError` (or of an event channel's
+    /// `Error::ChannelClosed` or `Error::ChannelFull`) carries the original Rust error along: If
+    /// the error is thrown back into Rust (e.g. a callback's error propagates through JavaScript
+    /// to the Rust code that called it), the resulting error is the original error rather than an
+    /// `Error::Value`, so it can still be matched or downcast. The Rust error can only be
+    /// recovered once, and is dropped along with the JavaScript error if that is never thrown
+    /// back.
     pub fn to_value(self, mv8: &MiniV8) -> Value {
         match self {
-            Error::Value(value) => value,
-            Error::ToJsConversionError { .. } |
-            Error::FromJsConversionError { .. } => {
-                let object = mv8.create_object();
-                let _ = object.set("name", "TypeError");
-                let _ = object.set("message", self.to_string());
-                Value::Object(object)
+            Error::Value(value) => match mv8.assert_owns(&value) {
+                Ok(()) => value,
+                Err(error) => error.to_value(mv8),
             },
-            _ => {
-                let object = mv8.create_object();
-                let _ = object.set("name", "Error");
-                let _ = object.set("message", self.to_string());
+            Error::ExternalError(_) |
+            Error::ChannelClosed |
+            Error::ChannelFull => {
+                let object = create_error(mv8, v8::Exception::error, &self.to_string());
+                attach_external_error(mv8, &object, self);
                 Value::Object(object)
             },
+            Error::ToJsConversionError { .. } |
+            Error::FromJsConversionError { .. } |
+            Error::InvalidArgument { .. } |
+            Error::Serde(_) => {
+                Value::Object(create_error(mv8, v8::Exception::type_error, &self.to_string()))
+            },
+            Error::InvalidRange { .. } |
+            Error::ReentrancyLimit |
+            Error::DepthLimit { .. } => {
+                Value::Object(create_error(mv8, v8::Exception::range_error, &self.to_string()))
+            },
+            _ => Value::Object(create_error(mv8, v8::Exception::error, &self.to_string())),
         }
     }
 
+    /// Returns the name, message, and stack trace of the exception held by an `Error::Value`, or
+    /// `None` if this isn't an `Error::Value` or the exception isn't error-like (an object with a
+    /// string `message`, such as an instance of `Error`).
+    ///
+    /// The properties are read without running any JavaScript (see `Object::get_data_property`),
+    /// so this is safe to call on exceptions thrown by untrusted code. For the same reason, the
+    /// stack trace isn't read from the error's `stack` property (which is an accessor), but from
+    /// the frames that V8 captured when the error object was created. V8 only captures these if
+    /// the `MiniV8` was built with `MiniV8Builder::capture_stack_traces`, and otherwise the stack
+    /// trace is `None`.
+    pub fn js_error(&self) -> Option<JsError> {
+        let object = match self {
+            Error::Value(Value::Object(object)) => object,
+            _ => return None,
+        };
+        let string = |key| match object.get_data_property(key) {
+            Ok(Some(Value::String(s))) => Some(s.to_string()),
+            _ => None,
+        };
+        let message = string("message")?;
+        let name = string("name").unwrap_or_else(|| "Error".to_string());
+        let frames = stack_frames(object);
+        let stack = match frames.is_empty() {
+            true => None,
+            false => {
+                let mut stack = format!("{}: {}", name, message);
+                for frame in &frames {
+                    stack.push_str(&format!("\n    at {}", frame));
+                }
+                Some(stack)
+            },
+        };
+        Some(JsError { name, message, stack, frames, value: Value::Object(object.clone()) })
+    }
+
+    /// Returns where the exception held by an `Error::Value` was created, as V8 recorded it, or
+    /// `None` if this isn't an `Error::Value` or V8 recorded no location for it. Locations are
+    /// recorded for error objects (e.g. instances of `Error`, including syntax errors reported by
+    /// V8), but not for other thrown values (e.g. `throw "oops"`).
+    ///
+    /// The location is in terms of the script's origin, if it was given one (see `Script`).
+    pub fn location(&self, mv8: &MiniV8) -> Option<ErrorLocation> {
+        let value = match self {
+            Error::Value(value @ Value::Object(_)) => value,
+            _ => return None,
+        };
+        mv8.assert_owns(value).ok()?;
+        mv8.scope(|scope| {
+            let exception = value.to_v8_value(scope);
+            let message = v8::Exception::create_message(scope, exception);
+            let line = message.get_line_number(scope).filter(|&line| line > 0)?;
+            let resource_name = message.get_script_resource_name(scope)
+                .filter(|name| name.is_string())
+                .map(|name| name.to_rust_string_lossy(scope))
+                .unwrap_or_default();
+            Some(ErrorLocation { resource_name, line, column: message.get_start_column() })
+        })
+    }
+
     pub(crate) fn from_js_conversion(from: &'static str, to: &'static str) -> Error {
         Error::FromJsConversionError { from, to }
     }
 }
 
+/// The details of an error object thrown in JavaScript, as returned by `Error::js_error`.
+#[derive(Clone, Debug)]
+pub struct JsError {
+    /// The error's name, e.g. "TypeError".
+    pub name: StdString,
+    /// The error's message.
+    pub message: StdString,
+    /// The error's stack trace, if it has one, e.g. "TypeError: x is not a function\n    at ...",
+    /// formatted from `frames` the way V8 formats the error's `stack` property.
+    pub stack: Option<StdString>,
+    /// The frames of the error's stack trace, innermost first. Empty if V8 captured no stack
+    /// trace for the error (e.g. it isn't an instance of `Error`, or the `MiniV8` wasn't built
+    /// with `MiniV8Builder::capture_stack_traces`).
+    pub frames: Vec<JsStackFrame>,
+    /// The error object itself.
+    pub value: Value,
+}
+
+impl JsError {
+    /// Returns the first frame of the stack trace, e.g. "at f (script.js:1:7)", if there is one.
+    pub fn first_frame(&self) -> Option<&str> {
+        self.stack.as_deref()?.lines().map(str::trim).find(|line| line.starts_with("at "))
+    }
+}
+
+impl fmt::Display for JsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.name, self.message)?;
+        if let Some(frame) = self.first_frame() {
+            write!(fmt, " ({})", frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// A frame of an error's stack trace (see `JsError::frames`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsStackFrame {
+    /// The name of the function, or `None` for anonymous functions and top-level code.
+    pub function_name: Option<StdString>,
+    /// The name of the script's origin, or `None` if the script had no origin.
+    pub script_name: Option<StdString>,
+    /// The (one-based) line.
+    pub line: usize,
+    /// The (one-based) column.
+    pub column: usize,
+}
+
+impl fmt::Display for JsStackFrame {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let script_name = self.script_name.as_deref().unwrap_or("<anonymous>");
+        match self.function_name {
+            Some(ref function_name) => {
+                write!(fmt, "{} ({}:{}:{})", function_name, script_name, self.line, self.column)
+            },
+            None => write!(fmt, "{}:{}:{}", script_name, self.line, self.column),
+        }
+    }
+}
+
+/// Where an exception was created, as returned by `Error::location`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorLocation {
+    /// The name of the script's origin, or an empty string if the script had no origin.
+    pub resource_name: StdString,
+    /// The (one-based) line, offset by the origin's line offset.
+    pub line: usize,
+    /// The (zero-based) column within the line, offset by the origin's column offset on the
+    /// script's first line.
+    pub column: usize,
+}
+
+fn stack_frames(object: &Object) -> Vec<JsStackFrame> {
+    object.mv8.scope(|scope| {
+        let exception = v8::Local::new(scope, object.handle.clone()).into();
+        let trace = match v8::Exception::get_stack_trace(scope, exception) {
+            Some(trace) => trace,
+            None => return Vec::new(),
+        };
+        let name = |scope: &mut v8::HandleScope, name: Option<v8::Local<v8::String>>| {
+            name.map(|name| name.to_rust_string_lossy(scope)).filter(|name| !name.is_empty())
+        };
+        let mut frames = Vec::new();
+        for index in 0..trace.get_frame_count() {
+            if let Some(frame) = trace.get_frame(scope, index) {
+                let function_name = frame.get_function_name(scope);
+                let script_name = frame.get_script_name_or_source_url(scope);
+                frames.push(JsStackFrame {
+                    function_name: name(scope, function_name),
+                    script_name: name(scope, script_name),
+                    line: frame.get_line_number(),
+                    column: frame.get_column(),
+                });
+            }
+        }
+        frames
+    })
+}
+
 impl StdError for Error {
     fn description(&self) -> &'static str {
         "JavaScript execution error"
     }
+
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::ExternalError(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<dyn StdError + Send + Sync + 'static>> for Error {
+    fn from(error: Box<dyn StdError + Send + Sync + 'static>) -> Error {
+        Error::ExternalError(error)
+    }
+}
+
+// One of V8's error constructors, e.g. `v8::Exception::error`.
+type ErrorConstructor =
+    for<'s> fn(&mut v8::HandleScope<'s>, v8::Local<v8::String>) -> v8::Local<'s, v8::Value>;
+
+// Creates a JavaScript error with the given constructor and message.
+fn create_error(mv8: &MiniV8, constructor: ErrorConstructor, message: &str) -> Object {
+    mv8.scope(|scope| {
+        let message = create_string(scope, message);
+        let error = v8::Local::<v8::Object>::try_from(constructor(scope, message)).unwrap();
+        Object {
+            mv8: mv8.clone(),
+            handle: v8::Global::new(scope, error),
+        }
+    })
+}
+
+type ExternalCell = RefCell<Option<Error>>;
+
+// Stores an error on the object that it was converted into, under a private key, so that
+// `take_external_error` can recover it. The error is kept alive until the object is collected.
+fn attach_external_error(mv8: &MiniV8, object: &Object, error: Error) {
+    let cell: Rc<ExternalCell> = Rc::new(RefCell::new(Some(error)));
+    mv8.scope(|scope| {
+        let object = v8::Local::new(scope, object.handle.clone());
+        let key = external_error_key(scope);
+        let ext = v8::External::new(scope, Rc::as_ptr(&cell) as *mut c_void);
+        object.set_private(scope, key, ext.into());
+        add_finalizer(scope, object, move || drop(cell));
+    });
+}
+
+// Takes the error stored on a thrown object by `attach_external_error`, if there is one.
+pub(crate) fn take_external_error(
+    scope: &mut v8::HandleScope,
+    exception: v8::Local<v8::Value>,
+) -> Option<Error> {
+    let object = v8::Local::<v8::Object>::try_from(exception).ok()?;
+    let key = external_error_key(scope);
+    let ext = object.get_private(scope, key)?;
+    let ext = v8::Local::<v8::External>::try_from(ext).ok()?;
+    // The cell lives as long as the object does:
+    let cell = unsafe { &*(ext.value() as *const ExternalCell) };
+    cell.borrow_mut().take()
+}
+
+fn external_error_key<'s>(scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Private> {
+    let name = create_string(scope, "mini_v8::external_error");
+    v8::Private::for_api(scope, Some(name))
 }
 
 impl fmt::Display for Error {
@@ -81,11 +438,56 @@ impl fmt::Display for Error {
             Error::FromJsConversionError { from, to } => {
                 write!(fmt, "error converting JavaScript {} to {}", from, to)
             },
+            Error::InvalidArgument { index, name: Some(name), expected, found } => {
+                write!(fmt, "parameter {:?} (argument {}) must be {}, but {} was given", name,
+                    index + 1, expected, found)
+            },
+            Error::InvalidArgument { index, name: None, expected, found } => {
+                write!(fmt, "argument {} must be {}, but {} was given", index + 1, expected, found)
+            },
+            Error::InvalidRange { start, end, len } => {
+                write!(fmt, "range {}..{} is invalid for array of length {}", start, end, len)
+            },
+            Error::AmbiguousKey { key, matches } => {
+                write!(fmt, "key {:?} is ambiguous between keys {:?}", key, matches)
+            },
+            Error::PatchFailed { index, path, reason } => {
+                write!(fmt, "patch operation {} failed at path {:?}: {}", index, path, reason)
+            },
+            Error::InvalidPath { path, offset } => {
+                write!(fmt, "path {:?} is malformed at offset {}", path, offset)
+            },
+            Error::ForeignValue { context, origin } => {
+                write!(fmt, "value from context {} used in context {}", origin, context)
+            },
             Error::Timeout => write!(fmt, "evaluation timed out"),
+            Error::Terminated => write!(fmt, "execution terminated"),
+            Error::OutOfMemory => write!(fmt, "JavaScript heap limit reached"),
             Error::RecursiveMutCallback => write!(fmt, "mutable callback called recursively"),
+            Error::ReentrancyLimit => {
+                write!(fmt, "maximum Rust function re-entrancy depth exceeded")
+            },
             Error::InvalidTimeout => write!(fmt, "invalid request for evaluation timeout"),
+            Error::NotSettled => write!(fmt, "evaluation did not settle"),
+            Error::Disposed => write!(fmt, "cannot create a MiniV8 after V8 has been disposed"),
+            Error::InvalidSnapshot => {
+                write!(fmt, "snapshot was created by another version of V8 or is corrupt")
+            },
+            Error::DepthLimit { limit } => {
+                write!(fmt, "value is nested more than {} levels deep", limit)
+            },
+            Error::SnapshotFailed(reason) => write!(fmt, "failed to create snapshot: {}", reason),
+            Error::ChannelClosed => write!(fmt, "event channel is closed"),
+            Error::ChannelFull => write!(fmt, "event channel is full"),
+            Error::Serde(message) => write!(fmt, "serde conversion error: {}", message),
+            Error::PoolReentrancy => {
+                write!(fmt, "isolate pool called from within one of its own jobs")
+            },
             Error::ExternalError(ref err) => err.fmt(fmt),
-            Error::Value(v) => write!(fmt, "JavaScript runtime error ({})", v.type_name()),
+            Error::Value(v) => match self.js_error() {
+                Some(error) => write!(fmt, "JavaScript runtime error ({})", error),
+                None => write!(fmt, "JavaScript runtime error ({})", v.type_name()),
+            },
         }
     }
 }
@@ -0,0 +1,161 @@
+use crate::*;
+use futures_core::Stream;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// What an event channel's `emit` function does when the channel's buffer is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Discard the oldest buffered event to make room for the new one.
+    DropOldest,
+    /// Discard the new event, keeping the buffer as it is.
+    DropNewest,
+    /// Throw an exception from `emit`, keeping the buffer as it is.
+    Error,
+}
+
+/// The receiving end of an event channel, created with `MiniV8::create_event_channel`. This is a
+/// `Stream` of the events passed to the channel's `emit` function, in the order they were emitted.
+///
+/// The stream ends once the channel has been closed or the `emit` function has been garbage
+/// collected, and all buffered events have been received. Dropping the stream closes the channel.
+pub struct EventStream<T> {
+    channel: Rc<RefCell<Channel<T>>>,
+}
+
+impl<T> EventStream<T> {
+    /// Closes the channel, so that any further calls to its `emit` function throw an exception
+    /// (`Error::ChannelClosed`). Events that were already buffered can still be received, after
+    /// which the stream ends.
+    pub fn close(&mut self) {
+        let mut channel = self.channel.borrow_mut();
+        channel.closed = true;
+        channel.wake();
+    }
+}
+
+impl<T> Stream for EventStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<T>>> {
+        let mut channel = self.channel.borrow_mut();
+        if let Some(event) = channel.buffer.pop_front() {
+            Poll::Ready(Some(event))
+        } else if channel.closed || channel.emitter_dropped {
+            Poll::Ready(None)
+        } else {
+            channel.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for EventStream<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for EventStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<event stream>")
+    }
+}
+
+struct Channel<T> {
+    buffer: VecDeque<Result<T>>,
+    capacity: usize,
+    backpressure: Backpressure,
+    closed: bool,
+    emitter_dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl<T> Channel<T> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// Owned by the `emit` function's callback, so that the stream can tell when no more events can
+// arrive.
+struct Emitter<T> {
+    channel: Rc<RefCell<Channel<T>>>,
+}
+
+impl<T> Emitter<T> {
+    fn emit(&self, event: Result<T>) -> Result<()> {
+        let mut channel = self.channel.borrow_mut();
+        if channel.closed {
+            return Err(Error::ChannelClosed);
+        }
+        if channel.buffer.len() >= channel.capacity {
+            match channel.backpressure {
+                Backpressure::DropOldest => {
+                    channel.buffer.pop_front();
+                },
+                Backpressure::DropNewest => return Ok(()),
+                Backpressure::Error => return Err(Error::ChannelFull),
+            }
+        }
+        channel.buffer.push_back(event);
+        channel.wake();
+        Ok(())
+    }
+}
+
+impl<T> Drop for Emitter<T> {
+    fn drop(&mut self) {
+        let mut channel = self.channel.borrow_mut();
+        channel.emitter_dropped = true;
+        channel.wake();
+    }
+}
+
+impl MiniV8 {
+    /// Creates a channel for events emitted by JavaScript, returning the channel's `emit` function
+    /// and an `EventStream` of the emitted events.
+    ///
+    /// Each call to `emit(event)` converts `event` to `T` and buffers the result (a failed
+    /// conversion is received as an error from the stream). The buffer holds up to `capacity`
+    /// events, and `backpressure` determines what happens to events emitted while it is full. Once
+    /// the stream has been closed or dropped, `emit` throws an exception (see
+    /// `Error::ChannelClosed` and `Error::ChannelFull`).
+    ///
+    /// The stream is polled on the thread that owns this `MiniV8`, e.g. with a single-threaded
+    /// executor, and events are only emitted while JavaScript is running, so the stream makes
+    /// progress only as scripts are evaluated or functions are called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn create_event_channel<T>(
+        &self,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> (Function, EventStream<T>)
+    where
+        T: FromValue + 'static,
+    {
+        assert!(capacity > 0, "event channel capacity must be nonzero");
+        let channel = Rc::new(RefCell::new(Channel {
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
+            backpressure,
+            closed: false,
+            emitter_dropped: false,
+            waker: None,
+        }));
+        let emitter = Emitter { channel: channel.clone() };
+        let emit = self.create_function(move |inv| {
+            emitter.emit(inv.args.from(&inv.mv8, 0))
+        });
+        (emit, EventStream { channel })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+use std::any::Any;
+use std::ffi::c_void;
+
+impl MiniV8 {
+    /// Creates an empty JavaScript object that holds a Rust value, which can be recovered with
+    /// `Object::external` (e.g. from a callback's arguments) for as long as the object is alive.
+    /// This is how native resources (e.g. files or connections) are handed to scripts.
+    ///
+    /// The value is dropped exactly once: when the object is garbage collected, or when the
+    /// `MiniV8` is dropped if the object is still alive by then. Since there is no telling when
+    /// garbage collection will happen, resources that need to be released promptly should also be
+    /// released explicitly (e.g. by a `close` function).
+    pub fn create_external<T: 'static>(&self, value: T) -> Object {
+        let value: Box<Box<dyn Any>> = Box::new(Box::new(value));
+        let ptr = Box::into_raw(value);
+        self.scope(|scope| {
+            let object = v8::Object::new(scope);
+            let key = external_key(scope);
+            let ext = v8::External::new(scope, ptr as *mut c_void);
+            object.set_private(scope, key, ext.into());
+            add_finalizer(scope, object, move || drop(unsafe { Box::from_raw(ptr) }));
+            Object {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, object),
+            }
+        })
+    }
+}
+
+impl Object {
+    /// Returns the Rust value held by the object if it was created with
+    /// `MiniV8::create_external` with a value of type `T`, or `None` otherwise.
+    ///
+    /// Only shared references are handed out, so values that need to be mutated should use
+    /// interior mutability (e.g. `RefCell`).
+    pub fn external<T: 'static>(&self) -> Option<&T> {
+        let ptr = self.mv8.scope(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let key = external_key(scope);
+            let ext = object.get_private(scope, key)?;
+            let ext = v8::Local::<v8::External>::try_from(ext).ok()?;
+            Some(ext.value() as *const Box<dyn Any>)
+        })?;
+        // The value lives as long as the object does, which this `Object` keeps alive:
+        let value = unsafe { &*ptr };
+        value.downcast_ref()
+    }
+}
+
+fn external_key<'s>(scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Private> {
+    let name = create_string(scope, "mini_v8::external");
+    v8::Private::for_api(scope, Some(name))
+}
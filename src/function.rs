@@ -1,5 +1,10 @@
 use crate::*;
+use std::ffi::c_void;
 use std::fmt;
+use std::mem;
+use std::panic;
+use std::process;
+use std::string::String as StdString;
 
 #[derive(Clone)]
 pub struct Function {
@@ -39,6 +44,11 @@ impl Function {
         let args = args.to_values(&self.mv8)?;
         self.mv8.try_catch(|scope| {
             let function = v8::Local::new(scope, self.handle.clone());
+            #[cfg(feature = "tracing")]
+            let _span = {
+                let name = function.get_name(scope).to_rust_string_lossy(scope);
+                tracing::debug_span!("call", function = name.as_str()).entered()
+            };
             let this = this.to_v8_value(scope);
             let args = args.into_vec();
             let args_v8: Vec<_> = args.into_iter().map(|v| v.to_v8_value(scope)).collect();
@@ -57,6 +67,11 @@ impl Function {
         let args = args.to_values(&self.mv8)?;
         self.mv8.try_catch(|scope| {
             let function = v8::Local::new(scope, self.handle.clone());
+            #[cfg(feature = "tracing")]
+            let _span = {
+                let name = function.get_name(scope).to_rust_string_lossy(scope);
+                tracing::debug_span!("call_new", function = name.as_str()).entered()
+            };
             let args = args.into_vec();
             let args_v8: Vec<_> = args.into_iter().map(|v| v.to_v8_value(scope)).collect();
             let result = function.new_instance(scope, &args_v8);
@@ -64,6 +79,70 @@ impl Function {
             Ok(Value::from_v8_value(&self.mv8, scope, result.unwrap().into()))
         }).and_then(|v| v.into(&self.mv8))
     }
+
+    /// Returns the name that the function was defined with (or inferred from, e.g. the variable
+    /// it was assigned to), which is empty for anonymous functions. This doesn't read the `name`
+    /// property, so it isn't affected by redefining it.
+    pub fn name(&self) -> StdString {
+        self.mv8.scope(|scope| {
+            let function = v8::Local::new(scope, self.handle.clone());
+            function.get_name(scope).to_rust_string_lossy(scope)
+        })
+    }
+
+    /// Returns the number of parameters that the function expects, i.e. its `length` property.
+    ///
+    /// V8 doesn't expose arity directly, so this reads the `length` data property without running
+    /// any JavaScript: Getters are never called, and proxy traps are never run. Returns 0 if the
+    /// property isn't a data property holding a non-negative integer.
+    pub fn arity(&self) -> u32 {
+        self.mv8.scope(|scope| {
+            let function = v8::Local::new(scope, self.handle.clone());
+            let key = create_string(scope, "length").into();
+            match lookup_data_property(scope, function.into(), key) {
+                Some((PropertyKind::Data, Some(length))) if length.is_number() => {
+                    let length = length.number_value(scope).unwrap();
+                    if length.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&length) {
+                        length as u32
+                    } else {
+                        0
+                    }
+                },
+                _ => 0,
+            }
+        })
+    }
+
+    /// Returns the source code of the function as it appears in its script, like
+    /// `Function.prototype.toString` does, or `None` if the function wasn't defined by a script
+    /// (e.g. built-in functions, bound functions, and functions created with
+    /// `MiniV8::create_function`).
+    pub fn source(&self) -> Option<StdString> {
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.scope(|scope| {
+            let function = v8::Local::new(scope, self.handle.clone());
+            function.get_script_line_number()?;
+            let to_string = v8::Local::new(scope, intrinsics.function_to_string.clone());
+            let source = to_string.call(scope, function.into(), &[])?;
+            Some(source.to_rust_string_lossy(scope))
+        })
+    }
+
+    /// Returns the resource name of the script that defined the function (which is empty if the
+    /// script was evaluated without one) and the (one-based) line number of the function's
+    /// definition within it, or `None` if the function wasn't defined by a script (see
+    /// `Function::source`).
+    pub fn script_origin(&self) -> Option<(StdString, u32)> {
+        self.mv8.scope(|scope| {
+            let function = v8::Local::new(scope, self.handle.clone());
+            let line = function.get_script_line_number()?;
+            let resource_name = function.get_script_origin().resource_name()
+                .filter(|name| name.is_string())
+                .map(|name| name.to_rust_string_lossy(scope))
+                .unwrap_or_default();
+            Some((resource_name, line + 1))
+        })
+    }
 }
 
 impl fmt::Debug for Function {
@@ -81,4 +160,343 @@ pub struct Invocation {
     pub this: Value,
     /// The list of arguments with which the function was called.
     pub args: Values,
+    /// The value of `new.target`, which is the constructor that `new` was applied to if the
+    /// function was called as a constructor (e.g. with `new` or `Function::call_new`), and
+    /// `Value::Undefined` otherwise. When the function is called as a constructor, `this` is the
+    /// newly created object, which is the result of the construction unless the function returns
+    /// another object.
+    pub new_target: Value,
+}
+
+impl Invocation {
+    /// Returns `true` if the function was called as a constructor. See `Invocation::new_target`.
+    pub fn is_construct_call(&self) -> bool {
+        !self.new_target.is_undefined()
+    }
+}
+
+/// The fields of the objects returned by a function created with `MiniV8::create_function_shaped`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectShape {
+    fields: Vec<StdString>,
+}
+
+impl ObjectShape {
+    /// Creates a shape with the given field names, in order.
+    pub fn new(fields: &[&str]) -> ObjectShape {
+        ObjectShape { fields: fields.iter().map(|field| field.to_string()).collect() }
+    }
+
+    /// Returns the shape's field names.
+    pub fn fields(&self) -> &[StdString] {
+        &self.fields
+    }
+}
+
+/// The result object that a function created with `MiniV8::create_function_shaped` is given to
+/// fill in. The object is only accessible for the duration of the call, which spares it a Rust
+/// handle.
+pub struct ShapedObject<'s> {
+    mv8: MiniV8,
+    object: v8::Local<'s, v8::Object>,
+}
+
+impl<'s> ShapedObject<'s> {
+    /// Inserts a key-value pair into the object, as with `Object::set`.
+    pub fn set<K: ToValue, V: ToValue>(&self, key: K, value: V) -> Result<()> {
+        let key = key.to_value(&self.mv8)?;
+        let value = value.to_value(&self.mv8)?;
+        self.mv8.try_catch(|scope| {
+            let key = key.to_v8_value(scope);
+            let value = value.to_v8_value(scope);
+            self.object.set(scope, key, value);
+            self.mv8.exception(scope)
+        })
+    }
+}
+
+/// How a function created with `MiniV8::create_function_typed` converts its arguments to the types
+/// of its parameters.
+///
+/// In both modes, missing arguments are `undefined` and extra arguments are ignored. The modes
+/// convert arguments as follows:
+///
+/// | Parameter type                  | `Strict` accepts            | `Lenient` accepts           |
+/// |---------------------------------|-----------------------------|-----------------------------|
+/// | `String`, `std::string::String` | strings                     | anything (`coerce_string`)  |
+/// | number types (`i32`, ...)       | numbers                     | anything (`coerce_number`)  |
+/// | `bool`                          | booleans                    | anything (`coerce_boolean`) |
+/// | `Symbol`                        | symbols                     | symbols                     |
+/// | `Array`                         | arrays                      | arrays                      |
+/// | `Function`                      | functions                   | functions                   |
+/// | `Object`                        | other objects               | other objects               |
+/// | `Option<T>`                     | `undefined`, `null`, as `T` | `undefined`, `null`, as `T` |
+/// | `Value`                         | anything                    | anything                    |
+///
+/// The `coerce_*` conversions are those of `Value`, which follow JavaScript's own coercions
+/// (e.g. `String(value)`). The number types convert as with `FromValue` once accepted, e.g.
+/// truncating towards zero for integers. An argument that isn't accepted causes
+/// `Error::InvalidArgument`, which is thrown as a `TypeError` naming the parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoercionMode {
+    /// Arguments must already have the JavaScript type that corresponds to their parameter's type.
+    Strict,
+    /// Arguments are coerced to their parameter's type where JavaScript allows it, like the
+    /// arguments of web APIs.
+    Lenient,
+}
+
+/// Trait for types that can be parameters of a function created with
+/// `MiniV8::create_function_typed`. See `CoercionMode`.
+pub trait FromArg: FromValue {
+    /// A description of the values that the parameter accepts in `CoercionMode::Strict`, e.g.
+    /// "a string".
+    const EXPECTED: &'static str;
+
+    /// Returns whether the parameter accepts `value` in `CoercionMode::Strict`.
+    fn accepts_strict(value: &Value) -> bool;
+}
+
+/// Trait for the parameter lists of functions created with `MiniV8::create_function_typed`,
+/// implemented for tuples of `FromArg` types.
+pub trait FromArgs: Sized {
+    /// Converts the arguments, naming parameters in errors after `params` (by position).
+    fn from_args(args: Values, mv8: &MiniV8, mode: CoercionMode, params: &[StdString])
+        -> Result<Self>;
+}
+
+fn from_arg<T: FromArg>(
+    value: Value,
+    mv8: &MiniV8,
+    mode: CoercionMode,
+    index: usize,
+    params: &[StdString],
+) -> Result<T> {
+    let found = value.type_name();
+    let invalid = || Error::InvalidArgument {
+        index,
+        name: params.get(index).cloned(),
+        expected: T::EXPECTED,
+        found,
+    };
+    if mode == CoercionMode::Strict && !T::accepts_strict(&value) {
+        return Err(invalid());
+    }
+    T::from_value(value, mv8).map_err(|e| match e {
+        Error::FromJsConversionError { .. } => invalid(),
+        e => e,
+    })
+}
+
+macro_rules! impl_from_args {
+    ($($name:ident),*) => (
+        impl<$($name),*> FromArgs for ($($name,)*)
+        where
+            $($name: FromArg,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn from_args(
+                args: Values,
+                mv8: &MiniV8,
+                mode: CoercionMode,
+                params: &[StdString],
+            ) -> Result<Self> {
+                let mut iter = args.into_vec().into_iter();
+                let mut index = 0;
+                Ok(($({
+                    let $name = ();
+                    let value = iter.next().unwrap_or(Value::Undefined);
+                    index += 1;
+                    from_arg(value, mv8, mode, index - 1, params)?
+                },)*))
+            }
+        }
+    );
+}
+
+impl_from_args!();
+impl_from_args!(A);
+impl_from_args!(A, B);
+impl_from_args!(A, B, C);
+impl_from_args!(A, B, C, D);
+impl_from_args!(A, B, C, D, E);
+impl_from_args!(A, B, C, D, E, F);
+impl_from_args!(A, B, C, D, E, F, G);
+impl_from_args!(A, B, C, D, E, F, G, H);
+
+impl MiniV8 {
+    /// Wraps a Rust function or closure that takes typed parameters, creating a callable
+    /// JavaScript function handle to it. The function's arguments are converted to the types of
+    /// its parameters according to `mode` (see `CoercionMode`), and `params` names the parameters
+    /// (in order) for the errors thrown when that fails.
+    ///
+    /// See `MiniV8::create_function` for how the function's return value is handled.
+    #[track_caller]
+    pub fn create_function_typed<A, F, R>(&self, mode: CoercionMode, params: &[&str], func: F)
+        -> Function
+    where
+        A: FromArgs,
+        F: Fn(&MiniV8, A) -> Result<R> + 'static,
+        R: ToValue,
+    {
+        let params: Vec<StdString> = params.iter().map(|param| param.to_string()).collect();
+        self.create_function(move |inv| {
+            let args = A::from_args(inv.args, &inv.mv8, mode, &params)?;
+            func(&inv.mv8, args)
+        })
+    }
+
+    /// Wraps a Rust function or closure that fills in a result object of a fixed shape, creating a
+    /// callable JavaScript function handle to it. On every call, the function is given a new
+    /// object with the shape's fields (all initially `undefined`) to set, and that object is what
+    /// the JavaScript function returns.
+    ///
+    /// The objects are instantiated from a template that is created once per function, which gives
+    /// all of them the same hidden class, and are handed to JavaScript without ever being given a
+    /// Rust handle. Result objects are never reused between calls, since there's no telling
+    /// whether JavaScript has kept a reference to an earlier one.
+    ///
+    /// If the function returns an error, the error is thrown and the object is discarded.
+    #[track_caller]
+    pub fn create_function_shaped<F>(&self, shape: &ObjectShape, func: F) -> Function
+    where
+        F: Fn(Invocation, &ShapedObject) -> Result<()> + 'static,
+    {
+        let template = self.scope(|scope| {
+            let template = v8::ObjectTemplate::new(scope);
+            let undefined = v8::undefined(scope);
+            for field in &shape.fields {
+                let key = create_string(scope, field);
+                template.set(key.into(), undefined.into());
+            }
+            v8::Global::new(scope, template)
+        });
+        self.create_function_raw(move |inv, scope| {
+            let template = v8::Local::new(scope, template.clone());
+            let object = template.new_instance(scope).unwrap();
+            let result = ShapedObject { mv8: inv.mv8.clone(), object };
+            func(inv, &result)?;
+            Ok(object.into())
+        })
+    }
+
+    /// Wraps a plain Rust function, creating a callable JavaScript function handle to it. This is
+    /// a cheaper alternative to `MiniV8::create_function` for small functions that are called very
+    /// often: The function pointer is stored in the JavaScript function itself, so creating the
+    /// function allocates nothing on the Rust side, and calling it constructs no `Invocation`.
+    ///
+    /// The function is called with the arguments (`this` is not available), and its return value
+    /// is handled as with `MiniV8::create_function`.
+    pub fn create_function_fast(&self, func: fn(&MiniV8, &[Value]) -> Result<Value>) -> Function {
+        self.scope(|scope| {
+            let ext = v8::External::new(scope, func as *mut c_void);
+            let function = v8::Function::builder(fast_callback).data(ext.into()).build(scope);
+            Function {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, function.unwrap()),
+            }
+        })
+    }
+
+    /// Wraps a plain Rust function of two numbers, creating a callable JavaScript function handle
+    /// to it. Both arguments are converted to numbers as with JavaScript's `Number(value)`
+    /// (missing arguments becoming `NaN`), and extra arguments are ignored.
+    ///
+    /// Where V8's optimizing compiler can tell that a call passes two numbers, it calls the
+    /// function directly from the optimized code, without any of the overhead of a Rust callback.
+    /// All other calls (e.g. from code that isn't optimized yet, or with arguments that need to be
+    /// converted) automatically take the ordinary path instead, with the same results.
+    ///
+    /// If the provided function panics, the executable will be aborted.
+    pub fn create_function_fast_f64(&self, func: fn(f64, f64) -> f64) -> Function {
+        const FAST_F64: v8::fast_api::FastFunction = v8::fast_api::FastFunction::new(
+            &[
+                v8::fast_api::Type::V8Value,
+                v8::fast_api::Type::Float64,
+                v8::fast_api::Type::Float64,
+                v8::fast_api::Type::CallbackOptions,
+            ],
+            v8::fast_api::CType::Float64,
+            fast_f64 as _,
+        );
+
+        self.scope(|scope| {
+            let ext = v8::External::new(scope, func as *mut c_void);
+            let template = v8::FunctionTemplate::builder(slow_f64)
+                .data(ext.into())
+                .length(2)
+                .build_fast(scope, &FAST_F64, None, None, None);
+            let function = template.get_function(scope);
+            Function {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, function.unwrap()),
+            }
+        })
+    }
+}
+
+type FastCallback = fn(&MiniV8, &[Value]) -> Result<Value>;
+
+fn fast_callback(
+    scope: &mut v8::HandleScope,
+    fca: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let ext = v8::Local::<v8::External>::try_from(fca.data()).unwrap();
+    let func: FastCallback = unsafe { mem::transmute(ext.value()) };
+    let mv8 = MiniV8::from_callback_scope(scope);
+    mv8.enter_callback(scope, |scope| {
+        let args: Vec<Value> = (0..fca.length())
+            .map(|i| Value::from_v8_value(&mv8, scope, fca.get(i)))
+            .collect();
+        let result = mv8.check_reentrancy()
+            .and_then(|_| func(&mv8, &args))
+            .and_then(|value| value.to_value(&mv8));
+        match result {
+            Ok(v) => rv.set(v.to_v8_value(scope)),
+            Err(e) => {
+                let exception = e.to_value(&mv8).to_v8_value(scope);
+                scope.throw_exception(exception);
+            },
+        }
+    });
+}
+
+// The fast path of `MiniV8::create_function_fast_f64`, called directly by optimized code.
+unsafe extern "C" fn fast_f64(
+    _receiver: v8::Local<v8::Object>,
+    a: f64,
+    b: f64,
+    options: *mut v8::fast_api::FastApiCallbackOptions,
+) -> f64 {
+    let options = &mut *options;
+    if !options.data.data.is_external() {
+        options.fallback = true;
+        return 0.0;
+    }
+    let ext = v8::Local::<v8::External>::cast(options.data.data);
+    let func: fn(f64, f64) -> f64 = mem::transmute(ext.value());
+    // Unwinding into the optimized code that called this isn't possible:
+    panic::catch_unwind(|| func(a, b)).unwrap_or_else(|_| process::abort())
+}
+
+// The slow path of `MiniV8::create_function_fast_f64`.
+fn slow_f64(
+    scope: &mut v8::HandleScope,
+    fca: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let ext = v8::Local::<v8::External>::try_from(fca.data()).unwrap();
+    let func: fn(f64, f64) -> f64 = unsafe { mem::transmute(ext.value()) };
+    // Converting an argument can throw (e.g. from a `valueOf` method), in which case the
+    // exception is left to propagate:
+    let a = match fca.get(0).number_value(scope) {
+        Some(a) => a,
+        None => return,
+    };
+    let b = match fca.get(1).number_value(scope) {
+        Some(b) => b,
+        None => return,
+    };
+    rv.set(v8::Number::new(scope, func(a, b)).into());
 }
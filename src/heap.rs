@@ -0,0 +1,57 @@
+use crate::*;
+
+/// Statistics about the memory used by a `MiniV8`'s isolate, as returned by
+/// `MiniV8::heap_statistics`. All sizes are in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapStatistics {
+    /// The total size of the JavaScript heap, including memory that is reserved but not in use.
+    pub total_heap_size: usize,
+    /// The size of the JavaScript heap that is in use by live (or not yet collected) objects.
+    pub used_heap_size: usize,
+    /// The maximum size that the JavaScript heap may grow to (see `MiniV8Builder::max_heap_size`).
+    pub heap_size_limit: usize,
+    /// The amount of memory outside of the JavaScript heap that is kept alive by JavaScript
+    /// objects, such as the contents of array buffers, and memory reported with
+    /// `MiniV8::adjust_external_memory`.
+    pub external_memory: usize,
+    /// The amount of memory currently allocated with `malloc` by V8 itself.
+    pub malloced_memory: usize,
+    /// The number of JavaScript contexts that are alive within the isolate.
+    pub number_of_native_contexts: usize,
+}
+
+impl MiniV8 {
+    /// Returns statistics about the memory used by this `MiniV8`'s isolate.
+    pub fn heap_statistics(&self) -> HeapStatistics {
+        self.scope(|scope| {
+            let mut stats = v8::HeapStatistics::default();
+            scope.get_heap_statistics(&mut stats);
+            HeapStatistics {
+                total_heap_size: stats.total_heap_size(),
+                used_heap_size: stats.used_heap_size(),
+                heap_size_limit: stats.heap_size_limit(),
+                external_memory: stats.external_memory(),
+                malloced_memory: stats.malloced_memory(),
+                number_of_native_contexts: stats.number_of_native_contexts(),
+            }
+        })
+    }
+
+    /// Tells V8 that the system is low on memory, which makes it run a full garbage collection
+    /// (and release as much memory as it can) before returning.
+    pub fn low_memory_notification(&self) {
+        self.scope(|scope| scope.low_memory_notification());
+    }
+
+    /// Reports that `delta` bytes of memory outside of the JavaScript heap have been allocated (or
+    /// freed, if negative) on behalf of JavaScript objects, e.g. large Rust buffers that are only
+    /// released once a JavaScript object holding them is collected. V8 takes this memory into
+    /// account when deciding to collect garbage, so that such objects don't pile up just because
+    /// they look small from within JavaScript.
+    ///
+    /// Every allocation that is reported must eventually be matched by a negative adjustment of
+    /// the same size. Returns the total amount of external memory now reported.
+    pub fn adjust_external_memory(&self, delta: i64) -> i64 {
+        self.scope(|scope| scope.adjust_amount_of_external_allocated_memory(delta))
+    }
+}
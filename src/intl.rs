@@ -0,0 +1,152 @@
+use crate::*;
+use crate::lru::Lru;
+use std::cell::RefCell;
+use std::string::String as StdString;
+
+// The number of formatters that are cached per `MiniV8`.
+const CAPACITY: usize = 32;
+
+/// Options for `MiniV8::format_number`, corresponding to the options of JavaScript's
+/// `Intl.NumberFormat`. Options that are `None` are left to their defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NumberFormatOptions {
+    /// The formatting style: `"decimal"`, `"percent"`, `"currency"`, or `"unit"`.
+    pub style: Option<StdString>,
+    /// The ISO 4217 currency code to use with the `"currency"` style, e.g. `"EUR"`.
+    pub currency: Option<StdString>,
+    /// The unit to use with the `"unit"` style, e.g. `"kilometer-per-hour"`.
+    pub unit: Option<StdString>,
+    /// The minimum number of fraction digits to use.
+    pub minimum_fraction_digits: Option<u32>,
+    /// The maximum number of fraction digits to use.
+    pub maximum_fraction_digits: Option<u32>,
+    /// Whether to use grouping separators, e.g. thousands separators.
+    pub use_grouping: Option<bool>,
+}
+
+/// Options for `MiniV8::format_date`, corresponding to the options of JavaScript's
+/// `Intl.DateTimeFormat`. Options that are `None` are left to their defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DateFormatOptions {
+    /// The style of the date part: `"full"`, `"long"`, `"medium"`, or `"short"`.
+    pub date_style: Option<StdString>,
+    /// The style of the time part: `"full"`, `"long"`, `"medium"`, or `"short"`.
+    pub time_style: Option<StdString>,
+    /// The IANA time zone to use, e.g. `"UTC"` or `"Europe/Berlin"`. Defaults to the local time
+    /// zone.
+    pub time_zone: Option<StdString>,
+    /// Whether to use 12-hour time (as opposed to 24-hour time).
+    pub hour12: Option<bool>,
+}
+
+impl MiniV8 {
+    /// Formats a number for the given locale (a BCP 47 language tag, e.g. `"de-DE"`), exactly as
+    /// `new Intl.NumberFormat(locale, options).format(value)` would in JavaScript.
+    ///
+    /// Formatters are cached by locale and options, so repeatedly formatting with the same locale
+    /// and options is cheap. Returns an error (V8's `RangeError`) if the locale or any of the
+    /// options are invalid.
+    pub fn format_number(
+        &self,
+        value: f64,
+        locale: &str,
+        options: &NumberFormatOptions,
+    ) -> Result<StdString> {
+        let key = format!("number:{}:{:?}", locale, options);
+        let format = self.formatter(key, locale, |mv8| {
+            let object = mv8.create_object();
+            set_option(&object, "style", &options.style)?;
+            set_option(&object, "currency", &options.currency)?;
+            set_option(&object, "unit", &options.unit)?;
+            set_option(&object, "minimumFractionDigits", &options.minimum_fraction_digits)?;
+            set_option(&object, "maximumFractionDigits", &options.maximum_fraction_digits)?;
+            set_option(&object, "useGrouping", &options.use_grouping)?;
+            let intrinsics = mv8.intrinsics();
+            Ok((intrinsics.number_format.clone(), intrinsics.number_format_format.clone(), object))
+        })?;
+        format.call((value,))
+    }
+
+    /// Formats a date for the given locale (a BCP 47 language tag, e.g. `"en-US"`), exactly as
+    /// `new Intl.DateTimeFormat(locale, options).format(value)` would in JavaScript. `value` is
+    /// typically a `Value::Date`, but can be anything that JavaScript converts to a date (e.g. a
+    /// number of milliseconds since the Unix epoch).
+    ///
+    /// Formatters are cached by locale and options, so repeatedly formatting with the same locale
+    /// and options is cheap. Returns an error (V8's `RangeError`) if the locale, any of the
+    /// options, or the date are invalid.
+    pub fn format_date(
+        &self,
+        value: Value,
+        locale: &str,
+        options: &DateFormatOptions,
+    ) -> Result<StdString> {
+        let key = format!("date:{}:{:?}", locale, options);
+        let format = self.formatter(key, locale, |mv8| {
+            let object = mv8.create_object();
+            set_option(&object, "dateStyle", &options.date_style)?;
+            set_option(&object, "timeStyle", &options.time_style)?;
+            set_option(&object, "timeZone", &options.time_zone)?;
+            set_option(&object, "hour12", &options.hour12)?;
+            let intrinsics = mv8.intrinsics();
+            let getter = intrinsics.date_time_format_format.clone();
+            Ok((intrinsics.date_time_format.clone(), getter, object))
+        })?;
+        format.call((value,))
+    }
+
+    // Returns the cached `format` function of the formatter with the given key, first creating
+    // the formatter with the constructor, `format` getter and options returned by `init` if it
+    // isn't cached yet. The constructor and getter are captured intrinsics, so scripts that
+    // tamper with `Intl` can't affect the result.
+    fn formatter<F>(&self, key: StdString, locale: &str, init: F) -> Result<Function>
+    where
+        F: FnOnce(&MiniV8) -> Result<(v8::Global<v8::Function>, v8::Global<v8::Function>, Object)>,
+    {
+        let cached = self.formatters(|formatters| formatters.0.borrow_mut().get(&key));
+        let handle = match cached {
+            Some(handle) => handle,
+            None => {
+                let (constructor, getter, options) = init(self)?;
+                let handle = self.try_catch(|scope| -> Result<_> {
+                    let constructor = v8::Local::new(scope, constructor);
+                    let locale = create_string(scope, locale);
+                    let options = v8::Local::new(scope, options.handle.clone());
+                    let args = [locale.into(), options.into()];
+                    let formatter = constructor.new_instance(scope, &args);
+                    self.exception(scope)?;
+                    // The `format` getter returns a function that is bound to the formatter:
+                    let getter = v8::Local::new(scope, getter);
+                    let format = getter.call(scope, formatter.unwrap().into(), &[]);
+                    self.exception(scope)?;
+                    let format = format.unwrap();
+                    let format: v8::Local<v8::Function> = format.try_into().map_err(|_| {
+                        let from = Value::from_v8_value(self, scope, format).type_name();
+                        Error::from_js_conversion(from, "Function")
+                    })?;
+                    Ok(v8::Global::new(scope, format))
+                })?;
+                self.formatters(|formatters| formatters.0.borrow_mut().insert(key, handle.clone()));
+                handle
+            },
+        };
+        Ok(Function { mv8: self.clone(), handle })
+    }
+}
+
+fn set_option<V: ToValue + Clone>(object: &Object, key: &str, value: &Option<V>) -> Result<()> {
+    match value {
+        Some(value) => object.set(key, value.clone()),
+        None => Ok(()),
+    }
+}
+
+// The `format` functions of the formatters created by `MiniV8::format_number` and
+// `MiniV8::format_date`, by locale and options, stored in an isolate slot.
+pub(crate) struct Formatters(RefCell<Lru<StdString, v8::Global<v8::Function>>>);
+
+impl Default for Formatters {
+    fn default() -> Formatters {
+        Formatters(RefCell::new(Lru::new(CAPACITY)))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::*;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+// A closure to run on a worker's `MiniV8`, which returns whether it panicked.
+type Job = Box<dyn FnOnce(&MiniV8) -> bool + Send>;
+
+// The id of the next pool to be created, and the id of the pool that owns the current thread (if
+// any), with which `IsolatePool::exec` detects being called from one of its own jobs.
+static NEXT_POOL_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static CURRENT_POOL: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// A fixed set of `MiniV8`s, each owned by a dedicated thread, that closures can be run on from
+/// any thread.
+///
+/// A `MiniV8` is bound to the thread that created it (it is neither `Send` nor `Sync`), so a
+/// multi-threaded program can't move one between threads. The pool is both `Send` and `Sync`
+/// instead, and ships closures to the threads that own the `MiniV8`s. Every `MiniV8` is created,
+/// used and dropped on its own thread, regardless of which thread creates, uses or drops the
+/// pool.
+pub struct IsolatePool {
+    id: u64,
+    workers: Vec<Worker>,
+    next: AtomicUsize,
+}
+
+struct Worker {
+    jobs: Option<mpsc::Sender<Job>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl IsolatePool {
+    /// Creates a pool of `size` default `MiniV8`s, each on its own thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new(size: usize) -> IsolatePool {
+        IsolatePool::with_builder(size, MiniV8::builder())
+    }
+
+    /// Creates a pool of `size` `MiniV8`s built with `builder`, each on its own thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn with_builder(size: usize, builder: MiniV8Builder) -> IsolatePool {
+        assert!(size > 0, "isolate pool must not be empty");
+        let id = NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed);
+        let workers = (0..size).map(|index| Worker::spawn(id, index, builder.clone())).collect();
+        IsolatePool { id, workers, next: AtomicUsize::new(0) }
+    }
+
+    /// Returns the number of `MiniV8`s in the pool.
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Runs `func` with one of the pool's `MiniV8`s on the thread that owns it, blocking until
+    /// `func` returns, and returns its result. `MiniV8`s are picked in turn, so state left behind
+    /// by one call (e.g. global variables) may or may not be seen by the next.
+    ///
+    /// If `func` panics, the panic is resumed on the calling thread, and the `MiniV8` that it
+    /// panicked with is replaced by a new one.
+    ///
+    /// Returns `Error::PoolReentrancy` (without running `func`) if called from within a closure
+    /// that this pool is running, since waiting on the pool from one of its own threads could
+    /// wait forever.
+    pub fn exec<F, R>(&self, func: F) -> Result<R>
+    where
+        F: FnOnce(&MiniV8) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        if CURRENT_POOL.with(|pool| pool.get()) == Some(self.id) {
+            return Err(Error::PoolReentrancy);
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        let (sender, receiver) = mpsc::channel::<thread::Result<R>>();
+        let job: Job = Box::new(move |mv8| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| func(mv8)));
+            let panicked = result.is_err();
+            let _ = sender.send(result);
+            panicked
+        });
+        self.workers[index].jobs.as_ref().unwrap().send(job).expect("isolate pool thread died");
+        match receiver.recv().expect("isolate pool thread died") {
+            Ok(result) => Ok(result),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl Worker {
+    fn spawn(pool: u64, index: usize, builder: MiniV8Builder) -> Worker {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let thread = thread::Builder::new()
+            .name(format!("mini-v8-pool-{}", index))
+            .spawn(move || {
+                CURRENT_POOL.with(|current| current.set(Some(pool)));
+                let mut mv8 = Some(builder.clone().build());
+                for job in receiver {
+                    if job(mv8.as_ref().unwrap()) {
+                        // Isolates are exited in the reverse order of their creation, so the old
+                        // one has to be dropped before its replacement is built:
+                        mv8.take();
+                        mv8 = Some(builder.clone().build());
+                    }
+                }
+            })
+            .expect("failed to spawn isolate pool thread");
+        Worker { jobs: Some(jobs), thread: Some(thread) }
+    }
+}
+
+impl fmt::Debug for IsolatePool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<isolate pool of {}>", self.workers.len())
+    }
+}
+
+// Closes every worker's job channel, so that each thread drops its `MiniV8` and exits, and then
+// waits for the threads to finish. If the pool is dropped on one of its own threads (e.g. by a job
+// that held the last reference to it), that thread can't wait for itself, so the threads are
+// detached instead.
+impl Drop for IsolatePool {
+    fn drop(&mut self) {
+        for worker in &mut self.workers {
+            worker.jobs.take();
+        }
+        if CURRENT_POOL.with(|pool| pool.get()) == Some(self.id) {
+            return;
+        }
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::*;
+use std::sync::Arc;
+
+impl MiniV8 {
+    /// Creates a read-only, array-like JavaScript object that is backed by a Rust `Vec`, without
+    /// converting any of its elements up front. Each element is converted to JavaScript when it is
+    /// read, so scripts can index into very large vectors at a cost that is proportional to the
+    /// number of elements that they actually read.
+    ///
+    /// The object has a `length` and indexed elements like an array, and is iterable (e.g. with
+    /// `for...of` or spread syntax), but it is not an actual array: `Array.isArray` returns
+    /// `false` for it, and it doesn't inherit from `Array.prototype`. Writing, defining, or
+    /// deleting elements throws a `TypeError`.
+    ///
+    /// Elements are converted anew on every read, so reading the same element twice produces two
+    /// distinct objects if the element converts to an object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` has more than 2^32-2 elements, the maximum length of a JavaScript array.
+    pub fn create_lazy_array<T: ToValue + Clone + 'static>(&self, data: Arc<Vec<T>>) -> Object {
+        let len = u32::try_from(data.len()).ok().filter(|&len| len < u32::MAX)
+            .expect("too many elements for a JavaScript array");
+        let lazy = LazyArray {
+            len,
+            get: Box::new(move |mv8, index| data[index as usize].clone().to_value(mv8)),
+        };
+        let intrinsics = self.intrinsics();
+        self.scope(|scope| {
+            let ptr = Box::into_raw(Box::new(lazy));
+            let ext = v8::External::new(scope, ptr as _);
+            let template = v8::ObjectTemplate::new(scope);
+            template.set_indexed_property_handler(
+                v8::IndexedPropertyHandlerConfiguration::new()
+                    .getter(get)
+                    .setter(set)
+                    .query(query)
+                    .deleter(delete)
+                    .enumerator(enumerate)
+                    .definer(define)
+                    .data(ext.into()),
+            );
+            let object = template.new_instance(scope).unwrap();
+            let hidden = || {
+                v8::PropertyAttribute::READ_ONLY | v8::PropertyAttribute::DONT_ENUM |
+                    v8::PropertyAttribute::DONT_DELETE
+            };
+            let key = create_string(scope, "length");
+            let length = v8::Integer::new_from_unsigned(scope, len);
+            object.define_own_property(scope, key.into(), length.into(), hidden());
+            // `Array.prototype.values` works with any array-like object:
+            let key = v8::Symbol::get_iterator(scope);
+            let values = v8::Local::new(scope, intrinsics.array_values.clone());
+            object.define_own_property(scope, key.into(), values.into(), hidden());
+            add_finalizer(scope, object, move || drop(unsafe { Box::from_raw(ptr) }));
+            Object {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, object),
+            }
+        })
+    }
+}
+
+type ElementGetter = Box<dyn Fn(&MiniV8, u32) -> Result<Value>>;
+
+// The data of an object created by `MiniV8::create_lazy_array`.
+struct LazyArray {
+    len: u32,
+    get: ElementGetter,
+}
+
+fn lazy_array<'a>(args: &v8::PropertyCallbackArguments) -> &'a LazyArray {
+    let ext = v8::Local::<v8::External>::try_from(args.data()).unwrap();
+    unsafe { &*(ext.value() as *const LazyArray) }
+}
+
+fn throw_read_only(scope: &mut v8::HandleScope) {
+    let message = create_string(scope, "cannot modify a read-only array");
+    let exception = v8::Exception::type_error(scope, message);
+    scope.throw_exception(exception);
+}
+
+fn get(
+    scope: &mut v8::HandleScope,
+    index: u32,
+    args: v8::PropertyCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let lazy = lazy_array(&args);
+    if index >= lazy.len {
+        return;
+    }
+    let mv8 = MiniV8::from_callback_scope(scope);
+    mv8.enter_callback(scope, |scope| {
+        match (lazy.get)(&mv8, index) {
+            Ok(value) => rv.set(value.to_v8_value(scope)),
+            Err(e) => {
+                let exception = e.to_value(&mv8).to_v8_value(scope);
+                scope.throw_exception(exception);
+            },
+        }
+    });
+}
+
+fn set(
+    scope: &mut v8::HandleScope,
+    _index: u32,
+    _value: v8::Local<v8::Value>,
+    _args: v8::PropertyCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    throw_read_only(scope);
+}
+
+fn query(
+    scope: &mut v8::HandleScope,
+    index: u32,
+    args: v8::PropertyCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    if index < lazy_array(&args).len {
+        let attributes = v8::PropertyAttribute::READ_ONLY | v8::PropertyAttribute::DONT_DELETE;
+        rv.set(v8::Integer::new(scope, attributes.as_u32() as i32).into());
+    }
+}
+
+fn delete(
+    scope: &mut v8::HandleScope,
+    index: u32,
+    args: v8::PropertyCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    if index < lazy_array(&args).len {
+        throw_read_only(scope);
+    }
+}
+
+fn enumerate(
+    scope: &mut v8::HandleScope,
+    args: v8::PropertyCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let len = lazy_array(&args).len;
+    let indices: Vec<v8::Local<v8::Value>> = (0..len)
+        .map(|i| v8::Integer::new_from_unsigned(scope, i).into())
+        .collect();
+    rv.set(v8::Array::new_with_elements(scope, &indices).into());
+}
+
+fn define(
+    scope: &mut v8::HandleScope,
+    _index: u32,
+    _descriptor: &v8::PropertyDescriptor,
+    _args: v8::PropertyCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    throw_read_only(scope);
+}
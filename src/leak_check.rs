@@ -0,0 +1,83 @@
+use crate::*;
+use std::fmt;
+use std::panic::Location;
+use std::string::String as StdString;
+
+/// The resources still held by a `MiniV8`, as returned by `MiniV8::leak_check`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeakReport {
+    /// Where each Rust function that is still alive was created (with `MiniV8::create_function` or
+    /// one of its variants).
+    pub functions: Vec<&'static Location<'static>>,
+    /// The keys of the user data values that are still set (see `MiniV8::set_user_data`).
+    pub user_data: Vec<StdString>,
+    /// The number of scripts held by the cache of `MiniV8::eval_cached`.
+    pub cached_scripts: usize,
+    /// The size in bytes of the V8 global handles in use beyond those that the `MiniV8` held when
+    /// it was built. Every `Value` (and every other handle) kept alive on the Rust side holds a
+    /// global handle, as do the values held by the `MiniV8`'s caches, e.g. those of
+    /// `MiniV8::cached_value` and `MiniV8::eval_cached`, or the module map.
+    pub global_handles_size: usize,
+}
+
+impl LeakReport {
+    /// Returns `true` if the `MiniV8` holds none of the resources that the report covers.
+    pub fn is_clean(&self) -> bool {
+        self.functions.is_empty()
+            && self.user_data.is_empty()
+            && self.cached_scripts == 0
+            && self.global_handles_size == 0
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "no leaks");
+        }
+        write!(f, "leaks:")?;
+        for location in &self.functions {
+            write!(f, "\n  function created at {}", location)?;
+        }
+        for key in &self.user_data {
+            write!(f, "\n  user data {:?}", key)?;
+        }
+        if self.cached_scripts > 0 {
+            write!(f, "\n  {} cached script(s)", self.cached_scripts)?;
+        }
+        if self.global_handles_size > 0 {
+            write!(f, "\n  {} byte(s) of global handles", self.global_handles_size)?;
+        }
+        Ok(())
+    }
+}
+
+impl MiniV8 {
+    /// Reports the resources that this `MiniV8` still holds, for asserting at the end of a test
+    /// that a scenario cleaned up after itself, e.g. with
+    /// `assert!(report.is_clean(), "{}", report)`.
+    ///
+    /// A full garbage collection is run first, so functions are only reported if they are still
+    /// reachable (e.g. from a global variable or from a Rust `Function` handle). Functions are
+    /// reported with where they were created, which is the caller of `MiniV8::create_function`
+    /// (or of one of its variants, such as `MiniV8::create_function_typed` or
+    /// `MiniV8::create_async_function`).
+    pub fn leak_check(&self) -> LeakReport {
+        self.low_memory_notification();
+        let baseline = self.global_handles_baseline();
+        LeakReport {
+            functions: self.function_locations(),
+            user_data: self.user_data_keys(),
+            cached_scripts: self.script_cache_stats().size,
+            global_handles_size: self.used_global_handles_size().saturating_sub(baseline),
+        }
+    }
+
+    pub(crate) fn used_global_handles_size(&self) -> usize {
+        self.scope(|scope| {
+            let mut stats = v8::HeapStatistics::default();
+            scope.get_heap_statistics(&mut stats);
+            stats.used_global_handles_size()
+        })
+    }
+}
@@ -1,19 +1,69 @@
 //! MiniV8 is a minimal embedded V8 JavaScript engine wrapper for Rust.
 
 mod array;
+mod array_buffer;
+mod async_function;
+mod async_iterable;
+#[cfg(feature = "commonjs")] mod commonjs;
+mod compiled_script;
+mod console;
 mod conversion;
+mod coverage;
 mod error;
+#[cfg(feature = "futures")] mod event_channel;
+mod external;
 mod function;
+mod heap;
+#[cfg(feature = "intl")] mod intl;
+mod isolate_pool;
+mod lazy_array;
+mod leak_check;
+mod lru;
 mod mini_v8;
+mod module;
 mod object;
+mod patch;
+mod pluck;
+mod promise;
+mod regex_cache;
+mod sandbox;
+mod script_cache;
+mod snapshot;
 mod string;
+mod summary;
+mod symbol;
+mod syntax;
 #[cfg(test)] mod tests;
+mod transfer;
 mod value;
+#[cfg(feature = "serde")] mod value_serde;
 
 pub use crate::array::*;
+pub use crate::array_buffer::*;
+pub use crate::async_iterable::*;
+#[cfg(feature = "commonjs")] pub use crate::commonjs::*;
+pub use crate::compiled_script::*;
+pub use crate::console::*;
+pub use crate::coverage::*;
 pub use crate::error::*;
+#[cfg(feature = "futures")] pub use crate::event_channel::*;
 pub use crate::function::*;
+pub use crate::heap::*;
+#[cfg(feature = "intl")] pub use crate::intl::*;
+pub use crate::isolate_pool::*;
+pub use crate::leak_check::*;
 pub use crate::mini_v8::*;
+pub use crate::module::*;
 pub use crate::object::*;
+pub use crate::patch::*;
+pub use crate::pluck::*;
+pub use crate::promise::*;
+pub use crate::regex_cache::*;
+pub use crate::sandbox::*;
+pub use crate::script_cache::*;
 pub use crate::string::*;
+pub use crate::summary::*;
+pub use crate::symbol::*;
+pub use crate::syntax::*;
 pub use crate::value::*;
+#[cfg(feature = "derive")] pub use mini_v8_derive::{FromValue, ToValue};
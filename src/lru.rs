@@ -0,0 +1,75 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+// A cache that evicts its least recently used entries once it holds more than its capacity. This
+// backs the caches kept in isolate slots (of regular expressions, scripts, and Intl formatters),
+// whose values are raw V8 handles: Holding `Object`s or `Function`s instead would keep the isolate
+// that owns the cache alive.
+pub(crate) struct Lru<K, V> {
+    entries: HashMap<K, (u64, V)>,
+    // The keys of the entries by when they were last used, from least to most recently:
+    order: BTreeMap<u64, K>,
+    clock: u64,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    pub(crate) fn new(capacity: usize) -> Lru<K, V> {
+        Lru { entries: HashMap::new(), order: BTreeMap::new(), clock: 0, capacity }
+    }
+
+    // Returns the value for `key`, and marks it as the most recently used one.
+    pub(crate) fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (used, value) = self.entries.get_mut(key)?;
+        let key = self.order.remove(used).unwrap();
+        self.clock += 1;
+        *used = self.clock;
+        let value = value.clone();
+        self.order.insert(self.clock, key);
+        Some(value)
+    }
+
+    // Returns whether the cache holds `key`, without marking it as used.
+    pub(crate) fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.contains_key(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        if let Some((used, _)) = self.entries.insert(key.clone(), (self.clock, value)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.clock, key);
+        self.evict();
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let (_, key) = self.order.pop_first().unwrap();
+            self.entries.remove(&key);
+        }
+    }
+}
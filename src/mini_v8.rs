@@ -1,12 +1,18 @@
 use crate::*;
+use crate::async_function::AsyncTasks;
+use crate::conversion::date_from_system_time;
+use crate::snapshot::snapshot_blob;
 use std::any::Any;
-use std::cell::RefCell;
-use std::collections::BTreeMap;
-use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::c_void;
+use std::panic::Location;
+use std::rc::{Rc, Weak};
 use std::string::String as StdString;
-use std::sync::{Arc, Condvar, Mutex, Once};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Clone)]
 pub struct MiniV8 {
@@ -14,11 +20,15 @@ pub struct MiniV8 {
 }
 
 impl MiniV8 {
+    /// Creates a new `MiniV8` with the default configuration. See `MiniV8::builder` for creating a
+    /// customized `MiniV8`.
     pub fn new() -> MiniV8 {
-        initialize_v8();
-        let mut isolate = v8::Isolate::new(Default::default());
-        initialize_slots(&mut isolate);
-        MiniV8 { interface: Interface::new(isolate) }
+        MiniV8::builder().build()
+    }
+
+    /// Returns a builder for configuring a new `MiniV8`.
+    pub fn builder() -> MiniV8Builder {
+        MiniV8Builder::default()
     }
 
     /// Returns the global JavaScript object.
@@ -39,39 +49,40 @@ impl MiniV8 {
         R: FromValue,
     {
         let script = script.into();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "eval",
+            origin = script.origin.as_ref().map(|o| o.name.as_str()),
+            timeout = script.timeout.map(tracing::field::debug),
+        ).entered();
+        let timeout = script.timeout;
+        self.with_timeout(timeout, || self.eval_inner(script))?.into(self)
+    }
+
+    // Runs `func`, terminating the JavaScript that it runs if it takes longer than `timeout`.
+    // Timeouts can only be set on the outermost evaluation.
+    pub(crate) fn with_timeout<F>(&self, timeout: Option<Duration>, func: F) -> Result<Value>
+    where
+        F: FnOnce() -> Result<Value>,
+    {
         let isolate_handle = self.interface.isolate_handle();
-        match (self.interface.len() == 1, script.timeout) {
+        match (self.interface.len() == 1, timeout) {
             (true, Some(timeout)) => {
                 execute_with_timeout(
                     timeout,
-                    || self.eval_inner(script),
+                    func,
                     move || { isolate_handle.terminate_execution(); },
-                )?.into(self)
+                )
             },
             (false, Some(_)) => Err(Error::InvalidTimeout),
-            (_, None) => self.eval_inner(script)?.into(self),
+            (_, None) => func(),
         }
     }
 
     fn eval_inner(&self, script: Script) -> Result<Value> {
         self.try_catch(|scope| {
             let source = create_string(scope, &script.source);
-            let origin = script.origin.map(|o| {
-                let name = create_string(scope, &o.name).into();
-                let source_map_url = create_string(scope, "").into();
-                v8::ScriptOrigin::new(
-                    scope,
-                    name,
-                    o.line_offset,
-                    o.column_offset,
-                    false,
-                    0,
-                    source_map_url,
-                    true,
-                    false,
-                    false,
-                )
-            });
+            let origin = script.origin.map(|o| create_origin(scope, &o, false));
             let script = v8::Script::compile(scope, source, origin.as_ref());
             self.exception(scope)?;
             let result = script.unwrap().run(scope);
@@ -80,6 +91,297 @@ impl MiniV8 {
         })
     }
 
+    /// Serializes a value to a JSON string with V8's native `JSON.stringify`, which calls
+    /// `toJSON` methods along the way.
+    ///
+    /// Returns an error if the value has no JSON representation (e.g. `undefined`, functions and
+    /// symbols, or objects whose `toJSON` method returns one of those), or if `JSON.stringify`
+    /// throws (e.g. because the value contains a circular reference, or a `BigInt`).
+    pub fn json_stringify(&self, value: Value) -> Result<StdString> {
+        self.assert_owns(&value)?;
+        self.try_catch(|scope| {
+            let v8_value = value.to_v8_value(scope);
+            let json = v8::json::stringify(scope, v8_value);
+            self.exception(scope)?;
+            // V8 converts a result of `undefined` to a string rather than returning it, and no
+            // other value serializes to this:
+            let json = json.unwrap().to_rust_string_lossy(scope);
+            if json == "undefined" {
+                return Err(Error::FromJsConversionError { from: value.type_name(), to: "JSON" });
+            }
+            Ok(json)
+        })
+    }
+
+    /// Parses a JSON string into a value with V8's native `JSON.parse`.
+    ///
+    /// Returns an error if the string is not valid JSON.
+    pub fn json_parse(&self, json: &str) -> Result<Value> {
+        self.try_catch(|scope| {
+            let json = create_string(scope, json);
+            let value = v8::json::parse(scope, json);
+            self.exception(scope)?;
+            Ok(Value::from_v8_value(self, scope, value.unwrap()))
+        })
+    }
+
+    /// Compiles a function with the given parameter names and body, like JavaScript's `Function`
+    /// constructor does, but without wrapping the body in any way: Line and column numbers in stack
+    /// traces and syntax errors map directly onto `body`, offset by `origin` if it is given.
+    ///
+    /// Returns an error if the body or any of the parameter names contain a syntax error.
+    pub fn compile_function(
+        &self,
+        body: &str,
+        params: &[&str],
+        origin: Option<ScriptOrigin>,
+    ) -> Result<Function> {
+        self.compile_function_with_extensions(body, params, &[], origin)
+    }
+
+    /// Compiles a function like `MiniV8::compile_function`, with the properties of each of the
+    /// given extension objects in scope as if the body was wrapped in `with` statements (the last
+    /// extension object being the innermost). The extension objects are captured by the function,
+    /// so changes to their properties remain visible to it.
+    pub fn compile_function_with_extensions(
+        &self,
+        body: &str,
+        params: &[&str],
+        extensions: &[Object],
+        origin: Option<ScriptOrigin>,
+    ) -> Result<Function> {
+        self.try_catch(|scope| {
+            let body = create_string(scope, body);
+            let params: Vec<_> = params.iter().map(|p| create_string(scope, p)).collect();
+            let extensions: Vec<_> = extensions.iter()
+                .map(|e| v8::Local::new(scope, e.handle.clone()))
+                .collect();
+            let origin = origin.map(|o| create_origin(scope, &o, false));
+            let source = v8::script_compiler::Source::new(body, origin.as_ref());
+            let function = v8::script_compiler::compile_function(
+                scope,
+                source,
+                &params,
+                &extensions,
+                v8::script_compiler::CompileOptions::NoCompileOptions,
+                v8::script_compiler::NoCacheReason::NoReason,
+            );
+            self.exception(scope)?;
+            Ok(Function {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, function.unwrap()),
+            })
+        })
+    }
+
+    /// Compiles a JavaScript module (an ECMAScript module, as opposed to a classic script) without
+    /// evaluating it. The module's `import` declarations are resolved by specifier against the
+    /// other modules compiled by this `MiniV8` (or loaded with the module loader, see
+    /// `MiniV8::set_module_loader`), and `specifier` is how this module can itself be imported. If
+    /// a module was previously compiled with the same specifier, it is replaced for the purposes
+    /// of resolution.
+    ///
+    /// Returns an error if the module contains a syntax error.
+    pub fn compile_module(&self, specifier: &str, source: &str) -> Result<Module> {
+        let (handle, script_id) = self.try_catch(|scope| -> Result<_> {
+            let module = compile_v8_module(scope, specifier, source);
+            self.exception(scope)?;
+            let module = module.unwrap();
+            Ok((v8::Global::new(scope, module), module.script_id()))
+        })?;
+        self.modules(|modules| modules.insert(specifier, script_id, handle.clone()));
+        Ok(Module { mv8: self.clone(), handle, specifier: specifier.to_string() })
+    }
+
+    /// Compiles a module under the specifier `inline:<name>`, so that other modules can import it
+    /// with `import ... from "inline:<name>"`. See `MiniV8::compile_module`.
+    ///
+    /// Returns an error if the module contains a syntax error.
+    pub fn register_inline_module(&self, name: &str, source: &str) -> Result<Module> {
+        self.compile_module(&format!("inline:{}", name), source)
+    }
+
+    /// Compiles and evaluates a module without giving it a specifier, and returns its namespace
+    /// object once its evaluation has completed. This is meant for tests and small tools that
+    /// don't need a full module resolver.
+    ///
+    /// The module's imports are resolved as with `MiniV8::compile_module`, which includes inline
+    /// modules (see `MiniV8::register_inline_module`). In addition, `data:` URLs of JavaScript
+    /// source (e.g. `data:text/javascript,export%20default%201`, optionally base64-encoded) are
+    /// resolved by compiling the source that they contain.
+    ///
+    /// Pending jobs (e.g. promise reactions) are run until the module's evaluation has settled, so
+    /// modules may use top-level `await`, as long as what they await can settle without any
+    /// further input from Rust.
+    ///
+    /// Returns an error if the module contains a syntax error, if an import could not be resolved,
+    /// if the module's evaluation throws an exception, or `Error::NotSettled` if the evaluation is
+    /// still waiting on something after all pending jobs have been run.
+    pub fn eval_module(&self, source: &str) -> Result<Object> {
+        let handle = self.try_catch(|scope| -> Result<_> {
+            let module = compile_v8_module(scope, "<module>", source);
+            self.exception(scope)?;
+            Ok(v8::Global::new(scope, module.unwrap()))
+        })?;
+        self.eval_module_inner(Module { mv8: self.clone(), handle, specifier: StdString::new() })
+    }
+
+    /// Compiles and evaluates a module like `MiniV8::eval_module`, but under the given specifier
+    /// (see `MiniV8::compile_module`), which is passed as the referrer to the module loader (see
+    /// `MiniV8::set_module_loader`) when resolving the module's imports, and through which modules
+    /// that import this one (directly or in a cycle) resolve to it.
+    ///
+    /// Returns an error under the same conditions as `MiniV8::eval_module`.
+    pub fn eval_module_as(&self, specifier: &str, source: &str) -> Result<Object> {
+        self.eval_module_inner(self.compile_module(specifier, source)?)
+    }
+
+    fn eval_module_inner(&self, module: Module) -> Result<Object> {
+        let result: Value = module.evaluate()?;
+        self.try_catch(|scope| {
+            let result = result.to_v8_value(scope);
+            // Modules evaluate to a promise when V8 supports top-level `await`:
+            let promise: v8::Local<v8::Promise> = match result.try_into() {
+                Ok(promise) => promise,
+                Err(_) => return Ok(()),
+            };
+            // A checkpoint runs jobs until there are none left, including any jobs that are
+            // queued by the jobs being run, so the promise can't settle any further after it:
+            scope.perform_microtask_checkpoint();
+            self.exception(scope)?;
+            match promise.state() {
+                v8::PromiseState::Fulfilled => Ok(()),
+                v8::PromiseState::Rejected => {
+                    let reason = promise.result(scope);
+                    Err(Error::Value(Value::from_v8_value(self, scope, reason)))
+                },
+                v8::PromiseState::Pending => Err(Error::NotSettled),
+            }
+        })?;
+        module.namespace()
+    }
+
+    /// Sets the function used to load the source of modules that are imported but haven't been
+    /// compiled yet, replacing any previously set function. The function is called with the
+    /// specifier of the importing module (the referrer, which is empty for modules evaluated with
+    /// `MiniV8::eval_module`) and the requested specifier as written in the `import` declaration,
+    /// and returns the source of the requested module.
+    ///
+    /// The loaded module is compiled under the requested specifier, so any later import of the
+    /// same specifier (e.g. both sides of a diamond, or a cycle) resolves to the same module
+    /// without calling the function again. Since modules are looked up by specifier alone,
+    /// specifiers should identify modules regardless of which module imports them, e.g. absolute
+    /// paths rather than relative ones.
+    ///
+    /// If the function returns an error, the error is thrown as an exception from the
+    /// instantiation of the importing module.
+    pub fn set_module_loader<F>(&self, func: F)
+    where
+        F: Fn(&MiniV8, &str, &str) -> Result<StdString> + 'static,
+    {
+        self.modules(|modules| *modules.loader.borrow_mut() = Some(Rc::new(func)));
+    }
+
+    /// Sets the function used to initialize the `import.meta` object of modules, replacing any
+    /// previously set function. The function is called lazily, the first time that a module
+    /// accesses `import.meta`, with the `MiniV8`, the module, and its (initially empty)
+    /// `import.meta` object, on which the function may set arbitrary properties. Use
+    /// `Module::specifier` to tell modules apart.
+    ///
+    /// If the function returns an error, the error is thrown as an exception where `import.meta`
+    /// was accessed.
+    pub fn set_import_meta_callback<F>(&self, func: F)
+    where
+        F: Fn(&MiniV8, &Module, &Object) -> Result<()> + 'static,
+    {
+        self.modules(|modules| *modules.import_meta_callback.borrow_mut() = Some(Rc::new(func)));
+    }
+
+    /// Returns the id of this `MiniV8`'s JavaScript context. Every `MiniV8` has a distinct id,
+    /// which stays the same for its whole lifetime (including all of its clones) and is never
+    /// reused by another `MiniV8` within the process. See `Value::origin_context`.
+    pub fn context_id(&self) -> u64 {
+        self.interface.0.context_id
+    }
+
+    /// Returns a handle with which JavaScript running in this `MiniV8` can be interrupted or
+    /// terminated from any thread, e.g. to stop a runaway script or function call on a deadline
+    /// that `Script::timeout` can't express.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            isolate: self.interface.isolate_handle(),
+            terminated: self.interface.0.terminated.clone(),
+            interrupts: self.interface.use_slot(|i: &Interrupts| Arc::downgrade(&i.0)),
+        }
+    }
+
+    /// Returns a handle with which JavaScript running in this `MiniV8` can be terminated from any
+    /// thread.
+    #[deprecated(note = "renamed to `MiniV8::interrupt_handle`")]
+    #[allow(deprecated)]
+    pub fn terminate_handle(&self) -> TerminateHandle {
+        self.interrupt_handle()
+    }
+
+    /// Returns an error if `value` is a reference to a JavaScript value (e.g. an object or a
+    /// string) that belongs to a different `MiniV8`'s context. Values that aren't references (e.g.
+    /// numbers) belong to no context in particular, and are always accepted.
+    ///
+    /// Values are checked like this wherever they are handed to a `MiniV8` (e.g. by `ToValue`
+    /// conversions, so in `Object::set` or `Function::call`), which return the same error for
+    /// foreign values. Use `MiniV8::transfer` to copy a value into another `MiniV8`.
+    pub fn assert_owns(&self, value: &Value) -> Result<()> {
+        match value.origin_context() {
+            Some(origin) if origin != self.context_id() => {
+                Err(Error::ForeignValue { context: self.context_id(), origin })
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Lets V8 use an idle period of at most `max` for deferrable work, e.g. incremental garbage
+    /// collection, so that the work doesn't have to be done during a later evaluation instead.
+    /// This is meant to be called when the embedder knows that it has some time to spare (e.g.
+    /// between frames or requests). Returns immediately if there is no idle work pending.
+    ///
+    /// Returns how much of `max` was used, which may slightly exceed `max` if an idle task overran
+    /// its deadline.
+    pub fn run_idle_tasks(&self, max: Duration) -> Duration {
+        let start = Instant::now();
+        let platform = v8::V8::get_current_platform();
+        self.scope(|scope| v8::Platform::run_idle_tasks(&platform, scope, max.as_secs_f64()));
+        start.elapsed()
+    }
+
+    /// Runs tasks that V8's background threads have posted back to this `MiniV8` (e.g. to finish
+    /// background compilation of WebAssembly or of baseline code), until there are none left or
+    /// until `max` has elapsed. Tasks are run one at a time, and a task that has started is always
+    /// run to completion. Returns immediately if there are no tasks pending.
+    ///
+    /// Returns how much of `max` was used, which may exceed `max` if the last task overran it.
+    pub fn pump_platform_tasks(&self, max: Duration) -> Duration {
+        let start = Instant::now();
+        let platform = v8::V8::get_current_platform();
+        self.scope(|scope| {
+            while start.elapsed() < max {
+                if !v8::Platform::pump_message_loop(&platform, scope, false) {
+                    break;
+                }
+            }
+        });
+        start.elapsed()
+    }
+
+    /// Sets the maximum number of Rust functions (created with `MiniV8::create_function`) that can
+    /// be running at once, each having been called from within JavaScript called by the previous
+    /// one, e.g. when a Rust function calls a JavaScript function that calls the Rust function
+    /// again. Calls beyond the limit throw a `RangeError` (see `Error::ReentrancyLimit`) instead of
+    /// calling the Rust function, so that scripts can't exhaust the native stack by recursing
+    /// through Rust. The default limit is 256.
+    pub fn set_max_reentrancy_depth(&self, depth: u32) {
+        self.interface.use_slot(|limit: &ReentrancyLimit| limit.0.set(depth));
+    }
+
     /// Inserts any sort of keyed value of type `T` into the `MiniV8`, typically for later retrieval
     /// from within Rust functions called from within JavaScript. If a value already exists with the
     /// key, it is returned.
@@ -109,8 +411,58 @@ impl MiniV8 {
         self.interface.use_slot(|m: &AnyMap| m.0.borrow_mut().remove(key))
     }
 
+    /// Returns the value cached under the given key, first calling `init` to create and cache it if
+    /// no value is cached yet. This avoids the cost of repeatedly converting the same constants
+    /// (for example, the same strings) into JavaScript values: Later calls with the same key return
+    /// the very same JavaScript value.
+    ///
+    /// Cached objects are shared by every script that is handed them, so scripts can mutate them
+    /// (e.g. by setting properties or by replacing their prototypes) and affect each other. Prefer
+    /// caching primitive values, or objects frozen with `Object.freeze`.
+    ///
+    /// Returns an error (without caching anything) if `init` returns an error.
+    pub fn cached_value<F>(&self, key: &'static str, init: F) -> Result<Value>
+    where
+        F: FnOnce(&MiniV8) -> Result<Value>,
+    {
+        let cached = self.interface.use_slot(|c: &ValueCache| c.0.borrow().get(key).cloned());
+        if let Some(handle) = cached {
+            return Ok(self.scope(|scope| {
+                let value = v8::Local::new(scope, handle);
+                Value::from_v8_value(self, scope, value)
+            }));
+        }
+
+        let value = init(self)?;
+        let handle = self.scope(|scope| {
+            let value = value.to_v8_value(scope);
+            v8::Global::new(scope, value)
+        });
+        self.interface.use_slot(|c: &ValueCache| c.0.borrow_mut().insert(key, handle));
+        Ok(value)
+    }
+
+    /// Removes all of the values cached with `MiniV8::cached_value`.
+    pub fn clear_value_cache(&self) {
+        self.interface.use_slot(|c: &ValueCache| c.0.borrow_mut().clear());
+    }
+
+    /// Creates a date from a `SystemTime`, which may be before the epoch. Like JavaScript's `Date`,
+    /// the date is truncated to whole milliseconds, and times more than 100,000,000 days away from
+    /// the epoch produce an invalid date.
+    ///
+    /// Dates are passed by value, so the date becomes a new JavaScript `Date` object every time it
+    /// is passed to JavaScript.
+    pub fn create_date(&self, time: SystemTime) -> Value {
+        date_from_system_time(time)
+    }
+
     /// Creates and returns a string managed by V8.
     ///
+    /// `value` is always valid UTF-8, being a `str`. To create a string from bytes that might not
+    /// be valid UTF-8, use `MiniV8::create_string_lossy` rather than converting them to a `str`
+    /// first.
+    ///
     /// # Panics
     ///
     /// Panics if source value is longer than `(1 << 28) - 16` bytes.
@@ -124,6 +476,88 @@ impl MiniV8 {
         })
     }
 
+    /// Creates and returns a string managed by V8 from bytes that are expected to be UTF-8, but
+    /// might not be valid UTF-8. V8 decodes the bytes directly, replacing each invalid sequence
+    /// with U+FFFD REPLACEMENT CHARACTER as `std::string::String::from_utf8_lossy` does, without
+    /// any intermediate Rust string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if source value is longer than `(1 << 28) - 16` bytes.
+    pub fn create_string_lossy(&self, value: &[u8]) -> String {
+        self.scope(|scope| {
+            let string = v8::String::new_from_utf8(scope, value, v8::NewStringType::Normal)
+                .expect("string exceeds maximum length");
+            String {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, string),
+            }
+        })
+    }
+
+    /// Creates and returns a string managed by V8 from Latin-1 (ISO-8859-1) bytes, in which every
+    /// byte is a character (the code point of the same value). Every sequence of bytes is valid
+    /// Latin-1, and V8 stores such strings at one byte per character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if source value is longer than `(1 << 28) - 16` bytes.
+    pub fn create_string_latin1(&self, value: &[u8]) -> String {
+        self.scope(|scope| {
+            let string = v8::String::new_from_one_byte(scope, value, v8::NewStringType::Normal)
+                .expect("string exceeds maximum length");
+            String {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, string),
+            }
+        })
+    }
+
+    /// Creates and returns a string managed by V8 from UTF-16 code units, which are taken as they
+    /// are (unpaired surrogates included), so that `String::to_utf16` returns them unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if source value is longer than `(1 << 28) - 16` code units.
+    pub fn create_string_utf16(&self, value: &[u16]) -> String {
+        self.scope(|scope| {
+            let string = v8::String::new_from_two_byte(scope, value, v8::NewStringType::Normal)
+                .expect("string exceeds maximum length");
+            String {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, string),
+            }
+        })
+    }
+
+    /// Creates a `StringBuilder` for efficiently building a large string out of many smaller
+    /// pieces. See `StringBuilder` for details.
+    pub fn create_string_builder(&self) -> StringBuilder {
+        self.scope(|scope| {
+            let string = v8::String::empty(scope);
+            StringBuilder {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, string),
+            }
+        })
+    }
+
+    /// Creates and returns a string managed by V8 by concatenating all of the given parts.
+    ///
+    /// This is a thin wrapper around `MiniV8::create_string_builder` and
+    /// `StringBuilder::append_str`. See `StringBuilder::append_str` for how this method might
+    /// return an error.
+    pub fn create_string_concat<'a, I>(&self, parts: I) -> Result<String>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut builder = self.create_string_builder();
+        for part in parts {
+            builder.append_str(part)?;
+        }
+        Ok(builder.finish())
+    }
+
     /// Creates and returns an empty `Array` managed by V8.
     pub fn create_array(&self) -> Array {
         self.scope(|scope| {
@@ -135,6 +569,55 @@ impl MiniV8 {
         })
     }
 
+    /// Creates and returns an `Array` managed by V8 holding the values of the given iterator, in
+    /// order.
+    ///
+    /// Returns an error if `ToValue::to_value` fails for any of the values.
+    ///
+    /// The values are converted in batches, and each batch is set within a single scope rather
+    /// than with a call to `Array::set` per value. Only one batch of converted values is held at a
+    /// time.
+    pub fn create_array_from<V, I>(&self, iter: I) -> Result<Array>
+    where
+        V: ToValue,
+        I: IntoIterator<Item = V>,
+    {
+        let array = self.create_array();
+        let mut iter = iter.into_iter();
+        let mut index = 0;
+        loop {
+            let values = iter.by_ref()
+                .take(BATCH_SIZE)
+                .map(|v| v.to_value(self))
+                .collect::<Result<Vec<_>>>()?;
+            if values.is_empty() {
+                return Ok(array);
+            }
+            self.try_catch(|scope| -> Result<()> {
+                let array = v8::Local::new(scope, array.handle.clone());
+                for value in values {
+                    let value = value.to_v8_value(scope);
+                    array.set_index(scope, index, value);
+                    self.exception(scope)?;
+                    index += 1;
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    /// Creates and returns an `ArrayBuffer` managed by V8, holding a copy of `bytes`.
+    pub fn create_array_buffer(&self, bytes: &[u8]) -> ArrayBuffer {
+        self.scope(|scope| {
+            let store = v8::ArrayBuffer::new_backing_store_from_vec(bytes.to_vec()).make_shared();
+            let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+            ArrayBuffer {
+                mv8: self.clone(),
+                handle: v8::Global::new(scope, buffer),
+            }
+        })
+    }
+
     /// Creates and returns an empty `Object` managed by V8.
     pub fn create_object(&self) -> Object {
         self.scope(|scope| {
@@ -149,8 +632,10 @@ impl MiniV8 {
     /// Creates and returns an `Object` managed by V8 filled with the keys and values from an
     /// iterator. Keys are coerced to object properties.
     ///
-    /// This is a thin wrapper around `MiniV8::create_object` and `Object::set`. See `Object::set`
-    /// for how this method might return an error.
+    /// This is equivalent to calling `MiniV8::create_object` and then `Object::set` for each entry
+    /// (see `Object::set` for how this method might return an error), except that the entries are
+    /// converted in batches, and each batch is set within a single scope. Only one batch of
+    /// converted entries is held at a time.
     pub fn create_object_from<K, V, I>(&self, iter: I) -> Result<Object>
     where
         K: ToValue,
@@ -158,10 +643,26 @@ impl MiniV8 {
         I: IntoIterator<Item = (K, V)>,
     {
         let object = self.create_object();
-        for (k, v) in iter {
-            object.set(k, v)?;
+        let mut iter = iter.into_iter();
+        loop {
+            let entries = iter.by_ref()
+                .take(BATCH_SIZE)
+                .map(|(k, v)| Ok((k.to_value(self)?, v.to_value(self)?)))
+                .collect::<Result<Vec<_>>>()?;
+            if entries.is_empty() {
+                return Ok(object);
+            }
+            self.try_catch(|scope| -> Result<()> {
+                let object = v8::Local::new(scope, object.handle.clone());
+                for (key, value) in entries {
+                    let key = key.to_v8_value(scope);
+                    let value = value.to_v8_value(scope);
+                    object.set(scope, key, value);
+                    self.exception(scope)?;
+                }
+                Ok(())
+            })?;
         }
-        Ok(object)
     }
 
     /// Wraps a Rust function or closure, creating a callable JavaScript function handle to it.
@@ -175,18 +676,32 @@ impl MiniV8 {
     /// For details on Rust-to-JavaScript conversions, refer to the `ToValue` and `ToValues` traits.
     ///
     /// If the provided function panics, the executable will be aborted.
+    #[track_caller]
     pub fn create_function<F, R>(&self, func: F) -> Function
     where
         F: Fn(Invocation) -> Result<R> + 'static,
         R: ToValue,
     {
-        let func = move |mv8: &MiniV8, this: Value, args: Values| {
-            func(Invocation { mv8: mv8.clone(), this, args })?.to_value(mv8)
-        };
+        self.create_function_raw(move |invocation, scope| {
+            let mv8 = invocation.mv8.clone();
+            Ok(func(invocation)?.to_value(&mv8)?.to_v8_value(scope))
+        })
+    }
 
+    // Wraps a callback that produces the function's return value directly in the callback's
+    // handle scope, for functions that return values without creating Rust handles to them.
+    #[track_caller]
+    pub(crate) fn create_function_raw<F>(&self, func: F) -> Function
+    where
+        F: for<'s> Fn(Invocation, &mut v8::HandleScope<'s>) -> Result<v8::Local<'s, v8::Value>>,
+        F: 'static,
+    {
+        let callback: SharedCallback = Rc::new(RefCell::new(Some(Box::new(func))));
+        let location = Location::caller();
+        self.interface.use_slot(|callbacks: &Callbacks| callbacks.register(&callback, location));
         self.scope(|scope| {
-            let callback = Box::new(func);
-            let callback_info = CallbackInfo { mv8: self.clone(), callback };
+            let interface = Rc::downgrade(&self.interface.0);
+            let callback_info = CallbackInfo { interface, callback };
             let ptr = Box::into_raw(Box::new(callback_info));
             let ext = v8::External::new(scope, ptr as _);
 
@@ -198,29 +713,44 @@ impl MiniV8 {
                 let data = fca.data();
                 let ext = v8::Local::<v8::External>::try_from(data).unwrap();
                 let callback_info_ptr = ext.value() as *mut CallbackInfo;
-                let callback_info = unsafe { &mut *callback_info_ptr };
-                let CallbackInfo { mv8, callback } = callback_info;
-                let ptr = scope as *mut v8::HandleScope;
-                // We can erase the lifetime of the `v8::HandleScope` safely because it only lives
-                // on the interface stack during the current block:
-                let ptr: *mut v8::HandleScope<'static> = unsafe { std::mem::transmute(ptr) };
-                mv8.interface.push(ptr);
-                let this = Value::from_v8_value(&mv8, scope, fca.this().into());
-                let len = fca.length();
-                let mut args = Vec::with_capacity(len as usize);
-                for i in 0..len {
-                    args.push(Value::from_v8_value(&mv8, scope, fca.get(i)));
-                }
-                match callback(&mv8, this, Values::from_vec(args)) {
-                    Ok(v) => {
-                        rv.set(v.to_v8_value(scope));
+                let callback_info = unsafe { &*callback_info_ptr };
+                let callback = callback_info.callback.borrow();
+                let (mv8, callback) = match (callback_info.interface.upgrade(), &*callback) {
+                    (Some(interface), Some(callback)) => {
+                        (MiniV8 { interface: Interface(interface) }, callback)
                     },
-                    Err(e) => {
-                        let exception = e.to_value(&mv8).to_v8_value(scope);
+                    // The function has been neutralized by the teardown of its `MiniV8`:
+                    _ => {
+                        let message = create_string(scope, "function called after teardown");
+                        let exception = v8::Exception::error(scope, message);
                         scope.throw_exception(exception);
+                        return;
                     },
                 };
-                mv8.interface.pop();
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("callback", args = fca.length()).entered();
+                mv8.enter_callback(scope, |scope| {
+                    let this = Value::from_v8_value(&mv8, scope, fca.this().into());
+                    let len = fca.length();
+                    let mut args = Vec::with_capacity(len as usize);
+                    for i in 0..len {
+                        args.push(Value::from_v8_value(&mv8, scope, fca.get(i)));
+                    }
+                    let new_target = Value::from_v8_value(&mv8, scope, fca.new_target());
+                    let args = Values::from_vec(args);
+                    let result = mv8.check_reentrancy().and_then(|_| {
+                        callback(Invocation { mv8: mv8.clone(), this, args, new_target }, scope)
+                    });
+                    match result {
+                        Ok(v) => {
+                            rv.set(v);
+                        },
+                        Err(e) => {
+                            let exception = e.to_value(&mv8).to_v8_value(scope);
+                            scope.throw_exception(exception);
+                        },
+                    };
+                });
             };
 
             let value = v8::Function::builder(v8_func).data(ext.into()).build(scope).unwrap();
@@ -245,6 +775,7 @@ impl MiniV8 {
     ///
     /// This is a version of `create_function` that accepts a FnMut argument. Refer to
     /// `create_function` for more information about the implementation.
+    #[track_caller]
     pub fn create_function_mut<F, R>(&self, func: F) -> Function
     where
         F: FnMut(Invocation) -> Result<R> + 'static,
@@ -274,10 +805,120 @@ impl MiniV8 {
         self.interface.try_catch(func)
     }
 
+    // Makes the `MiniV8` usable from within a V8 callback for the duration of `func` by pushing the
+    // callback's handle scope onto the interface stack.
+    pub(crate) fn enter_callback<F, T>(&self, scope: &mut v8::HandleScope, func: F) -> T
+    where
+        F: FnOnce(&mut v8::HandleScope) -> T,
+    {
+        let ptr = scope as *mut v8::HandleScope;
+        // We can erase the lifetime of the `v8::HandleScope` safely because it only lives on the
+        // interface stack during the current block:
+        let ptr: *mut v8::HandleScope<'static> = unsafe { std::mem::transmute(ptr) };
+        self.interface.push(ptr);
+        let result = func(scope);
+        self.interface.pop();
+        result
+    }
+
+    // Returns `Error::ReentrancyLimit` if more Rust functions are being called (each from within
+    // the JavaScript called by the previous one) than `MiniV8::set_max_reentrancy_depth` allows.
+    // Every nested call passes through a callback, so checking callbacks bounds the native stack
+    // used by nested calls and evaluations from Rust as well.
+    pub(crate) fn check_reentrancy(&self) -> Result<()> {
+        // The bottom of the interface stack is the isolate itself, and every other entry is a
+        // callback that has been entered:
+        let depth = self.interface.len() - 1;
+        let max = self.interface.use_slot(|limit: &ReentrancyLimit| limit.0.get());
+        if depth > max as usize {
+            return Err(Error::ReentrancyLimit);
+        }
+        Ok(())
+    }
+
+    // Recovers the `MiniV8` that owns the isolate from within a V8 callback that has no other way
+    // of reaching it (i.e. anything other than a function created by `MiniV8::create_function`).
+    pub(crate) fn from_callback_scope(scope: &mut v8::HandleScope) -> MiniV8 {
+        let interface = scope.get_slot::<WeakInterface>().unwrap().0.upgrade().unwrap();
+        MiniV8 { interface: Interface(interface) }
+    }
+
+    pub(crate) fn async_tasks<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(&AsyncTasks) -> T,
+    {
+        self.interface.use_slot(func)
+    }
+
+    pub(crate) fn modules<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(&Modules) -> T,
+    {
+        self.interface.use_slot(func)
+    }
+
+    pub(crate) fn regexes<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(&Regexes) -> T,
+    {
+        self.interface.use_slot(func)
+    }
+
+    pub(crate) fn scripts<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(&Scripts) -> T,
+    {
+        self.interface.use_slot(func)
+    }
+
+    #[cfg(feature = "intl")]
+    pub(crate) fn formatters<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(&Formatters) -> T,
+    {
+        self.interface.use_slot(func)
+    }
+
+    // Returns where each of the functions created with `MiniV8::create_function` that are still
+    // alive was created.
+    pub(crate) fn function_locations(&self) -> Vec<&'static Location<'static>> {
+        self.interface.use_slot(|callbacks: &Callbacks| callbacks.locations())
+    }
+
+    pub(crate) fn user_data_keys(&self) -> Vec<StdString> {
+        self.interface.use_slot(|m: &AnyMap| m.0.borrow().keys().cloned().collect())
+    }
+
+    pub(crate) fn global_handles_baseline(&self) -> usize {
+        self.interface.use_slot(|baseline: &GlobalHandlesBaseline| baseline.0)
+    }
+
+    pub(crate) fn coverage(&self) -> CoverageSlot {
+        self.interface.use_slot(|coverage: &CoverageSlot| coverage.clone())
+    }
+
+    pub(crate) fn intrinsics(&self) -> Rc<Intrinsics> {
+        self.interface.use_slot(|global: &Global| global.intrinsics.clone())
+    }
+
     pub(crate) fn exception(&self, scope: &mut v8::TryCatch<v8::HandleScope>) -> Result<()> {
         if scope.has_terminated() {
-            Err(Error::Timeout)
+            // Only the outermost evaluation clears the flags, so that every evaluation that the
+            // termination unwinds through reports it the same way:
+            let outermost = self.interface.len() == 1;
+            if heap_limit_reached(scope, outermost) {
+                return Err(Error::OutOfMemory);
+            }
+            let mut terminated = self.interface.0.terminated.lock().unwrap();
+            let terminated = match outermost {
+                true => std::mem::replace(&mut *terminated, false),
+                false => *terminated,
+            };
+            Err(if terminated { Error::Terminated } else { Error::Timeout })
         } else if let Some(exception) = scope.exception() {
+            if let Some(error) = take_external_error(scope, exception) {
+                return Err(error);
+            }
             Err(Error::Value(Value::from_v8_value(self, scope, exception)))
         } else {
             Ok(())
@@ -285,12 +926,48 @@ impl MiniV8 {
     }
 }
 
+// The interface shared by all clones of a `MiniV8`, with the context's id (see
+// `MiniV8::context_id`) kept alongside the stack so that it can be read without touching V8. The
+// `terminated` flag is set by `InterruptHandle::terminate`, to tell its terminations apart from
+// timeouts. It is only changed under its lock, so that a termination can't be requested or
+// cancelled halfway through another thread's `terminate`.
+struct InterfaceStack {
+    context_id: u64,
+    terminated: Arc<Mutex<bool>>,
+    stack: RefCell<Vec<Rc<RefCell<InterfaceEntry>>>>,
+}
+
+// The id of the next context to be created. Ids are never reused within a process.
+static NEXT_CONTEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Tears the `MiniV8` down in a fixed order once its last clone has been dropped: First, every
+// function created with `MiniV8::create_function` is neutralized, so that it throws instead of
+// calling back into Rust. Then the functions' callbacks (and whatever they have captured) are
+// dropped, then the user data, and only then the isolate itself. Anything dropped before the
+// isolate (e.g. `v8::Global` handles captured by a callback) can still safely use it.
+impl Drop for InterfaceStack {
+    fn drop(&mut self) {
+        let entry = match self.stack.get_mut().first() {
+            Some(entry) => entry.borrow(),
+            None => return,
+        };
+        let callbacks = entry.get_slot::<Callbacks>().take_all();
+        drop(callbacks);
+        let user_data = std::mem::take(&mut *entry.get_slot::<AnyMap>().0.borrow_mut());
+        drop(user_data);
+    }
+}
+
 #[derive(Clone)]
-struct Interface(Rc<RefCell<Vec<Rc<RefCell<InterfaceEntry>>>>>);
+struct Interface(Rc<InterfaceStack>);
+
+// A weak reference back to the interface, kept in an isolate slot for
+// `MiniV8::from_callback_scope`. This must be weak so that the isolate doesn't keep itself alive.
+struct WeakInterface(Weak<InterfaceStack>);
 
 impl Interface {
     fn len(&self) -> usize {
-        self.0.borrow().len()
+        self.0.stack.borrow().len()
     }
 
     fn isolate_handle(&self) -> v8::IsolateHandle {
@@ -302,9 +979,22 @@ impl Interface {
     where
         F: FnOnce(&mut v8::ContextScope<v8::HandleScope>) -> T,
     {
+        if self.len() == 1 {
+            self.cancel_idle_termination();
+        }
         self.top(|entry| entry.scope(func))
     }
 
+    // Nothing is running when the outermost scope is opened, so a termination requested since the
+    // last one was closed had nothing to terminate, and is cancelled rather than left pending for
+    // whatever runs next:
+    fn cancel_idle_termination(&self) {
+        let mut terminated = self.0.terminated.lock().unwrap();
+        if std::mem::replace(&mut *terminated, false) {
+            self.isolate_handle().cancel_terminate_execution();
+        }
+    }
+
     // Opens a new try-catch scope in the global context.
     fn try_catch<F, T>(&self, func: F) -> T
     where
@@ -313,16 +1003,26 @@ impl Interface {
         self.scope(|scope| func(&mut v8::TryCatch::new(scope)))
     }
 
-    fn new(isolate: v8::OwnedIsolate) -> Interface {
-        Interface(Rc::new(RefCell::new(vec![Rc::new(RefCell::new(InterfaceEntry::Isolate(isolate)))])))
+    fn new(isolate: v8::OwnedIsolate, live: LiveIsolate) -> Interface {
+        let entry = InterfaceEntry::Isolate(isolate, live);
+        Interface(Rc::new(InterfaceStack {
+            context_id: NEXT_CONTEXT_ID.fetch_add(1, Ordering::Relaxed),
+            terminated: Arc::new(Mutex::new(false)),
+            stack: RefCell::new(vec![Rc::new(RefCell::new(entry))]),
+        }))
     }
 
     fn push(&self, handle_scope: *mut v8::HandleScope<'static>) {
-        self.0.borrow_mut().push(Rc::new(RefCell::new(InterfaceEntry::HandleScope(handle_scope))));
+        let entry = InterfaceEntry::HandleScope(handle_scope);
+        self.0.stack.borrow_mut().push(Rc::new(RefCell::new(entry)));
     }
 
     fn pop(&self) {
-        self.0.borrow_mut().pop();
+        self.0.stack.borrow_mut().pop();
+    }
+
+    fn set_slot<T: 'static>(&self, value: T) {
+        self.top(|entry| entry.set_slot(value));
     }
 
     fn use_slot<F, T: 'static, U>(&self, func: F) -> U
@@ -336,14 +1036,15 @@ impl Interface {
     where
         F: FnOnce(&mut InterfaceEntry) -> T,
     {
-        let top = self.0.borrow().last().unwrap().clone();
+        let top = self.0.stack.borrow().last().unwrap().clone();
         let mut top_mut = top.borrow_mut();
         func(&mut top_mut)
     }
 }
 
 enum InterfaceEntry {
-    Isolate(v8::OwnedIsolate),
+    // Fields are dropped in order, so the isolate is always gone by the time `LiveIsolate` is.
+    Isolate(v8::OwnedIsolate, LiveIsolate),
     HandleScope(*mut v8::HandleScope<'static>),
 }
 
@@ -353,7 +1054,7 @@ impl InterfaceEntry {
         F: FnOnce(&mut v8::ContextScope<v8::HandleScope>) -> T,
     {
         match self {
-            InterfaceEntry::Isolate(isolate) => {
+            InterfaceEntry::Isolate(isolate, _) => {
                 let global_context = isolate.get_slot::<Global>().unwrap().context.clone();
                 let scope = &mut v8::HandleScope::new(isolate);
                 let context = v8::Local::new(scope, global_context);
@@ -368,9 +1069,19 @@ impl InterfaceEntry {
         }
     }
 
+    fn set_slot<T: 'static>(&mut self, value: T) {
+        match self {
+            InterfaceEntry::Isolate(isolate, _) => isolate.set_slot(value),
+            InterfaceEntry::HandleScope(ref ptr) => {
+                let scope: &mut v8::HandleScope = unsafe { &mut **ptr };
+                scope.set_slot(value)
+            },
+        };
+    }
+
     fn get_slot<T: 'static>(&self) -> &T {
         match self {
-            InterfaceEntry::Isolate(isolate) => isolate.get_slot::<T>().unwrap(),
+            InterfaceEntry::Isolate(isolate, _) => isolate.get_slot::<T>().unwrap(),
             InterfaceEntry::HandleScope(ref ptr) => {
                 let scope: &mut v8::HandleScope = unsafe { &mut **ptr };
                 scope.get_slot::<T>().unwrap()
@@ -380,7 +1091,7 @@ impl InterfaceEntry {
 
     fn isolate_handle(&self) -> v8::IsolateHandle {
         match self {
-            InterfaceEntry::Isolate(isolate) => isolate.thread_safe_handle(),
+            InterfaceEntry::Isolate(isolate, _) => isolate.thread_safe_handle(),
             InterfaceEntry::HandleScope(ref ptr) => {
                 let scope: &mut v8::HandleScope = unsafe { &mut **ptr };
                 scope.thread_safe_handle()
@@ -391,32 +1102,331 @@ impl InterfaceEntry {
 
 struct Global {
     context: v8::Global<v8::Context>,
+    intrinsics: Rc<Intrinsics>,
 }
 
-static INIT: Once = Once::new();
+// Built-in functions captured before any script has had the chance to run, so that operations
+// implemented on top of them can't be affected by scripts tampering with the global object or with
+// prototypes.
+pub(crate) struct Intrinsics {
+    pub(crate) array_slice: v8::Global<v8::Function>,
+    pub(crate) array_sort: v8::Global<v8::Function>,
+    pub(crate) array_splice: v8::Global<v8::Function>,
+    pub(crate) array_values: v8::Global<v8::Function>,
+    #[cfg(feature = "intl")]
+    pub(crate) date_time_format: v8::Global<v8::Function>,
+    #[cfg(feature = "intl")]
+    pub(crate) date_time_format_format: v8::Global<v8::Function>,
+    pub(crate) function_to_string: v8::Global<v8::Function>,
+    #[cfg(feature = "intl")]
+    pub(crate) number_format: v8::Global<v8::Function>,
+    #[cfg(feature = "intl")]
+    pub(crate) number_format_format: v8::Global<v8::Function>,
+    pub(crate) regexp: v8::Global<v8::Function>,
+    pub(crate) regexp_test: v8::Global<v8::Function>,
+    pub(crate) string_concat: v8::Global<v8::Function>,
+    pub(crate) string_replace: v8::Global<v8::Function>,
+    pub(crate) symbol_key_for: v8::Global<v8::Function>,
+}
 
-fn initialize_v8() {
-    INIT.call_once(|| {
-        let platform = v8::new_default_platform(0, false).make_shared();
-        v8::V8::initialize_platform(platform);
-        v8::V8::initialize();
-    });
+impl Intrinsics {
+    // Captures the built-in functions from the current context, or returns `None` if any of them
+    // is missing (which can only happen in a context created from a snapshot whose initialization
+    // script tampered with them, see `MiniV8::create_snapshot`).
+    pub(crate) fn capture(scope: &mut v8::HandleScope) -> Option<Intrinsics> {
+        Some(Intrinsics {
+            array_slice: get_intrinsic(scope, &["Array", "prototype", "slice"])?,
+            array_sort: get_intrinsic(scope, &["Array", "prototype", "sort"])?,
+            array_splice: get_intrinsic(scope, &["Array", "prototype", "splice"])?,
+            array_values: get_intrinsic(scope, &["Array", "prototype", "values"])?,
+            #[cfg(feature = "intl")]
+            date_time_format: get_intrinsic(scope, &["Intl", "DateTimeFormat"])?,
+            #[cfg(feature = "intl")]
+            date_time_format_format:
+                get_intrinsic_getter(scope, &["Intl", "DateTimeFormat", "prototype"], "format")?,
+            function_to_string: get_intrinsic(scope, &["Function", "prototype", "toString"])?,
+            #[cfg(feature = "intl")]
+            number_format: get_intrinsic(scope, &["Intl", "NumberFormat"])?,
+            #[cfg(feature = "intl")]
+            number_format_format:
+                get_intrinsic_getter(scope, &["Intl", "NumberFormat", "prototype"], "format")?,
+            regexp: get_intrinsic(scope, &["RegExp"])?,
+            regexp_test: get_intrinsic(scope, &["RegExp", "prototype", "test"])?,
+            string_concat: get_intrinsic(scope, &["String", "prototype", "concat"])?,
+            string_replace: get_intrinsic(scope, &["String", "prototype", "replace"])?,
+            symbol_key_for: get_intrinsic(scope, &["Symbol", "keyFor"])?,
+        })
+    }
+
+    // Returns `true` if the current context's built-in functions are still the captured ones.
+    pub(crate) fn unchanged(&self, scope: &mut v8::HandleScope) -> bool {
+        match Intrinsics::capture(scope) {
+            Some(current) => self.functions().eq(current.functions()),
+            None => false,
+        }
+    }
+
+    fn functions(&self) -> impl Iterator<Item = &v8::Global<v8::Function>> {
+        let functions = [
+            &self.array_slice,
+            &self.array_sort,
+            &self.array_splice,
+            &self.array_values,
+            &self.function_to_string,
+            &self.regexp,
+            &self.regexp_test,
+            &self.string_concat,
+            &self.string_replace,
+            &self.symbol_key_for,
+        ];
+        #[cfg(feature = "intl")]
+        let functions = functions.into_iter().chain([
+            &self.date_time_format,
+            &self.date_time_format_format,
+            &self.number_format,
+            &self.number_format_format,
+        ]);
+        functions.into_iter()
+    }
+}
+
+fn get_intrinsic(scope: &mut v8::HandleScope, path: &[&str]) -> Option<v8::Global<v8::Function>> {
+    let value = get_path(scope, path)?;
+    let function: v8::Local<v8::Function> = value.try_into().ok()?;
+    Some(v8::Global::new(scope, function))
+}
+
+// Returns the getter of the accessor property `name` of the object at `path`.
+#[cfg(feature = "intl")]
+fn get_intrinsic_getter(
+    scope: &mut v8::HandleScope,
+    path: &[&str],
+    name: &str,
+) -> Option<v8::Global<v8::Function>> {
+    let object: v8::Local<v8::Object> = get_path(scope, path)?.try_into().ok()?;
+    let key = create_string(scope, name);
+    let descriptor: v8::Local<v8::Object> =
+        object.get_own_property_descriptor(scope, key.into())?.try_into().ok()?;
+    let key = create_string(scope, "get");
+    let getter: v8::Local<v8::Function> = descriptor.get(scope, key.into())?.try_into().ok()?;
+    Some(v8::Global::new(scope, getter))
 }
 
-fn initialize_slots(isolate: &mut v8::Isolate) {
+fn get_path<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    path: &[&str],
+) -> Option<v8::Local<'s, v8::Value>> {
+    let mut value: v8::Local<v8::Value> = scope.get_current_context().global(scope).into();
+    for segment in path {
+        let object: v8::Local<v8::Object> = value.try_into().ok()?;
+        let key = create_string(scope, segment);
+        value = object.get(scope, key.into())?;
+    }
+    Some(value)
+}
+
+// The process-wide state of V8, which is initialized when the first `MiniV8` is created and can be
+// torn down once with `dispose`, after which V8 can never be initialized again.
+enum V8State {
+    Uninitialized,
+    Initialized { live_isolates: usize },
+    Disposed,
+}
+
+static V8_STATE: Mutex<V8State> = Mutex::new(V8State::Uninitialized);
+
+/// Disposes of V8 and tears down its platform, releasing all of the process-wide resources held by
+/// V8. This is never necessary, but gives control over when teardown happens (e.g. before the
+/// process exits, rather than racing with it). Disposing more than once does nothing.
+///
+/// V8 cannot be reinitialized after it has been disposed, so no `MiniV8` can be created afterward
+/// (see `MiniV8Builder::try_build`).
+///
+/// # Panics
+///
+/// Panics if any `MiniV8` is still alive (that is, if any `MiniV8` or any value created by one has
+/// not been dropped yet).
+pub fn dispose() {
+    let mut state = V8_STATE.lock().unwrap();
+    if let V8State::Initialized { live_isolates } = *state {
+        if live_isolates > 0 {
+            // Unlock first so as not to poison the lock for the isolates that are still alive:
+            drop(state);
+            panic!("cannot dispose of V8 while {} MiniV8(s) are alive", live_isolates);
+        }
+        unsafe { v8::V8::dispose(); }
+        v8::V8::dispose_platform();
+    }
+    *state = V8State::Disposed;
+}
+
+// The flags V8 is initialized with, which snapshots also depend on.
+pub(crate) const V8_FLAGS: &str = "--harmony-import-assertions";
+
+// Initializes V8 if necessary, and records the creation of a new isolate. Returns
+// `Error::Disposed` (rather than panicking, which would poison the state's lock) if V8 has been
+// disposed.
+pub(crate) fn initialize_v8() -> Result<LiveIsolate> {
+    let mut state = V8_STATE.lock().unwrap();
+    match *state {
+        V8State::Uninitialized => {
+            v8::V8::set_flags_from_string(V8_FLAGS);
+            let platform = v8::new_default_platform(0, true).make_shared();
+            v8::V8::initialize_platform(platform);
+            v8::V8::initialize();
+            *state = V8State::Initialized { live_isolates: 1 };
+        },
+        V8State::Initialized { ref mut live_isolates } => *live_isolates += 1,
+        V8State::Disposed => return Err(Error::Disposed),
+    }
+    Ok(LiveIsolate(()))
+}
+
+// Records the destruction of an isolate when dropped. This must be dropped after the isolate
+// itself.
+pub(crate) struct LiveIsolate(());
+
+impl Drop for LiveIsolate {
+    fn drop(&mut self) {
+        if let V8State::Initialized { ref mut live_isolates } = *V8_STATE.lock().unwrap() {
+            *live_isolates -= 1;
+        }
+    }
+}
+
+fn initialize_slots(isolate: &mut v8::Isolate, builder: &MiniV8Builder) -> Result<()> {
     let scope = &mut v8::HandleScope::new(isolate);
     let context = v8::Context::new(scope);
     let scope = &mut v8::ContextScope::new(scope, context);
     let global_context = v8::Global::new(scope, context);
-    scope.set_slot(Global { context: global_context });
+    let intrinsics = Rc::new(Intrinsics::capture(scope).ok_or(Error::InvalidSnapshot)?);
+    if let Some(ref allowlist) = builder.global_allowlist {
+        restrict_globals(scope, allowlist);
+    }
+    scope.set_slot(Global { context: global_context, intrinsics });
     scope.set_slot(AnyMap(Rc::new(RefCell::new(BTreeMap::new()))));
+    scope.set_slot(Callbacks::default());
+    scope.set_slot(Modules::default());
+    scope.set_slot(ValueCache::default());
+    scope.set_slot(ReentrancyLimit(Cell::new(DEFAULT_MAX_REENTRANCY_DEPTH)));
+    scope.set_slot(Regexes::default());
+    scope.set_slot(Scripts::default());
+    #[cfg(feature = "intl")]
+    scope.set_slot(Formatters::default());
+    scope.set_slot(CoverageSlot::default());
+    scope.set_slot(AsyncTasks::default());
+    scope.set_slot(Interrupts::default());
+    Ok(())
+}
+
+#[cfg(feature = "tracing")]
+extern "C" fn trace_gc_prologue(
+    _isolate: *mut v8::Isolate,
+    gc_type: v8::GCType,
+    flags: v8::GCCallbackFlags,
+    _data: *mut c_void,
+) {
+    // V8 offers no epilogue hook through the `v8` crate, so a collection can only be marked as an
+    // event at its start rather than as a span covering the whole pause.
+    tracing::debug!(target: "mini_v8::gc", ?gc_type, ?flags, "garbage collection starting");
+}
+
+// The state of the heap limit set with `MiniV8Builder::max_heap_size`, stored (boxed, so that V8
+// can hold a stable pointer to it) in an isolate slot.
+struct HeapLimit {
+    isolate: v8::IsolateHandle,
+    reached: Cell<bool>,
+    max_heap_size: usize,
+}
+
+extern "C" fn near_heap_limit(data: *mut c_void, current_heap_limit: usize, _: usize) -> usize {
+    let heap_limit = unsafe { &*(data as *const HeapLimit) };
+    heap_limit.reached.set(true);
+    heap_limit.isolate.terminate_execution();
+    // V8 aborts the process if the limit isn't raised, so it is raised for as long as it takes
+    // the terminated JavaScript to unwind, and then restored by `heap_limit_reached`:
+    current_heap_limit * 2
+}
+
+// Returns whether the heap limit set with `MiniV8Builder::max_heap_size` was reached, which
+// caused a termination. Once the outermost evaluation reports it, the garbage left behind is
+// collected and the limit is restored.
+fn heap_limit_reached(scope: &mut v8::HandleScope, outermost: bool) -> bool {
+    let (data, max_heap_size) = match scope.get_slot::<Box<HeapLimit>>() {
+        Some(heap_limit) if heap_limit.reached.get() => {
+            if outermost {
+                heap_limit.reached.set(false);
+            }
+            (&**heap_limit as *const HeapLimit as *mut c_void, heap_limit.max_heap_size)
+        },
+        _ => return false,
+    };
+    if outermost {
+        scope.low_memory_notification();
+        scope.remove_near_heap_limit_callback(near_heap_limit, max_heap_size);
+        scope.add_near_heap_limit_callback(near_heap_limit, data);
+    }
+    true
 }
 
-fn create_string<'s>(scope: &mut v8::HandleScope<'s>, value: &str) -> v8::Local<'s, v8::String> {
+fn restrict_globals(scope: &mut v8::HandleScope, allowlist: &[StdString]) {
+    if !allowlist.iter().any(|name| name == "Function") {
+        let source = create_string(scope, r#"
+            [
+                Function.prototype,
+                Object.getPrototypeOf(async function() {}),
+                Object.getPrototypeOf(function*() {}),
+                Object.getPrototypeOf(async function*() {}),
+            ].forEach(function(proto) { delete proto.constructor; });
+        "#);
+        let script = v8::Script::compile(scope, source, None).unwrap();
+        script.run(scope).unwrap();
+    }
+
+    let global = scope.get_current_context().global(scope);
+    let args = v8::GetPropertyNamesArgsBuilder::new()
+        .mode(v8::KeyCollectionMode::OwnOnly)
+        .property_filter(v8::PropertyFilter::ALL_PROPERTIES | v8::PropertyFilter::SKIP_SYMBOLS)
+        .key_conversion(v8::KeyConversionMode::ConvertToString)
+        .build();
+    let names = global.get_own_property_names(scope, args).unwrap();
+    for i in 0..names.length() {
+        let name = names.get_index(scope, i).unwrap();
+        if !allowlist.iter().any(|allowed| *allowed == name.to_rust_string_lossy(scope)) {
+            global.delete(scope, name);
+        }
+    }
+}
+
+pub(crate) fn create_origin<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    origin: &ScriptOrigin,
+    is_module: bool,
+) -> v8::ScriptOrigin<'s> {
+    let name = create_string(scope, &origin.name).into();
+    let source_map_url = create_string(scope, "").into();
+    v8::ScriptOrigin::new(
+        scope,
+        name,
+        origin.line_offset,
+        origin.column_offset,
+        false,
+        0,
+        source_map_url,
+        true,
+        false,
+        is_module,
+    )
+}
+
+pub(crate) fn create_string<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: &str,
+) -> v8::Local<'s, v8::String> {
     v8::String::new(scope, value).expect("string exceeds maximum length")
 }
 
-fn add_finalizer<T: 'static>(
+pub(crate) fn add_finalizer<T: 'static>(
     isolate: &mut v8::Isolate,
     handle: impl v8::Handle<Data = T>,
     finalizer: impl FnOnce() + 'static,
@@ -433,15 +1443,289 @@ fn add_finalizer<T: 'static>(
     rc.replace(Some(weak));
 }
 
-type Callback = Box<dyn Fn(&MiniV8, Value, Values) -> Result<Value>>;
+type Callback =
+    Box<dyn for<'s> Fn(Invocation, &mut v8::HandleScope<'s>) -> Result<v8::Local<'s, v8::Value>>>;
+
+// A function's callback, shared between the function and the `Callbacks` registry so that the
+// callback can be dropped (and the function neutralized) when its `MiniV8` is torn down, even if
+// the function itself outlives the teardown.
+type SharedCallback = Rc<RefCell<Option<Callback>>>;
 
+// The data of a function created by `MiniV8::create_function`. The interface is only referenced
+// weakly, since the function would otherwise keep its own isolate alive.
 struct CallbackInfo {
-    mv8: MiniV8,
-    callback: Callback,
+    interface: Weak<InterfaceStack>,
+    callback: SharedCallback,
+}
+
+// The callbacks of all functions created by `MiniV8::create_function` that haven't been garbage
+// collected yet, along with where they were created (see `MiniV8::leak_check`), stored in an
+// isolate slot.
+#[derive(Default)]
+struct Callbacks(RefCell<Vec<(WeakCallback, &'static Location<'static>)>>);
+
+type WeakCallback = Weak<RefCell<Option<Callback>>>;
+
+impl Callbacks {
+    fn register(&self, callback: &SharedCallback, location: &'static Location<'static>) {
+        let mut callbacks = self.0.borrow_mut();
+        // Forget about garbage collected functions every time the registry doubles in size, which
+        // keeps registration amortized constant time:
+        if callbacks.len() == callbacks.capacity() {
+            callbacks.retain(|(c, _)| c.strong_count() > 0);
+        }
+        callbacks.push((Rc::downgrade(callback), location));
+    }
+
+    fn locations(&self) -> Vec<&'static Location<'static>> {
+        self.0.borrow().iter()
+            .filter(|(c, _)| c.strong_count() > 0)
+            .map(|(_, location)| *location)
+            .collect()
+    }
+
+    // Takes the callbacks of all remaining functions, after which calling any of the functions
+    // throws an exception.
+    fn take_all(&self) -> Vec<Callback> {
+        self.0.borrow_mut().drain(..)
+            .filter_map(|(c, _)| c.upgrade())
+            .filter_map(|c| c.borrow_mut().take())
+            .collect()
+    }
 }
 
 struct AnyMap(Rc<RefCell<BTreeMap<StdString, Box<dyn Any>>>>);
 
+// The values cached by `MiniV8::cached_value`. These are kept as raw V8 handles rather than as
+// `Value`s, which would keep the isolate that owns this cache alive.
+#[derive(Default)]
+struct ValueCache(RefCell<HashMap<&'static str, v8::Global<v8::Value>>>);
+
+// The size of the global handles in use once a `MiniV8` has been built, which
+// `MiniV8::leak_check` doesn't report as leaked.
+struct GlobalHandlesBaseline(usize);
+
+const DEFAULT_MAX_REENTRANCY_DEPTH: u32 = 256;
+
+// The number of values that `MiniV8::create_array_from` and `MiniV8::create_object_from` convert
+// before setting them.
+const BATCH_SIZE: usize = 1024;
+
+// The limit set by `MiniV8::set_max_reentrancy_depth`.
+struct ReentrancyLimit(Cell<u32>);
+
+/// A builder for a customized `MiniV8`, created with `MiniV8::builder`.
+#[derive(Clone, Debug, Default)]
+pub struct MiniV8Builder {
+    capture_stack_traces: Option<usize>,
+    global_allowlist: Option<Vec<StdString>>,
+    max_heap_size: Option<usize>,
+    sandbox: Option<SandboxOptions>,
+}
+
+impl MiniV8Builder {
+    /// Makes V8 capture up to `frames` frames of the stack trace of every error object when it is
+    /// created, which `Error::js_error` reports as `JsError::frames` (and formats as
+    /// `JsError::stack`). This is off by default, since it adds to the cost of creating any error
+    /// object, including those that scripts create and catch themselves.
+    pub fn capture_stack_traces(mut self, frames: usize) -> MiniV8Builder {
+        self.capture_stack_traces = Some(frames);
+        self
+    }
+
+    /// Restricts the global object of the new context to the given names. Every other property of
+    /// the global object (e.g. `Date`, `Function`, `WebAssembly`, `Atomics`, `Intl`) is deleted
+    /// before any script can run, so it is absent rather than merely shadowed.
+    ///
+    /// If `Function` is not allowed, the `constructor` properties of the prototypes of ordinary,
+    /// async, generator, and async generator functions are deleted as well, so that escapes like
+    /// `(() => {}).constructor` or `({}).constructor.constructor` can't reach any of the function
+    /// constructors (they instead resolve to `Object`).
+    ///
+    /// Removing a name from the global object can't make every built-in unreachable:
+    ///
+    /// * `undefined`, `NaN`, and `Infinity` are non-configurable and always remain.
+    /// * Built-ins that are reachable from syntax are still reachable through their instances, e.g.
+    ///   `Object` through `({}).constructor`, `Array` through `[].constructor`, `RegExp` through
+    ///   `/a/.constructor`, `Promise` through `(async () => {})().constructor`, and the error
+    ///   constructors through caught exceptions' `constructor`.
+    ///
+    /// The operations of this crate itself are unaffected by the restriction.
+    pub fn global_allowlist(mut self, names: &[&str]) -> MiniV8Builder {
+        self.global_allowlist = Some(names.iter().map(|n| n.to_string()).collect());
+        self
+    }
+
+    /// Limits the size of the JavaScript heap to roughly `bytes`. When the heap approaches the
+    /// limit, the running JavaScript is terminated and the evaluation or function call that is
+    /// running it returns `Error::OutOfMemory`, rather than V8 aborting the process. The `MiniV8`
+    /// remains usable afterwards, as long as the memory is freed once the terminated JavaScript
+    /// is gone (i.e. it wasn't stored anywhere reachable, like a global variable).
+    ///
+    /// The limit covers V8's heap only. Memory allocated outside of it (e.g. the contents of
+    /// `ArrayBuffer`s) doesn't count towards it.
+    pub fn max_heap_size(mut self, bytes: usize) -> MiniV8Builder {
+        self.max_heap_size = Some(bytes);
+        self
+    }
+
+    /// Restricts the global context of the new `MiniV8` as described by `options`. The
+    /// restrictions are applied after those of `MiniV8Builder::global_allowlist`.
+    pub fn sandbox(mut self, options: SandboxOptions) -> MiniV8Builder {
+        self.sandbox = Some(options);
+        self
+    }
+
+    /// Creates the configured `MiniV8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if V8 has been disposed (see `dispose`). Use `MiniV8Builder::try_build` to handle
+    /// that case instead.
+    pub fn build(self) -> MiniV8 {
+        self.try_build().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates the configured `MiniV8`, or returns `Error::Disposed` if V8 has been disposed (see
+    /// `dispose`).
+    pub fn try_build(self) -> Result<MiniV8> {
+        // A context that isn't created from a snapshot always has all of its built-ins, so this
+        // can't fail otherwise:
+        self.build_inner(None)
+    }
+
+    /// Creates the configured `MiniV8` from a snapshot produced by `MiniV8::create_snapshot`, so
+    /// that its global context starts out in the state that the snapshot's initialization script
+    /// left it in. Returns `Error::InvalidSnapshot` if the snapshot was produced by another V8
+    /// version, is corrupt, or lacks built-in functions that `MiniV8` relies on, and
+    /// `Error::Disposed` if V8 has been disposed (see `dispose`).
+    pub fn build_from_snapshot(self, snapshot: &[u8]) -> Result<MiniV8> {
+        let blob = snapshot_blob(snapshot)?;
+        self.build_inner(Some(blob.to_vec()))
+    }
+
+    fn build_inner(self, snapshot: Option<Vec<u8>>) -> Result<MiniV8> {
+        let live = initialize_v8()?;
+        let mut params = v8::CreateParams::default();
+        if let Some(snapshot) = snapshot {
+            params = params.snapshot_blob(snapshot);
+        }
+        if let Some(max_heap_size) = self.max_heap_size {
+            params = params.heap_limits(0, max_heap_size);
+        }
+        let mut isolate = v8::Isolate::new(params);
+        initialize_slots(&mut isolate, &self)?;
+        if let Some(max_heap_size) = self.max_heap_size {
+            let heap_limit = Box::new(HeapLimit {
+                isolate: isolate.thread_safe_handle(),
+                reached: Cell::new(false),
+                max_heap_size,
+            });
+            let data = &*heap_limit as *const HeapLimit as *mut c_void;
+            isolate.add_near_heap_limit_callback(near_heap_limit, data);
+            isolate.set_slot(heap_limit);
+        }
+        isolate.set_host_initialize_import_meta_object_callback(initialize_import_meta);
+        if let Some(frames) = self.capture_stack_traces {
+            let frames = frames.min(i32::MAX as usize) as i32;
+            isolate.set_capture_stack_trace_for_uncaught_exceptions(true, frames);
+        }
+        #[cfg(feature = "tracing")]
+        isolate.add_gc_prologue_callback(trace_gc_prologue, std::ptr::null_mut(), v8::GCType::ALL);
+        let interface = Interface::new(isolate, live);
+        interface.set_slot(WeakInterface(Rc::downgrade(&interface.0)));
+        let mv8 = MiniV8 { interface };
+        if let Some(ref sandbox) = self.sandbox {
+            sandbox.apply(&mv8);
+        }
+        let baseline = mv8.used_global_handles_size();
+        mv8.interface.set_slot(GlobalHandlesBaseline(baseline));
+        Ok(mv8)
+    }
+}
+
+/// A handle for interrupting or terminating JavaScript running in a `MiniV8` from another thread,
+/// as returned by `MiniV8::interrupt_handle`.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+    isolate: v8::IsolateHandle,
+    terminated: Arc<Mutex<bool>>,
+    interrupts: std::sync::Weak<Mutex<PendingInterrupts>>,
+}
+
+/// The former name of `InterruptHandle`.
+#[deprecated(note = "renamed to `InterruptHandle`")]
+pub type TerminateHandle = InterruptHandle;
+
+type Interrupt = Box<dyn FnOnce() + Send>;
+
+// The closures passed to `InterruptHandle::request_interrupt` that haven't been called yet, kept
+// in an isolate slot and handed to V8 by id only. Closures whose interrupt never happens (because
+// no JavaScript runs again) are dropped along with the isolate.
+#[derive(Default)]
+struct Interrupts(Arc<Mutex<PendingInterrupts>>);
+
+#[derive(Default)]
+struct PendingInterrupts {
+    next_id: usize,
+    closures: HashMap<usize, Interrupt>,
+}
+
+impl InterruptHandle {
+    /// Terminates the JavaScript that is running in the `MiniV8`, which makes the evaluation or
+    /// function call that is running it return `Error::Terminated`. The `MiniV8` remains usable
+    /// afterwards.
+    ///
+    /// Like `Script::timeout`, termination only takes effect while JavaScript code is running, so
+    /// a Rust function called from JavaScript keeps running until it returns. If no JavaScript is
+    /// running at all, this does nothing.
+    ///
+    /// Returns `false` if the `MiniV8` has already been dropped.
+    pub fn terminate(&self) -> bool {
+        let mut terminated = self.terminated.lock().unwrap();
+        *terminated = true;
+        self.isolate.terminate_execution()
+    }
+
+    /// Interrupts the JavaScript that is running in the `MiniV8` to call `func` on the thread that
+    /// is running it, and then resumes the JavaScript. This can be used to inspect state shared
+    /// with the JavaScript's thread (e.g. to decide whether to `terminate` it) at a point where
+    /// that thread isn't running any Rust code.
+    ///
+    /// `func` must not use the `MiniV8`, since V8 can't be reentered while it is interrupted. If no
+    /// JavaScript is running, `func` is called once JavaScript runs next, and is dropped without
+    /// being called if none does before the `MiniV8` is dropped.
+    ///
+    /// Returns `false` (without calling `func`) if the `MiniV8` has already been dropped.
+    pub fn request_interrupt<F: FnOnce() + Send + 'static>(&self, func: F) -> bool {
+        let interrupts = match self.interrupts.upgrade() {
+            Some(interrupts) => interrupts,
+            None => return false,
+        };
+        let id = {
+            let mut pending = interrupts.lock().unwrap();
+            let id = pending.next_id;
+            pending.next_id += 1;
+            pending.closures.insert(id, Box::new(func));
+            id
+        };
+        if self.isolate.request_interrupt(run_interrupt, id as *mut c_void) {
+            true
+        } else {
+            interrupts.lock().unwrap().closures.remove(&id);
+            false
+        }
+    }
+}
+
+extern "C" fn run_interrupt(isolate: &mut v8::Isolate, data: *mut c_void) {
+    let func = isolate.get_slot::<Interrupts>()
+        .and_then(|interrupts| interrupts.0.lock().unwrap().closures.remove(&(data as usize)));
+    if let Some(func) = func {
+        func();
+    }
+}
+
 // A JavaScript script.
 #[derive(Clone, Debug, Default)]
 pub struct Script {
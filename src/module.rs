@@ -0,0 +1,364 @@
+use crate::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::string::String as StdString;
+
+/// A JavaScript (ECMAScript) module, created with `MiniV8::compile_module`.
+///
+/// A module's `import` declarations are resolved by specifier against the other modules that have
+/// been compiled by the same `MiniV8`.
+#[derive(Clone)]
+pub struct Module {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::Module>,
+    pub(crate) specifier: StdString,
+}
+
+impl Module {
+    /// Returns the specifier that the module was compiled with, which is empty for modules
+    /// evaluated with `MiniV8::eval_module`.
+    pub fn specifier(&self) -> StdString {
+        self.specifier.clone()
+    }
+
+    /// Returns the module's current status.
+    pub fn status(&self) -> ModuleStatus {
+        self.mv8.scope(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            ModuleStatus::from_v8(module.get_status())
+        })
+    }
+
+    /// Returns the exception that caused the module's instantiation or evaluation to fail, or
+    /// `None` if the module's status is not `ModuleStatus::Errored`.
+    pub fn error(&self) -> Option<Value> {
+        self.mv8.scope(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            if module.get_status() != v8::ModuleStatus::Errored {
+                return None;
+            }
+            Some(Value::from_v8_value(&self.mv8, scope, module.get_exception()))
+        })
+    }
+
+    /// Returns the module's requests for other modules (its `import` declarations and
+    /// `export ... from` declarations), in source order. This is available as soon as the module
+    /// is compiled, before it is instantiated.
+    pub fn requests(&self) -> Vec<ModuleRequest> {
+        self.mv8.scope(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            let requests = module.get_module_requests();
+            let mut list = Vec::with_capacity(requests.length());
+            for i in 0..requests.length() {
+                let request: v8::Local<v8::ModuleRequest> =
+                    requests.get(scope, i).unwrap().try_into().unwrap();
+                let specifier = request.get_specifier().to_rust_string_lossy(scope);
+                // Assertions are laid out as `[key1, value1, source_offset1, key2, ...]`:
+                let assertions = request.get_import_assertions();
+                let mut import_assertions = Vec::with_capacity(assertions.length() / 3);
+                for j in (0..assertions.length()).step_by(3) {
+                    let key = fixed_array_string(scope, assertions, j);
+                    let value = fixed_array_string(scope, assertions, j + 1);
+                    import_assertions.push((key, value));
+                }
+                list.push(ModuleRequest { specifier, import_assertions });
+            }
+            list
+        })
+    }
+
+    /// Instantiates the module, resolving its imports (and its imports' imports, and so on).
+    /// Instantiating a module that has already been instantiated does nothing.
+    ///
+    /// Returns an error if an import could not be resolved.
+    pub fn instantiate(&self) -> Result<()> {
+        self.mv8.try_catch(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            if module.get_status() != v8::ModuleStatus::Uninstantiated {
+                return Ok(());
+            }
+            let _ = module.instantiate_module(scope, resolve_module);
+            self.mv8.exception(scope)
+        })
+    }
+
+    /// Evaluates the module, first instantiating it if necessary (see `Module::instantiate`), and
+    /// returns the result of the evaluation.
+    ///
+    /// Returns an error if instantiation fails or if the module throws an exception during its
+    /// evaluation.
+    pub fn evaluate<R: FromValue>(&self) -> Result<R> {
+        self.instantiate()?;
+        self.mv8.try_catch(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            let result = module.evaluate(scope);
+            self.mv8.exception(scope)?;
+            if module.get_status() == v8::ModuleStatus::Errored {
+                let exception = module.get_exception();
+                return Err(Error::Value(Value::from_v8_value(&self.mv8, scope, exception)));
+            }
+            Ok(Value::from_v8_value(&self.mv8, scope, result.unwrap()))
+        }).and_then(|v| v.into(&self.mv8))
+    }
+
+    /// Returns the module's namespace object, which holds its exports. The module is first
+    /// instantiated if necessary (see `Module::instantiate`).
+    pub fn namespace(&self) -> Result<Object> {
+        self.instantiate()?;
+        self.mv8.scope(|scope| {
+            let module = v8::Local::new(scope, self.handle.clone());
+            let namespace = Value::from_v8_value(&self.mv8, scope, module.get_module_namespace());
+            namespace.into(&self.mv8)
+        })
+    }
+}
+
+impl fmt::Debug for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<module {:?}>", self.specifier())
+    }
+}
+
+/// The status of a `Module`.
+///
+/// These correspond to the states used in ECMAScript, except that "evaluated" is split into
+/// `Evaluated` and `Errored`, indicating success and failure respectively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleStatus {
+    /// The module has been compiled but not instantiated.
+    Uninstantiated,
+    /// The module is in the process of being instantiated.
+    Instantiating,
+    /// The module and its dependencies have been instantiated, but not evaluated.
+    Instantiated,
+    /// The module is in the process of being evaluated (e.g. it is waiting on a top-level
+    /// `await`).
+    Evaluating,
+    /// The module has been evaluated successfully.
+    Evaluated,
+    /// The module's instantiation or evaluation failed. See `Module::error`.
+    Errored,
+}
+
+impl ModuleStatus {
+    fn from_v8(status: v8::ModuleStatus) -> ModuleStatus {
+        match status {
+            v8::ModuleStatus::Uninstantiated => ModuleStatus::Uninstantiated,
+            v8::ModuleStatus::Instantiating => ModuleStatus::Instantiating,
+            v8::ModuleStatus::Instantiated => ModuleStatus::Instantiated,
+            v8::ModuleStatus::Evaluating => ModuleStatus::Evaluating,
+            v8::ModuleStatus::Evaluated => ModuleStatus::Evaluated,
+            v8::ModuleStatus::Errored => ModuleStatus::Errored,
+        }
+    }
+}
+
+/// A module's request for another module, e.g. `import { a } from "specifier"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleRequest {
+    /// The specifier of the requested module.
+    pub specifier: StdString,
+    /// The request's import assertions as key-value pairs in source order, e.g.
+    /// `[("type", "json")]` for `import data from "./data.json" assert { type: "json" }`.
+    pub import_assertions: Vec<(StdString, StdString)>,
+}
+
+fn fixed_array_string(
+    scope: &mut v8::HandleScope,
+    array: v8::Local<v8::FixedArray>,
+    index: usize,
+) -> StdString {
+    let data = array.get(scope, index).unwrap();
+    let value: v8::Local<v8::Value> = data.try_into().unwrap();
+    value.to_rust_string_lossy(scope)
+}
+
+type ImportMetaCallback = Rc<dyn Fn(&MiniV8, &Module, &Object) -> Result<()>>;
+type ModuleLoader = Rc<dyn Fn(&MiniV8, &str, &str) -> Result<StdString>>;
+
+// The bookkeeping for all of the modules compiled within an isolate, stored in an isolate slot.
+#[derive(Default)]
+pub(crate) struct Modules {
+    by_specifier: RefCell<HashMap<StdString, v8::Global<v8::Module>>>,
+    // The specifiers of all modules compiled with one, by their script ids, which (unlike
+    // `by_specifier`) includes modules that have since been replaced:
+    specifiers: RefCell<HashMap<i32, StdString>>,
+    pub(crate) import_meta_callback: RefCell<Option<ImportMetaCallback>>,
+    pub(crate) loader: RefCell<Option<ModuleLoader>>,
+}
+
+impl Modules {
+    pub(crate) fn insert(
+        &self,
+        specifier: &str,
+        script_id: Option<i32>,
+        module: v8::Global<v8::Module>,
+    ) {
+        if let Some(script_id) = script_id {
+            self.specifiers.borrow_mut().insert(script_id, specifier.to_string());
+        }
+        self.by_specifier.borrow_mut().insert(specifier.to_string(), module);
+    }
+
+    fn get(&self, specifier: &str) -> Option<v8::Global<v8::Module>> {
+        self.by_specifier.borrow().get(specifier).cloned()
+    }
+
+    // Returns the specifier that a module was compiled with, which is empty if it was compiled
+    // without one.
+    fn specifier_of(&self, module: v8::Local<v8::Module>) -> StdString {
+        let specifiers = self.specifiers.borrow();
+        module.script_id().and_then(|id| specifiers.get(&id).cloned()).unwrap_or_default()
+    }
+}
+
+fn resolve_module<'a>(
+    context: v8::Local<'a, v8::Context>,
+    specifier: v8::Local<'a, v8::String>,
+    _import_assertions: v8::Local<'a, v8::FixedArray>,
+    referrer: v8::Local<'a, v8::Module>,
+) -> Option<v8::Local<'a, v8::Module>> {
+    let scope = &mut unsafe { v8::CallbackScope::new(context) };
+    let specifier = specifier.to_rust_string_lossy(scope);
+    let module = scope.get_slot::<Modules>().unwrap().get(&specifier);
+    if let Some(module) = module {
+        return Some(v8::Local::new(scope, module));
+    }
+
+    let source = match decode_data_url(&specifier) {
+        Some(source) => source,
+        None => load_module(scope, &specifier, referrer)?,
+    };
+    // Any syntax error is thrown by the compilation itself:
+    let module = compile_v8_module(scope, &specifier, &source)?;
+    let handle = v8::Global::new(scope, module);
+    let script_id = module.script_id();
+    scope.get_slot::<Modules>().unwrap().insert(&specifier, script_id, handle);
+    Some(module)
+}
+
+// Loads the source of a module with the module loader, returning `None` if an exception was
+// thrown (including when there is no module loader).
+fn load_module(
+    scope: &mut v8::HandleScope,
+    specifier: &str,
+    referrer: v8::Local<v8::Module>,
+) -> Option<StdString> {
+    let loader = scope.get_slot::<Modules>().unwrap().loader.borrow().clone();
+    let loader = match loader {
+        Some(loader) => loader,
+        None => {
+            let message = format!("unable to resolve module \"{}\"", specifier);
+            let message = create_string(scope, &message);
+            let exception = v8::Exception::error(scope, message);
+            scope.throw_exception(exception);
+            return None;
+        },
+    };
+
+    let referrer = scope.get_slot::<Modules>().unwrap().specifier_of(referrer);
+    let mv8 = MiniV8::from_callback_scope(scope);
+    mv8.enter_callback(scope, |scope| match loader(&mv8, &referrer, specifier) {
+        Ok(source) => Some(source),
+        Err(e) => {
+            let exception = e.to_value(&mv8).to_v8_value(scope);
+            scope.throw_exception(exception);
+            None
+        },
+    })
+}
+
+// Compiles a module, returning `None` if an exception was thrown.
+pub(crate) fn compile_v8_module<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    specifier: &str,
+    source: &str,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let source = create_string(scope, source);
+    let origin = ScriptOrigin { name: specifier.to_string(), ..Default::default() };
+    let origin = create_origin(scope, &origin, true);
+    let source = v8::script_compiler::Source::new(source, Some(&origin));
+    v8::script_compiler::compile_module(scope, source)
+}
+
+// Decodes the JavaScript source held by a `data:` URL, returning `None` if `url` isn't a valid
+// `data:` URL of JavaScript source.
+fn decode_data_url(url: &str) -> Option<StdString> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let (media_type, base64) = match header.strip_suffix(";base64") {
+        Some(media_type) => (media_type, true),
+        None => (header, false),
+    };
+    let mime_type = media_type.split(';').next().unwrap().trim().to_ascii_lowercase();
+    if !matches!(mime_type.as_str(), "" | "text/javascript" | "application/javascript") {
+        return None;
+    }
+    let bytes = percent_decode(data)?;
+    let bytes = if base64 { decode_base64(&bytes)? } else { bytes };
+    StdString::from_utf8(bytes).ok()
+}
+
+fn percent_decode(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut iter = data.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let high = (iter.next()? as char).to_digit(16)?;
+            let low = (iter.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    Some(bytes)
+}
+
+fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let (mut bits, mut len) = (0u32, 0);
+    for &byte in data.iter().filter(|b| !b.is_ascii_whitespace() && **b != b'=') {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | value as u32;
+        len += 6;
+        if len >= 8 {
+            len -= 8;
+            bytes.push((bits >> len) as u8);
+            bits &= (1 << len) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+pub(crate) extern "C" fn initialize_import_meta(
+    context: v8::Local<v8::Context>,
+    module: v8::Local<v8::Module>,
+    meta: v8::Local<v8::Object>,
+) {
+    let scope = &mut unsafe { v8::CallbackScope::new(context) };
+    let callback = scope.get_slot::<Modules>().unwrap().import_meta_callback.borrow().clone();
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return,
+    };
+
+    let mv8 = MiniV8::from_callback_scope(scope);
+    let specifier = scope.get_slot::<Modules>().unwrap().specifier_of(module);
+    let handle = v8::Global::new(scope, module);
+    let module = Module { mv8: mv8.clone(), handle, specifier };
+    let meta = Object { mv8: mv8.clone(), handle: v8::Global::new(scope, meta) };
+    mv8.enter_callback(scope, |scope| {
+        if let Err(e) = callback(&mv8, &module, &meta) {
+            let exception = e.to_value(&mv8).to_v8_value(scope);
+            scope.throw_exception(exception);
+        }
+    });
+}
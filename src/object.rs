@@ -1,6 +1,8 @@
 use crate::*;
 use std::fmt;
 use std::marker::PhantomData;
+use std::ops::BitOr;
+use std::string::String as StdString;
 
 #[derive(Clone)]
 pub struct Object {
@@ -41,6 +43,152 @@ impl Object {
         })
     }
 
+    /// Defines (or redefines) an own property of the object with the given key, like JavaScript's
+    /// `Object.defineProperty`. This can define properties that `Object::set` can't, such as
+    /// read-only or non-enumerable data properties, and accessor properties whose getter and setter
+    /// are Rust closures wrapped with `MiniV8::create_function`.
+    ///
+    /// Returns `false` if the property could not be defined, e.g. because a non-configurable
+    /// property already exists with the key, or because the object is frozen.
+    ///
+    /// Returns an error if `ToValue::to_value` fails for the key or if the key value could not be
+    /// cast to a property key string.
+    pub fn define_property<K>(&self, key: K, descriptor: PropertyDescriptor) -> Result<bool>
+    where
+        K: ToValue,
+    {
+        let key = key.to_value(&self.mv8)?;
+        match descriptor {
+            PropertyDescriptor::Data { ref value, .. } => self.mv8.assert_owns(value)?,
+            PropertyDescriptor::Accessor { ref get, ref set, .. } => {
+                for function in get.iter().chain(set) {
+                    self.mv8.assert_owns(&Value::Function(function.clone()))?;
+                }
+            },
+        }
+        self.mv8.try_catch(|scope| {
+            let key = self.property_name(scope, &key)?;
+            let (mut v8_descriptor, enumerable, configurable) = match descriptor {
+                PropertyDescriptor::Data { value, writable, enumerable, configurable } => {
+                    let value = value.to_v8_value(scope);
+                    let descriptor =
+                        v8::PropertyDescriptor::new_from_value_writable(value, writable);
+                    (descriptor, enumerable, configurable)
+                },
+                PropertyDescriptor::Accessor { get, set, enumerable, configurable } => {
+                    let get = get.map_or(Value::Undefined, Value::Function).to_v8_value(scope);
+                    let set = set.map_or(Value::Undefined, Value::Function).to_v8_value(scope);
+                    let descriptor = v8::PropertyDescriptor::new_from_get_set(get, set);
+                    (descriptor, enumerable, configurable)
+                },
+            };
+            v8_descriptor.set_enumerable(enumerable);
+            v8_descriptor.set_configurable(configurable);
+            let object = v8::Local::new(scope, self.handle.clone());
+            let defined = object.define_property(scope, key, &v8_descriptor);
+            self.mv8.exception(scope)?;
+            Ok(defined.unwrap())
+        })
+    }
+
+    /// Sets an own data property of the object with the given key, value and attributes, e.g. to
+    /// bind a value that scripts can't overwrite. Unlike `Object::set`, this defines the property
+    /// on the object itself without calling any setter on the object or its prototype chain.
+    ///
+    /// Returns `false` if the property could not be defined, e.g. because a non-configurable
+    /// property already exists with the key, or because the object is frozen.
+    ///
+    /// Returns an error if `ToValue::to_value` fails for either the key or the value or if the key
+    /// value could not be cast to a property key string.
+    pub fn set_with_attributes<K, V>(
+        &self,
+        key: K,
+        value: V,
+        attributes: PropertyAttributes,
+    ) -> Result<bool>
+    where
+        K: ToValue,
+        V: ToValue,
+    {
+        let key = key.to_value(&self.mv8)?;
+        let value = value.to_value(&self.mv8)?;
+        self.mv8.try_catch(|scope| {
+            let key = self.property_name(scope, &key)?;
+            let value = value.to_v8_value(scope);
+            let object = v8::Local::new(scope, self.handle.clone());
+            let mut v8_attributes = v8::PropertyAttribute::NONE;
+            for (flag, v8_flag) in [
+                (PropertyAttributes::READ_ONLY, v8::PropertyAttribute::READ_ONLY),
+                (PropertyAttributes::DONT_ENUM, v8::PropertyAttribute::DONT_ENUM),
+                (PropertyAttributes::DONT_DELETE, v8::PropertyAttribute::DONT_DELETE),
+            ] {
+                if attributes.contains(flag) {
+                    v8_attributes = v8_attributes | v8_flag;
+                }
+            }
+            let defined = object.define_own_property(scope, key, value, v8_attributes);
+            self.mv8.exception(scope)?;
+            Ok(defined.unwrap())
+        })
+    }
+
+    /// Freezes the object, like JavaScript's `Object.freeze`: its properties can no longer be
+    /// added, removed or reassigned, and their descriptors can't be changed.
+    ///
+    /// Returns an error if the object can't be frozen, e.g. because it is a proxy that refuses.
+    pub fn freeze(&self) -> Result<()> {
+        self.set_integrity_level(v8::IntegrityLevel::Frozen)
+    }
+
+    /// Seals the object, like JavaScript's `Object.seal`: its properties can no longer be added
+    /// or removed, and their descriptors can't be changed, but writable properties can still be
+    /// reassigned.
+    ///
+    /// Returns an error if the object can't be sealed, e.g. because it is a proxy that refuses.
+    pub fn seal(&self) -> Result<()> {
+        self.set_integrity_level(v8::IntegrityLevel::Sealed)
+    }
+
+    fn set_integrity_level(&self, level: v8::IntegrityLevel) -> Result<()> {
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            object.set_integrity_level(scope, level);
+            self.mv8.exception(scope)
+        })
+    }
+
+    /// Registers a function to be called once the object has been garbage collected, or when the
+    /// `MiniV8` is dropped if the object is still alive by then, e.g. to release a native resource
+    /// associated with the object. Every registered function is called exactly once, but there is
+    /// no telling when garbage collection will happen.
+    ///
+    /// The function is called while the object is being collected (or the `MiniV8` torn down), so
+    /// it must not use the `MiniV8`.
+    pub fn set_finalizer<F: FnOnce() + 'static>(&self, func: F) {
+        self.mv8.scope(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            add_finalizer(scope, object, func);
+        });
+    }
+
+    // Converts a key to a property name, which involves calling `toString` on keys that are
+    // neither strings nor symbols.
+    fn property_name<'s>(
+        &self,
+        scope: &mut v8::TryCatch<'s, v8::HandleScope>,
+        key: &Value,
+    ) -> Result<v8::Local<'s, v8::Name>> {
+        let key = key.to_v8_value(scope);
+        match key.try_into() {
+            Ok(name) => Ok(name),
+            Err(_) => {
+                let key = key.to_string(scope);
+                self.mv8.exception(scope)?;
+                Ok(key.unwrap().into())
+            },
+        }
+    }
+
     /// Removes the property associated with the given key from the object. This function does
     /// nothing if the property does not exist.
     ///
@@ -71,6 +219,119 @@ impl Object {
         })
     }
 
+    /// Gets an object property value using the given integer key, like `Object::get` does but
+    /// without converting the key into a JavaScript value first. This is faster when treating
+    /// objects as integer-indexed maps.
+    pub fn get_index<V: FromValue>(&self, index: u32) -> Result<V> {
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let result = object.get_index(scope, index);
+            self.mv8.exception(scope)?;
+            Ok(Value::from_v8_value(&self.mv8, scope, result.unwrap()))
+        }).and_then(|v| v.into(&self.mv8))
+    }
+
+    /// Sets an object property using the given integer key and value, like `Object::set` does but
+    /// without converting the key into a JavaScript value first.
+    pub fn set_index<V: ToValue>(&self, index: u32, value: V) -> Result<()> {
+        let value = value.to_value(&self.mv8)?;
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let value = value.to_v8_value(scope);
+            object.set_index(scope, index, value);
+            self.mv8.exception(scope)
+        })
+    }
+
+    /// Returns `true` if the given integer key is a property of the object, `false` otherwise, like
+    /// `Object::has` does but without converting the key into a JavaScript value first.
+    pub fn has_index(&self, index: u32) -> Result<bool> {
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let has = object.has_index(scope, index);
+            self.mv8.exception(scope)?;
+            Ok(has.unwrap())
+        })
+    }
+
+    /// Returns the kind of the property associated with the given key, searching the object and its
+    /// prototype chain like `Object::get` does, but without running any JavaScript: Getters are
+    /// never called, and proxy traps are never run.
+    ///
+    /// A property reached through a proxy is reported as `PropertyKind::Accessor`, since its value
+    /// can only be determined by running the proxy's traps.
+    ///
+    /// Returns an error if `ToValue::to_value` fails for the key or if the key value could not be
+    /// cast to a property key string.
+    pub fn property_kind<K: ToValue>(&self, key: K) -> Result<PropertyKind> {
+        Ok(self.lookup_data_property(key)?.0)
+    }
+
+    /// Get the value of a plain data property using the given key, searching the object and its
+    /// prototype chain like `Object::get` does, but without running any JavaScript. Returns `None`
+    /// if the property is missing or if it is an accessor (see `Object::property_kind`), in which
+    /// case its getter is not called.
+    ///
+    /// This is suitable for inspecting untrusted objects without side effects.
+    ///
+    /// Returns an error if `ToValue::to_value` fails for the key or if the key value could not be
+    /// cast to a property key string.
+    pub fn get_data_property<K: ToValue>(&self, key: K) -> Result<Option<Value>> {
+        Ok(self.lookup_data_property(key)?.1)
+    }
+
+    /// Get an object property value using a key that is matched against the object's own property
+    /// keys under the given normalization, returning the actual matching key alongside the value.
+    /// Returns `None` if no key matches. This is useful for reading user-supplied objects with
+    /// inconsistently spelled keys, and the returned key can be compared against `key` to warn
+    /// about such inconsistencies.
+    ///
+    /// Returns an `Error::AmbiguousKey` listing the matching keys if more than one key matches
+    /// (even if one of them matches exactly), or an error if `FromValue::from_value` fails for the
+    /// value.
+    pub fn get_normalized<V: FromValue>(
+        &self,
+        key: &str,
+        normalization: KeyNormalization,
+    ) -> Result<Option<(StdString, V)>> {
+        let normalized = normalization.normalize(key);
+        let mut matches = Vec::new();
+        for k in self.keys(false)?.elements::<Value>() {
+            let k = k?.coerce_string(&self.mv8)?.to_string();
+            if normalization.normalize(&k) == normalized {
+                matches.push(k);
+            }
+        }
+
+        if matches.len() > 1 {
+            return Err(Error::AmbiguousKey { key: key.to_string(), matches });
+        }
+        match matches.pop() {
+            Some(k) => {
+                let value = self.get(k.as_str())?;
+                Ok(Some((k, value)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns `true` if the object is an instance of the given constructor, like JavaScript's
+    /// `instanceof` operator: The constructor's `Symbol.hasInstance` method decides if it has one,
+    /// and otherwise the object's prototype chain is searched for the constructor's `prototype`.
+    ///
+    /// Returns an error if `Symbol.hasInstance` throws, or if the constructor's `prototype` is not
+    /// an object.
+    pub fn instance_of(&self, constructor: &Function) -> Result<bool> {
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let constructor: v8::Local<v8::Object> =
+                v8::Local::new(scope, constructor.handle.clone()).into();
+            let result = object.instance_of(scope, constructor);
+            self.mv8.exception(scope)?;
+            Ok(result.unwrap())
+        })
+    }
+
     /// Calls the function at the key with the given arguments, with `this` set to the object.
     /// Returns an error if the value at the key is not a function.
     pub fn call_prop<K, A, R>(&self, key: K, args: A) -> Result<R>
@@ -85,9 +346,13 @@ impl Object {
 
     /// Returns an array containing all of this object's enumerable property keys. If
     /// `include_inherited` is `false`, then only the object's own enumerable properties will be
-    /// collected (similar to `Object.getOwnPropertyNames` in Javascript). If `include_inherited` is
-    /// `true`, then the object's own properties and the enumerable properties from its prototype
-    /// chain will be collected.
+    /// collected (similar to `Object.keys` in Javascript). If `include_inherited` is `true`, then
+    /// the object's own properties and the enumerable properties from its prototype chain will be
+    /// collected (similar to a `for-in` loop).
+    ///
+    /// Symbol-keyed and non-enumerable properties (such as an array's `length`) are never
+    /// collected. Integer index keys, such as the indices of an array's elements, are collected
+    /// as numbers rather than strings, and come first in ascending order (as in JavaScript).
     pub fn keys(&self, include_inherited: bool) -> Result<Array> {
         self.mv8.try_catch(|scope| {
             let object = v8::Local::new(scope, self.handle.clone());
@@ -104,6 +369,49 @@ impl Object {
         })
     }
 
+    /// Returns the number of the object's own enumerable string-keyed properties, like
+    /// `Object.keys(object).length` in JavaScript. Properties inherited from the prototype chain
+    /// are not counted. See `Object::len_with` to count other kinds of properties.
+    ///
+    /// The keys are only collected within V8, and never converted into Rust values.
+    pub fn len(&self) -> Result<u32> {
+        self.len_with(KeyCollection::default())
+    }
+
+    /// Returns `true` if the object has no own enumerable string-keyed properties, like
+    /// `Object.keys(object).length === 0` in JavaScript. See `Object::len`.
+    pub fn is_empty(&self) -> Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Returns the number of the object's own properties that are selected by `collection`.
+    pub fn len_with(&self, collection: KeyCollection) -> Result<u32> {
+        self.mv8.try_catch(|scope| {
+            let object = v8::Local::new(scope, self.handle.clone());
+            let mut property_filter = v8::PropertyFilter::ALL_PROPERTIES;
+            if !collection.include_non_enumerable {
+                property_filter = property_filter | v8::PropertyFilter::ONLY_ENUMERABLE;
+            }
+            if !collection.include_symbols {
+                property_filter = property_filter | v8::PropertyFilter::SKIP_SYMBOLS;
+            }
+            let args = v8::GetPropertyNamesArgs {
+                mode: v8::KeyCollectionMode::OwnOnly,
+                property_filter,
+                index_filter: v8::IndexFilter::IncludeIndices,
+                key_conversion: v8::KeyConversionMode::NoNumbers,
+            };
+            let keys = object.get_own_property_names(scope, args);
+            self.mv8.exception(scope)?;
+            Ok(keys.unwrap().length())
+        })
+    }
+
+    /// Returns `true` if the object has no own properties that are selected by `collection`.
+    pub fn is_empty_with(&self, collection: KeyCollection) -> Result<bool> {
+        self.len_with(collection).map(|len| len == 0)
+    }
+
     /// Converts the object into an iterator over the object's keys and values, acting like a
     /// `for-in` loop.
     ///
@@ -118,8 +426,60 @@ impl Object {
     }
 }
 
+impl Object {
+    pub(crate) fn lookup_data_property<K>(&self, key: K) -> Result<(PropertyKind, Option<Value>)>
+    where
+        K: ToValue,
+    {
+        let key = key.to_value(&self.mv8)?;
+        self.mv8.try_catch(|scope| {
+            let key = self.property_name(scope, &key)?;
+            let object = v8::Local::new(scope, self.handle.clone());
+            let result = lookup_data_property(scope, object, key);
+            self.mv8.exception(scope)?;
+            let (kind, value) = result.unwrap();
+            Ok((kind, value.map(|v| Value::from_v8_value(&self.mv8, scope, v))))
+        })
+    }
+}
+
+// Looks up a property like `Object::lookup_data_property` does, returning `None` if an exception
+// was thrown.
+pub(crate) fn lookup_data_property<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    mut object: v8::Local<'s, v8::Object>,
+    key: v8::Local<v8::Name>,
+) -> Option<(PropertyKind, Option<v8::Local<'s, v8::Value>>)> {
+    let get_key = create_string(scope, "get").into();
+    let value_key = create_string(scope, "value").into();
+    loop {
+        if object.is_proxy() {
+            return Some((PropertyKind::Accessor, None));
+        }
+        let descriptor = object.get_own_property_descriptor(scope, key)?;
+        // The descriptor is a fresh plain object, but `Object.prototype` may have been tampered
+        // with, so only its own properties are read:
+        if let Ok(descriptor) = v8::Local::<v8::Object>::try_from(descriptor) {
+            if descriptor.has_own_property(scope, get_key) == Some(true) {
+                return Some((PropertyKind::Accessor, None));
+            }
+            return Some((PropertyKind::Data, descriptor.get(scope, value_key)));
+        }
+        match object.get_prototype(scope).map(v8::Local::<v8::Object>::try_from) {
+            Some(Ok(prototype)) => object = prototype,
+            _ => return Some((PropertyKind::Missing, None)),
+        }
+    }
+}
+
 impl fmt::Debug for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Listing a proxy's keys would run its traps:
+        let proxy = self.mv8.scope(|scope| v8::Local::new(scope, self.handle.clone()).is_proxy());
+        if proxy {
+            return write!(f, "<proxy>");
+        }
+
         let keys = match self.keys(false) {
             Ok(keys) => keys,
             Err(_) => return write!(f, "<object with keys exception>"),
@@ -134,8 +494,9 @@ impl fmt::Debug for Object {
         for i in 0..len {
             if let Ok(k) = keys.get::<Value>(i).and_then(|k| k.coerce_string(&self.mv8)) {
                 write!(f, "{:?}: ", k)?;
-                match self.get::<_, Value>(k) {
-                    Ok(v) => write!(f, "{:?}", v)?,
+                match self.get_data_property(k) {
+                    Ok(Some(v)) => write!(f, "{:?}", v)?,
+                    Ok(None) => write!(f, "<accessor>")?,
                     Err(_) => write!(f, "?")?,
                 };
             } else {
@@ -149,6 +510,118 @@ impl fmt::Debug for Object {
     }
 }
 
+/// The kind of an object property, as returned by `Object::property_kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyKind {
+    /// A plain data property, which holds a value.
+    Data,
+    /// An accessor property, whose value is computed by a getter.
+    Accessor,
+    /// No property with the key exists on the object or its prototype chain.
+    Missing,
+}
+
+/// A description of an object property, as taken by `Object::define_property`. The flags
+/// correspond to the attributes of JavaScript property descriptors.
+#[derive(Clone, Debug)]
+pub enum PropertyDescriptor {
+    /// A plain data property, which holds a value.
+    Data {
+        /// The value of the property.
+        value: Value,
+        /// Whether the value can be changed by assignment.
+        writable: bool,
+        /// Whether the property shows up in enumerations of the object's properties (e.g.
+        /// `Object.keys` and `for-in` loops).
+        enumerable: bool,
+        /// Whether the property can be deleted, and its descriptor changed.
+        configurable: bool,
+    },
+    /// An accessor property, whose value is computed by a getter (called with the object as
+    /// `this` and no arguments) each time it is read, and which may have a setter (called with the
+    /// object as `this` and the assigned value) to handle assignments. Without a setter,
+    /// assignments are ignored (or throw in strict mode code).
+    ///
+    /// The getter and setter are typically created with `MiniV8::create_function`, so that errors
+    /// they return are thrown as exceptions where the property is accessed.
+    Accessor {
+        /// The function called to read the property, or `None` to always read `undefined`.
+        get: Option<Function>,
+        /// The function called to assign the property, or `None` to ignore assignments.
+        set: Option<Function>,
+        /// Whether the property shows up in enumerations of the object's properties (e.g.
+        /// `Object.keys` and `for-in` loops).
+        enumerable: bool,
+        /// Whether the property can be deleted, and its descriptor changed.
+        configurable: bool,
+    },
+}
+
+/// The attributes of a data property set with `Object::set_with_attributes`, which can be combined
+/// with `|`. The default (`PropertyAttributes::NONE`) is a writable, enumerable and configurable
+/// property, like the properties set with `Object::set`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PropertyAttributes(u32);
+
+impl PropertyAttributes {
+    /// No attributes.
+    pub const NONE: PropertyAttributes = PropertyAttributes(0);
+    /// The value can't be changed by assignment. Assignments are ignored (or throw in strict mode
+    /// code).
+    pub const READ_ONLY: PropertyAttributes = PropertyAttributes(1 << 0);
+    /// The property doesn't show up in enumerations of the object's properties (e.g.
+    /// `Object.keys` and `for-in` loops).
+    pub const DONT_ENUM: PropertyAttributes = PropertyAttributes(1 << 1);
+    /// The property can't be deleted, and its descriptor can't be changed.
+    pub const DONT_DELETE: PropertyAttributes = PropertyAttributes(1 << 2);
+
+    /// Returns `true` if all of the attributes in `other` are set.
+    pub fn contains(self, other: PropertyAttributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for PropertyAttributes {
+    type Output = PropertyAttributes;
+
+    fn bitor(self, other: PropertyAttributes) -> PropertyAttributes {
+        PropertyAttributes(self.0 | other.0)
+    }
+}
+
+/// Which of an object's own properties are counted by `Object::len_with` and
+/// `Object::is_empty_with`. The default selects the enumerable string-keyed properties, as with
+/// `Object.keys`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyCollection {
+    /// Whether to include symbol-keyed properties.
+    pub include_symbols: bool,
+    /// Whether to include non-enumerable properties.
+    pub include_non_enumerable: bool,
+}
+
+/// How keys are normalized before being compared by `Object::get_normalized`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyNormalization {
+    /// Keys are compared ignoring case, e.g. `maxRetries` matches `maxretries` and `MAXRETRIES`.
+    CaseInsensitive,
+    /// Keys are compared ignoring case, underscores, and hyphens, so that camel case, snake case,
+    /// and kebab case spellings match each other, e.g. `maxRetries` matches `max_retries` and
+    /// `max-retries`.
+    CamelSnakeAgnostic,
+}
+
+impl KeyNormalization {
+    fn normalize(self, key: &str) -> StdString {
+        match self {
+            KeyNormalization::CaseInsensitive => key.to_lowercase(),
+            KeyNormalization::CamelSnakeAgnostic => {
+                key.chars().filter(|&c| c != '_' && c != '-').flat_map(char::to_lowercase).collect()
+            },
+        }
+    }
+}
+
 /// An iterator over an object's keys and values, acting like a `for-in` loop.
 pub struct Properties<K, V> {
     object: Object,
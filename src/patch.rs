@@ -0,0 +1,456 @@
+use crate::*;
+use std::result::Result as StdResult;
+use std::string::String as StdString;
+
+/// An operation of a JSON patch (RFC 6902), as applied by `Object::apply_patch`.
+///
+/// Paths are JSON pointers (RFC 6901) relative to the patched object, e.g. `/items/0/name`. Within
+/// a path segment, `~1` stands for `/` and `~0` stands for `~`, and as the last segment of a path
+/// into an array, `-` stands for the end of the array. The empty path refers to the patched object
+/// itself, which can be tested (and copied from), but not otherwise targeted.
+#[derive(Clone, Debug)]
+pub enum PatchOp {
+    /// Inserts a value into an array (shifting later elements), or sets an object's property.
+    Add {
+        /// Where to add the value. Its parent must exist.
+        path: StdString,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes an element from an array (shifting later elements), or an object's property.
+    Remove {
+        /// The value to remove, which must exist.
+        path: StdString,
+    },
+    /// Replaces an existing array element or object property.
+    Replace {
+        /// The value to replace, which must exist.
+        path: StdString,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes a value and adds it elsewhere.
+    Move {
+        /// The value to move, which must exist.
+        from: StdString,
+        /// Where to add the value, as with `PatchOp::Add`. This can't be within the moved value.
+        path: StdString,
+    },
+    /// Adds a deep copy of a value elsewhere. Arrays and plain objects are copied (by their own
+    /// enumerable properties), and any other values are shared.
+    Copy {
+        /// The value to copy, which must exist.
+        from: StdString,
+        /// Where to add the copy, as with `PatchOp::Add`.
+        path: StdString,
+    },
+    /// Checks that a value is equal to the given value, failing the patch if it isn't. Arrays and
+    /// plain objects are compared structurally (by their own enumerable properties), and any
+    /// other values are compared with `===`.
+    Test {
+        /// The value to check, which must exist.
+        path: StdString,
+        /// The expected value.
+        value: Value,
+    },
+}
+
+impl Object {
+    /// Applies a JSON patch (RFC 6902) to the object, see `PatchOp`. The patch is applied
+    /// atomically: If an operation fails, the changes of all earlier operations are rolled back,
+    /// and an `Error::PatchFailed` is returned with the failed operation's index and path.
+    ///
+    /// Properties are read and written as with `Object::get` and `Object::set`, so getters and
+    /// setters are invoked. If one of them throws, the patch is rolled back and the exception is
+    /// returned.
+    pub fn apply_patch(&self, ops: &[PatchOp]) -> Result<()> {
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.try_catch(|scope| {
+            let root = v8::Local::new(scope, self.handle.clone());
+            let splice = v8::Local::new(scope, intrinsics.array_splice.clone());
+            let mut patch = Patch { root, splice, undo: Vec::new() };
+            for (index, op) in ops.iter().enumerate() {
+                let failure = match patch.apply(scope, op) {
+                    Ok(()) => continue,
+                    Err(failure) => failure,
+                };
+                let error = match failure {
+                    Failure::Path(path, reason) => {
+                        Error::PatchFailed { index, path: path.to_string(), reason }
+                    },
+                    Failure::Exception => {
+                        let error = self.mv8.exception(scope).unwrap_err();
+                        scope.reset();
+                        error
+                    },
+                };
+                patch.roll_back(scope);
+                return Err(error);
+            }
+            Ok(())
+        })
+    }
+}
+
+// Why an operation failed: Either because of one of its paths, or because an exception was thrown.
+enum Failure<'a> {
+    Path(&'a str, &'static str),
+    Exception,
+}
+
+type PatchResult<'a, T> = StdResult<T, Failure<'a>>;
+
+// A change made by an operation, which can be undone.
+enum Change<'s> {
+    // A property was set (or deleted), which previously had the given value (or didn't exist).
+    Set(v8::Local<'s, v8::Object>, v8::Local<'s, v8::Value>, Option<v8::Local<'s, v8::Value>>),
+    // An element was inserted into an array at the given index.
+    Insert(v8::Local<'s, v8::Object>, u32),
+    // An element with the given value was removed from an array at the given index.
+    Remove(v8::Local<'s, v8::Object>, u32, v8::Local<'s, v8::Value>),
+}
+
+struct Patch<'s> {
+    root: v8::Local<'s, v8::Object>,
+    splice: v8::Local<'s, v8::Function>,
+    undo: Vec<Change<'s>>,
+}
+
+impl<'s> Patch<'s> {
+    fn apply<'a>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        op: &'a PatchOp,
+    ) -> PatchResult<'a, ()> {
+        match op {
+            PatchOp::Add { path, value } => {
+                let value = value.to_v8_value(scope);
+                self.add(scope, path, value)
+            },
+            PatchOp::Remove { path } => self.remove(scope, path).map(|_| ()),
+            PatchOp::Replace { path, value } => {
+                let (parent, segment) = self.parent(scope, path)?;
+                let key = match existing_key(scope, parent, &segment)? {
+                    Some(key) => key,
+                    None => return Err(Failure::Path(path, "path does not exist")),
+                };
+                let old = parent.get(scope, key).ok_or(Failure::Exception)?;
+                let value = value.to_v8_value(scope);
+                self.set(scope, parent, key, value, Some(old))
+            },
+            PatchOp::Move { from, path } => {
+                if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                    return Err(Failure::Path(path, "cannot move a value into itself"));
+                }
+                let value = self.remove(scope, from)?;
+                self.add(scope, path, value)
+            },
+            PatchOp::Copy { from, path } => {
+                let value = self.get(scope, from)?;
+                let value = deep_copy(scope, value)?;
+                self.add(scope, path, value)
+            },
+            PatchOp::Test { path, value } => {
+                let actual = self.get(scope, path)?;
+                let expected = value.to_v8_value(scope);
+                if !deep_equals(scope, actual, expected)? {
+                    return Err(Failure::Path(path, "test failed"));
+                }
+                Ok(())
+            },
+        }
+    }
+
+    fn add<'a>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        path: &'a str,
+        value: v8::Local<'s, v8::Value>,
+    ) -> PatchResult<'a, ()> {
+        let (parent, segment) = self.parent(scope, path)?;
+        if !parent.is_array() {
+            let key = create_string(scope, &segment);
+            let old = match parent.has_own_property(scope, key.into()) {
+                Some(true) => Some(parent.get(scope, key.into()).ok_or(Failure::Exception)?),
+                Some(false) => None,
+                None => return Err(Failure::Exception),
+            };
+            return self.set(scope, parent, key.into(), value, old);
+        }
+
+        let len = array_len(parent);
+        let index = match segment.as_str() {
+            "-" => len,
+            segment => parse_index(segment).ok_or(Failure::Path(path, "invalid array index"))?,
+        };
+        if index > len {
+            return Err(Failure::Path(path, "array index out of bounds"));
+        }
+        self.splice(scope, parent, index, 0, Some(value))?;
+        self.undo.push(Change::Insert(parent, index));
+        Ok(())
+    }
+
+    fn remove<'a>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        path: &'a str,
+    ) -> PatchResult<'a, v8::Local<'s, v8::Value>> {
+        let (parent, segment) = self.parent(scope, path)?;
+        let key = match existing_key(scope, parent, &segment)? {
+            Some(key) => key,
+            None => return Err(Failure::Path(path, "path does not exist")),
+        };
+        let old = parent.get(scope, key).ok_or(Failure::Exception)?;
+        if parent.is_array() {
+            let index = parse_index(&segment).unwrap();
+            self.splice(scope, parent, index, 1, None)?;
+            self.undo.push(Change::Remove(parent, index, old));
+        } else {
+            parent.delete(scope, key).ok_or(Failure::Exception)?;
+            self.undo.push(Change::Set(parent, key, Some(old)));
+        }
+        Ok(old)
+    }
+
+    fn set<'a>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        object: v8::Local<'s, v8::Object>,
+        key: v8::Local<'s, v8::Value>,
+        value: v8::Local<'s, v8::Value>,
+        old: Option<v8::Local<'s, v8::Value>>,
+    ) -> PatchResult<'a, ()> {
+        object.set(scope, key, value).ok_or(Failure::Exception)?;
+        self.undo.push(Change::Set(object, key, old));
+        Ok(())
+    }
+
+    fn splice<'a>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        array: v8::Local<'s, v8::Object>,
+        index: u32,
+        delete_count: u32,
+        item: Option<v8::Local<'s, v8::Value>>,
+    ) -> PatchResult<'a, ()> {
+        let mut args = vec![
+            v8::Integer::new_from_unsigned(scope, index).into(),
+            v8::Integer::new_from_unsigned(scope, delete_count).into(),
+        ];
+        args.extend(item);
+        self.splice.call(scope, array.into(), &args).ok_or(Failure::Exception)?;
+        Ok(())
+    }
+
+    // Returns the value at the given path, which must exist.
+    fn get<'a>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        path: &'a str,
+    ) -> PatchResult<'a, v8::Local<'s, v8::Value>> {
+        let segments = parse_pointer(path).ok_or(Failure::Path(path, "invalid pointer"))?;
+        self.walk(scope, path, &segments)
+    }
+
+    // Returns the object that contains the value at the given path, which must exist, along with
+    // the path's last segment.
+    fn parent<'a>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        path: &'a str,
+    ) -> PatchResult<'a, (v8::Local<'s, v8::Object>, StdString)> {
+        let mut segments = parse_pointer(path).ok_or(Failure::Path(path, "invalid pointer"))?;
+        let last = segments.pop().ok_or(Failure::Path(path, "cannot target the patched object"))?;
+        let parent = self.walk(scope, path, &segments)?;
+        let parent = parent.try_into().map_err(|_| Failure::Path(path, "parent is not an object"))?;
+        Ok((parent, last))
+    }
+
+    fn walk<'a>(
+        &self,
+        scope: &mut v8::HandleScope<'s>,
+        path: &'a str,
+        segments: &[StdString],
+    ) -> PatchResult<'a, v8::Local<'s, v8::Value>> {
+        let mut value: v8::Local<v8::Value> = self.root.into();
+        for segment in segments {
+            let object: v8::Local<v8::Object> = value.try_into()
+                .map_err(|_| Failure::Path(path, "path does not exist"))?;
+            let key = existing_key(scope, object, segment)?
+                .ok_or(Failure::Path(path, "path does not exist"))?;
+            value = object.get(scope, key).ok_or(Failure::Exception)?;
+        }
+        Ok(value)
+    }
+
+    // Undoes all changes made so far, in reverse order.
+    fn roll_back(&mut self, scope: &mut v8::HandleScope<'s>) {
+        while let Some(change) = self.undo.pop() {
+            let _ = match change {
+                Change::Set(object, key, Some(old)) => object.set(scope, key, old).map(|_| ()),
+                Change::Set(object, key, None) => object.delete(scope, key).map(|_| ()),
+                Change::Insert(array, index) => self.splice(scope, array, index, 1, None).ok(),
+                Change::Remove(array, index, old) => {
+                    self.splice(scope, array, index, 0, Some(old)).ok()
+                },
+            };
+        }
+    }
+}
+
+// Splits a JSON pointer into its unescaped segments, or returns `None` if it is malformed.
+fn parse_pointer(path: &str) -> Option<Vec<StdString>> {
+    if path.is_empty() {
+        return Some(Vec::new());
+    }
+    let mut segments = Vec::new();
+    for raw in path.strip_prefix('/')?.split('/') {
+        let mut segment = StdString::with_capacity(raw.len());
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '~' => match chars.next() {
+                    Some('0') => segment.push('~'),
+                    Some('1') => segment.push('/'),
+                    _ => return None,
+                },
+                c => segment.push(c),
+            }
+        }
+        segments.push(segment);
+    }
+    Some(segments)
+}
+
+// Parses an array index without leading zeros, as JSON pointers (and paths passed to
+// `Value::pluck_path`) require.
+pub(crate) fn parse_index(segment: &str) -> Option<u32> {
+    if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if segment.len() > 1 && segment.starts_with('0') {
+        return None;
+    }
+    segment.parse().ok().filter(|&index| index < u32::MAX)
+}
+
+fn array_len(array: v8::Local<v8::Object>) -> u32 {
+    v8::Local::<v8::Array>::try_from(array).unwrap().length()
+}
+
+// Returns the key of the given segment within an object or array, if the object or array has it
+// as an own property (or element).
+fn existing_key<'s, 'a>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<'s, v8::Object>,
+    segment: &str,
+) -> PatchResult<'a, Option<v8::Local<'s, v8::Value>>> {
+    if object.is_array() {
+        return Ok(match parse_index(segment) {
+            Some(index) if index < array_len(object) => {
+                Some(v8::Integer::new_from_unsigned(scope, index).into())
+            },
+            _ => None,
+        });
+    }
+    let key: v8::Local<v8::Value> = create_string(scope, segment).into();
+    match object.has_own_property(scope, key.try_into().unwrap()) {
+        Some(true) => Ok(Some(key)),
+        Some(false) => Ok(None),
+        None => Err(Failure::Exception),
+    }
+}
+
+// Returns whether the value is an array or an object that JSON would represent as an object (as
+// opposed to e.g. a function or a date).
+fn is_plain<'s>(value: v8::Local<'s, v8::Value>) -> Option<v8::Local<'s, v8::Object>> {
+    let plain = value.is_array() || (value.is_object() && !value.is_function() &&
+        !value.is_date() && !value.is_reg_exp() && !value.is_promise());
+    if plain { value.try_into().ok() } else { None }
+}
+
+fn deep_copy<'s, 'a>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+) -> PatchResult<'a, v8::Local<'s, v8::Value>> {
+    let object = match is_plain(value) {
+        Some(object) => object,
+        None => return Ok(value),
+    };
+    if object.is_array() {
+        let len = array_len(object);
+        let copy = v8::Array::new(scope, len as i32);
+        for i in 0..len {
+            let element = object.get_index(scope, i).ok_or(Failure::Exception)?;
+            let element = deep_copy(scope, element)?;
+            copy.set_index(scope, i, element).ok_or(Failure::Exception)?;
+        }
+        return Ok(copy.into());
+    }
+    let copy = v8::Object::new(scope);
+    let keys = own_keys(scope, object)?;
+    for i in 0..keys.length() {
+        let key = keys.get_index(scope, i).ok_or(Failure::Exception)?;
+        let property = object.get(scope, key).ok_or(Failure::Exception)?;
+        let property = deep_copy(scope, property)?;
+        copy.set(scope, key, property).ok_or(Failure::Exception)?;
+    }
+    Ok(copy.into())
+}
+
+fn deep_equals<'s, 'a>(
+    scope: &mut v8::HandleScope<'s>,
+    a: v8::Local<'s, v8::Value>,
+    b: v8::Local<'s, v8::Value>,
+) -> PatchResult<'a, bool> {
+    let (a, b) = match (is_plain(a), is_plain(b)) {
+        (Some(a), Some(b)) if a.is_array() == b.is_array() => (a, b),
+        (None, None) => return Ok(a.strict_equals(b)),
+        _ => return Ok(false),
+    };
+    if a.is_array() {
+        let len = array_len(a);
+        if len != array_len(b) {
+            return Ok(false);
+        }
+        for i in 0..len {
+            let a = a.get_index(scope, i).ok_or(Failure::Exception)?;
+            let b = b.get_index(scope, i).ok_or(Failure::Exception)?;
+            if !deep_equals(scope, a, b)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+    let keys = own_keys(scope, a)?;
+    if keys.length() != own_keys(scope, b)?.length() {
+        return Ok(false);
+    }
+    for i in 0..keys.length() {
+        let key = keys.get_index(scope, i).ok_or(Failure::Exception)?;
+        let key: v8::Local<v8::Name> = key.try_into().map_err(|_| Failure::Exception)?;
+        match b.has_own_property(scope, key) {
+            Some(true) => {},
+            Some(false) => return Ok(false),
+            None => return Err(Failure::Exception),
+        }
+        let a = a.get(scope, key.into()).ok_or(Failure::Exception)?;
+        let b = b.get(scope, key.into()).ok_or(Failure::Exception)?;
+        if !deep_equals(scope, a, b)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn own_keys<'s, 'a>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<'s, v8::Object>,
+) -> PatchResult<'a, v8::Local<'s, v8::Array>> {
+    let args = v8::GetPropertyNamesArgsBuilder::new()
+        .mode(v8::KeyCollectionMode::OwnOnly)
+        .key_conversion(v8::KeyConversionMode::ConvertToString)
+        .build();
+    object.get_property_names(scope, args).ok_or(Failure::Exception)
+}
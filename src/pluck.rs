@@ -0,0 +1,132 @@
+use crate::*;
+use crate::patch::parse_index;
+use std::result::Result as StdResult;
+use std::string::String as StdString;
+
+/// A segment of a path passed to `Value::pluck`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathSeg<'a> {
+    /// A property key, as in `value?.key` or `value?.["key"]`.
+    Key(&'a str),
+    /// An element index, as in `value?.[0]`.
+    Index(u32),
+}
+
+impl Value {
+    /// Reads the value at the end of a chain of property accesses, with the semantics of
+    /// JavaScript's optional chaining: `value.pluck(&mv8, &[Key("data"), Index(0)])` behaves like
+    /// `value?.data?.[0]`.
+    ///
+    /// As soon as a link in the chain is `undefined` or `null`, `Value::Undefined` is returned
+    /// without reading any further, so missing properties never cause an error. Properties of
+    /// primitive values are read as in JavaScript (e.g. a string's `length`). Exceptions thrown by
+    /// getters (or proxies) along the way are returned as errors.
+    pub fn pluck(&self, mv8: &MiniV8, path: &[PathSeg]) -> Result<Value> {
+        mv8.assert_owns(self)?;
+        mv8.try_catch(|scope| {
+            let mut value = self.to_v8_value(scope);
+            for segment in path {
+                if value.is_null_or_undefined() {
+                    return Ok(Value::Undefined);
+                }
+                let object = value.to_object(scope).unwrap();
+                let result = match *segment {
+                    PathSeg::Key(key) => {
+                        let key = create_string(scope, key);
+                        object.get(scope, key.into())
+                    },
+                    PathSeg::Index(index) => object.get_index(scope, index),
+                };
+                mv8.exception(scope)?;
+                value = result.unwrap();
+            }
+            Ok(Value::from_v8_value(mv8, scope, value))
+        })
+    }
+
+    /// Reads the value at the end of a chain of property accesses like `Value::pluck`, and then
+    /// converts it to the type `T`. Returns an error if the conversion fails, including when the
+    /// chain ended early at an `undefined` or `null` link and `T` doesn't accept `undefined`.
+    pub fn pluck_as<T: FromValue>(&self, mv8: &MiniV8, path: &[PathSeg]) -> Result<T> {
+        self.pluck(mv8, path)?.into(mv8)
+    }
+
+    /// Reads the value at the end of a chain of property accesses like `Value::pluck`, with the
+    /// chain given as a string such as `"data.items[0].name"`.
+    ///
+    /// Keys are separated by `.`, and indices are written in brackets as decimal numbers without
+    /// leading zeros. The first key isn't preceded by a `.`, and the path may start with an index.
+    /// Within keys, a backslash makes the next character literal, so `.`, `[`, `]`, and `\` are
+    /// written as `\.`, `\[`, `\]`, and `\\` respectively: `"a\.b"` is the single key `a.b`. Keys
+    /// can't be empty. An empty path reads the value itself.
+    ///
+    /// Returns `Error::InvalidPath` if the path is malformed.
+    pub fn pluck_path(&self, mv8: &MiniV8, path: &str) -> Result<Value> {
+        let segments = parse_path(path).map_err(|offset| Error::InvalidPath {
+            path: path.to_string(),
+            offset,
+        })?;
+        let segments: Vec<PathSeg> = segments.iter()
+            .map(|segment| match segment {
+                Segment::Key(key) => PathSeg::Key(key),
+                &Segment::Index(index) => PathSeg::Index(index),
+            })
+            .collect();
+        self.pluck(mv8, &segments)
+    }
+}
+
+// A parsed segment of a path passed to `Value::pluck_path`, owning its unescaped key.
+enum Segment {
+    Key(StdString),
+    Index(u32),
+}
+
+// Splits a path into its segments, or returns the byte offset at which it is malformed.
+fn parse_path(path: &str) -> StdResult<Vec<Segment>, usize> {
+    let bytes = path.as_bytes();
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = match bytes[pos] {
+            b'[' => {
+                let len = bytes[pos + 1..].iter().position(|&b| b == b']').ok_or(pos)?;
+                let index = parse_index(&path[pos + 1..pos + 1 + len]).ok_or(pos + 1)?;
+                segments.push(Segment::Index(index));
+                pos += len + 2;
+                continue;
+            },
+            b'.' if pos > 0 => pos + 1,
+            _ if pos == 0 => 0,
+            _ => return Err(pos),
+        };
+        let (key, end) = parse_key(path, start)?;
+        segments.push(Segment::Key(key));
+        pos = end;
+    }
+    Ok(segments)
+}
+
+// Unescapes the key starting at `start`, returning it along with the offset at which it ends.
+fn parse_key(path: &str, start: usize) -> StdResult<(StdString, usize), usize> {
+    let mut key = StdString::new();
+    let mut end = path.len();
+    let mut chars = path[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '.' | '[' | ']' => {
+                end = start + i;
+                break;
+            },
+            '\\' => match chars.next() {
+                Some((_, c)) => key.push(c),
+                None => return Err(start + i),
+            },
+            c => key.push(c),
+        }
+    }
+    if end == start {
+        return Err(start);
+    }
+    Ok((key, end))
+}
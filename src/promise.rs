@@ -0,0 +1,132 @@
+use crate::*;
+use std::fmt;
+
+/// Reference to a JavaScript promise.
+///
+/// Promises are JavaScript objects, so they are converted to `Value::Object`, and can be converted
+/// back from it with `FromValue`.
+#[derive(Clone)]
+pub struct Promise {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::Promise>,
+}
+
+/// The state of a `Promise`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromiseState {
+    /// The promise has neither been fulfilled nor rejected yet.
+    Pending,
+    /// The promise has been fulfilled with a value.
+    Fulfilled,
+    /// The promise has been rejected with a reason.
+    Rejected,
+}
+
+impl Promise {
+    /// Consumes the promise and downgrades it to a JavaScript object.
+    pub fn into_object(self) -> Object {
+        self.mv8.clone().scope(|scope| {
+            let object: v8::Local<v8::Object> = v8::Local::new(scope, self.handle.clone()).into();
+            Object {
+                mv8: self.mv8,
+                handle: v8::Global::new(scope, object),
+            }
+        })
+    }
+
+    /// Returns the state of the promise. Promise reactions only run at microtask checkpoints, so a
+    /// promise that depends on other promises may remain pending until `MiniV8::run_microtasks` is
+    /// called.
+    pub fn state(&self) -> PromiseState {
+        self.mv8.scope(|scope| match v8::Local::new(scope, self.handle.clone()).state() {
+            v8::PromiseState::Pending => PromiseState::Pending,
+            v8::PromiseState::Fulfilled => PromiseState::Fulfilled,
+            v8::PromiseState::Rejected => PromiseState::Rejected,
+        })
+    }
+
+    /// Returns the value that the promise was fulfilled with or the reason that it was rejected
+    /// with, or `None` if the promise is still pending.
+    pub fn result(&self) -> Option<Value> {
+        self.mv8.scope(|scope| {
+            let promise = v8::Local::new(scope, self.handle.clone());
+            match promise.state() {
+                v8::PromiseState::Pending => None,
+                _ => {
+                    let result = promise.result(scope);
+                    Some(Value::from_v8_value(&self.mv8, scope, result))
+                },
+            }
+        })
+    }
+}
+
+impl fmt::Debug for Promise {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<promise ({:?})>", self.state())
+    }
+}
+
+impl MiniV8 {
+    /// Runs all pending microtasks (e.g. promise reactions), including any microtasks that are
+    /// queued by the microtasks being run, so that promises which don't wait on anything outside
+    /// of JavaScript settle.
+    ///
+    /// Exceptions thrown by microtasks don't propagate here. They reject the promises that depend
+    /// on them instead, and a promise that is rejected without a handler doesn't cause an error.
+    pub fn run_microtasks(&self) {
+        self.scope(|scope| scope.perform_microtask_checkpoint());
+    }
+
+    /// Executes a JavaScript script like `MiniV8::eval`, and if its result is a promise (e.g. the
+    /// script calls an async function), runs pending microtasks until the promise has settled and
+    /// returns the value that it was fulfilled with.
+    ///
+    /// Returns the rejection reason as an `Error::Value` if the promise is rejected, and
+    /// `Error::NotSettled` if the promise is still pending after all microtasks have run, i.e. if
+    /// it is waiting on something that only Rust can provide.
+    pub fn eval_async<S, R>(&self, script: S) -> Result<R>
+    where
+        S: Into<Script>,
+        R: FromValue,
+    {
+        let value: Value = self.eval(script)?;
+        let promise = match Promise::from_value(value.clone(), self) {
+            Ok(promise) => promise,
+            Err(_) => return value.into(self),
+        };
+        if promise.state() == PromiseState::Pending {
+            self.run_microtasks();
+        }
+        match (promise.state(), promise.result()) {
+            (PromiseState::Fulfilled, Some(value)) => value.into(self),
+            (PromiseState::Rejected, Some(reason)) => Err(Error::Value(reason)),
+            _ => Err(Error::NotSettled),
+        }
+    }
+}
+
+impl ToValue for Promise {
+    fn to_value(self, _mv8: &MiniV8) -> Result<Value> {
+        Ok(Value::Object(self.into_object()))
+    }
+}
+
+impl FromValue for Promise {
+    fn from_value(value: Value, mv8: &MiniV8) -> Result<Promise> {
+        let object = match value {
+            Value::Object(object) => object,
+            value => return Err(Error::from_js_conversion(value.type_name(), "Promise")),
+        };
+        mv8.scope(|scope| {
+            let object = v8::Local::new(scope, object.handle);
+            match v8::Local::<v8::Promise>::try_from(object) {
+                Ok(promise) => Ok(Promise {
+                    mv8: mv8.clone(),
+                    handle: v8::Global::new(scope, promise),
+                }),
+                Err(_) => Err(Error::from_js_conversion("object", "Promise")),
+            }
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use crate::*;
+use crate::lru::Lru;
+use std::cell::RefCell;
+use std::fmt;
+use std::string::String as StdString;
+
+// The number of regular expressions that a `RegexCache` holds by default.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// A cache of compiled JavaScript regular expressions, keyed by pattern and flags, returned by
+/// `MiniV8::regex_cache`. All `RegexCache`s of a `MiniV8` share the same cache.
+///
+/// The cache holds up to 64 regular expressions by default (see `RegexCache::set_capacity`),
+/// evicting the least recently used one when it is full.
+///
+/// Regular expressions are run within V8, so a pattern that backtracks catastrophically can be
+/// interrupted like any other JavaScript, e.g. by the timeout of the script (see `Script::timeout`)
+/// that called the Rust function that is running it. An interrupted regular expression returns
+/// `Error::Timeout`.
+#[derive(Clone)]
+pub struct RegexCache {
+    mv8: MiniV8,
+}
+
+impl RegexCache {
+    /// Returns whether `input` matches the regular expression with the given pattern and flags,
+    /// as with `new RegExp(pattern, flags).test(input)` in JavaScript.
+    ///
+    /// Returns an error if the pattern or flags are invalid.
+    pub fn test(&self, pattern: &str, flags: &str, input: &str) -> Result<bool> {
+        let regexp = self.get(pattern, flags)?;
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.try_catch(|scope| {
+            let regexp = reset(scope, regexp);
+            let test = v8::Local::new(scope, intrinsics.regexp_test.clone());
+            let input = create_string(scope, input);
+            let result = test.call(scope, regexp.into(), &[input.into()]);
+            self.mv8.exception(scope)?;
+            Ok(result.unwrap().boolean_value(scope))
+        })
+    }
+
+    /// Replaces the matches of the regular expression with the given pattern and flags in `input`,
+    /// as with `input.replace(new RegExp(pattern, flags), replacement)` in JavaScript. Only the
+    /// first match is replaced unless `flags` includes "g", and `replacement` may refer to the
+    /// match with the usual patterns (e.g. `$1` or `$<name>`).
+    ///
+    /// Returns an error if the pattern or flags are invalid.
+    pub fn replace(
+        &self,
+        pattern: &str,
+        flags: &str,
+        input: &str,
+        replacement: &str,
+    ) -> Result<StdString> {
+        let regexp = self.get(pattern, flags)?;
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.try_catch(|scope| {
+            let regexp = reset(scope, regexp);
+            let replace = v8::Local::new(scope, intrinsics.string_replace.clone());
+            let input = create_string(scope, input);
+            let replacement = create_string(scope, replacement);
+            let result = replace.call(scope, input.into(), &[regexp.into(), replacement.into()]);
+            self.mv8.exception(scope)?;
+            Ok(result.unwrap().to_rust_string_lossy(scope))
+        })
+    }
+
+    /// Sets the maximum number of regular expressions held by the cache, evicting the least
+    /// recently used ones if it holds more than that.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.mv8.regexes(|regexes| regexes.0.borrow_mut().set_capacity(capacity));
+    }
+
+    /// Returns whether the cache holds the regular expression with the given pattern and flags,
+    /// without counting as a use of it.
+    pub fn contains(&self, pattern: &str, flags: &str) -> bool {
+        let key = (pattern.to_owned(), flags.to_owned());
+        self.mv8.regexes(|regexes| regexes.0.borrow().contains(&key))
+    }
+
+    /// Returns the number of regular expressions held by the cache.
+    pub fn len(&self) -> usize {
+        self.mv8.regexes(|regexes| regexes.0.borrow().len())
+    }
+
+    /// Returns `true` if the cache holds no regular expressions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all regular expressions from the cache.
+    pub fn clear(&self) {
+        self.mv8.regexes(|regexes| regexes.0.borrow_mut().clear());
+    }
+
+    fn get(&self, pattern: &str, flags: &str) -> Result<v8::Global<v8::Object>> {
+        let key = (pattern.to_owned(), flags.to_owned());
+        if let Some(regexp) = self.mv8.regexes(|regexes| regexes.0.borrow_mut().get(&key)) {
+            return Ok(regexp);
+        }
+
+        let intrinsics = self.mv8.intrinsics();
+        let regexp = self.mv8.try_catch(|scope| -> Result<_> {
+            let constructor = v8::Local::new(scope, intrinsics.regexp.clone());
+            let args = [create_string(scope, pattern).into(), create_string(scope, flags).into()];
+            let regexp = constructor.new_instance(scope, &args);
+            self.mv8.exception(scope)?;
+            Ok(v8::Global::new(scope, regexp.unwrap()))
+        })?;
+        self.mv8.regexes(|regexes| regexes.0.borrow_mut().insert(key, regexp.clone()));
+        Ok(regexp)
+    }
+}
+
+impl fmt::Debug for RegexCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<regex cache>")
+    }
+}
+
+impl MiniV8 {
+    /// Returns this `MiniV8`'s cache of compiled regular expressions.
+    pub fn regex_cache(&self) -> RegexCache {
+        RegexCache { mv8: self.clone() }
+    }
+}
+
+// Resets the `lastIndex` of a cached regular expression, which global and sticky regular
+// expressions would otherwise carry over from their previous use.
+fn reset<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    regexp: v8::Global<v8::Object>,
+) -> v8::Local<'s, v8::Object> {
+    let regexp = v8::Local::new(scope, regexp);
+    let key = create_string(scope, "lastIndex");
+    let zero = v8::Integer::new(scope, 0);
+    regexp.set(scope, key.into(), zero.into());
+    regexp
+}
+
+// The regular expressions cached by `RegexCache`, by pattern and flags, stored in an isolate slot.
+pub(crate) struct Regexes(RefCell<Lru<(StdString, StdString), v8::Global<v8::Object>>>);
+
+impl Default for Regexes {
+    fn default() -> Regexes {
+        Regexes(RefCell::new(Lru::new(DEFAULT_CAPACITY)))
+    }
+}
@@ -0,0 +1,85 @@
+use crate::*;
+use std::fmt;
+use std::sync::Arc;
+
+type Clock = Arc<dyn Fn() -> f64 + Send + Sync>;
+
+/// Restrictions for the global context of a sandboxed `MiniV8`, created with
+/// `MiniV8::new_sandboxed` or `MiniV8Builder::sandbox`. By default nothing is restricted.
+#[derive(Clone, Default)]
+pub struct SandboxOptions {
+    disallow_code_generation: bool,
+    date_now: Option<Clock>,
+    math_random: Option<Clock>,
+}
+
+impl SandboxOptions {
+    /// Returns options that don't restrict anything yet.
+    pub fn new() -> SandboxOptions {
+        SandboxOptions::default()
+    }
+
+    /// Disallows generating code from strings, so that `eval`, the `Function` constructor (and
+    /// the constructors of async and generator functions) throw an `EvalError` instead of running
+    /// any code. Unlike deleting `eval` or `Function` from the global object (see
+    /// `MiniV8Builder::global_allowlist`), this also covers the constructors that are reachable
+    /// through function instances, e.g. `(() => {}).constructor`.
+    ///
+    /// Scripts evaluated from Rust (e.g. with `MiniV8::eval`) are unaffected.
+    pub fn disallow_code_generation(mut self) -> SandboxOptions {
+        self.disallow_code_generation = true;
+        self
+    }
+
+    /// Replaces `Date.now` with a function that returns the result of `func`, e.g. a fixed or
+    /// simulated time, in milliseconds since the Unix epoch. `new Date()` still reads the system
+    /// clock.
+    pub fn date_now<F: Fn() -> f64 + Send + Sync + 'static>(mut self, func: F) -> SandboxOptions {
+        self.date_now = Some(Arc::new(func));
+        self
+    }
+
+    /// Replaces `Math.random` with a function that returns the result of `func`, e.g. a number
+    /// from a seeded generator, which should be in the range `[0, 1)`.
+    pub fn math_random<F>(mut self, func: F) -> SandboxOptions
+    where
+        F: Fn() -> f64 + Send + Sync + 'static,
+    {
+        self.math_random = Some(Arc::new(func));
+        self
+    }
+
+    // Applies the options to the global context of a newly built `MiniV8`.
+    pub(crate) fn apply(&self, mv8: &MiniV8) {
+        if self.disallow_code_generation {
+            mv8.scope(|scope| scope.get_current_context().set_allow_generation_from_strings(false));
+        }
+        let global = mv8.global();
+        let replacements = [("Date", "now", &self.date_now), ("Math", "random", &self.math_random)];
+        for (object, name, func) in replacements {
+            // The object is missing if it was left out of `MiniV8Builder::global_allowlist`:
+            if let (Some(func), Ok(Value::Object(object))) = (func, global.get(object)) {
+                let func = func.clone();
+                let _ = object.set(name, mv8.create_function(move |_| Ok(func())));
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SandboxOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SandboxOptions")
+            .field("disallow_code_generation", &self.disallow_code_generation)
+            .field("date_now", &self.date_now.is_some())
+            .field("math_random", &self.math_random.is_some())
+            .finish()
+    }
+}
+
+impl MiniV8 {
+    /// Creates a new `MiniV8` whose global context is restricted by `options`. This is the same as
+    /// `MiniV8::builder().sandbox(options).build()`.
+    pub fn new_sandboxed(options: SandboxOptions) -> MiniV8 {
+        MiniV8::builder().sandbox(options).build()
+    }
+}
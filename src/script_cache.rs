@@ -0,0 +1,105 @@
+use crate::*;
+use crate::lru::Lru;
+use std::cell::{Cell, RefCell};
+use std::string::String as StdString;
+
+// The number of compiled scripts that the script cache holds by default.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// Statistics about the cache used by `MiniV8::eval_cached`, as returned by
+/// `MiniV8::script_cache_stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScriptCacheStats {
+    /// The number of evaluations that reused a cached script.
+    pub hits: u64,
+    /// The number of evaluations that had to compile their script.
+    pub misses: u64,
+    /// The number of scripts currently held by the cache.
+    pub size: usize,
+}
+
+impl MiniV8 {
+    /// Executes a JavaScript script like `MiniV8::eval`, reusing the compiled script from a
+    /// previous call with the same source if there is one. This avoids recompiling snippets that
+    /// are evaluated over and over, when keeping `Script`s around isn't practical.
+    ///
+    /// The cache holds up to 64 scripts by default (see `MiniV8::set_script_cache_capacity`),
+    /// evicting the least recently used one when it is full. Scripts that fail to compile are not
+    /// cached, so each evaluation of them reports the syntax error anew.
+    pub fn eval_cached<R: FromValue>(&self, source: &str) -> Result<R> {
+        let script = match self.scripts(|scripts| scripts.get(source)) {
+            Some(script) => script,
+            None => {
+                let script = self.try_catch(|scope| -> Result<_> {
+                    let source = create_string(scope, source);
+                    let source = v8::script_compiler::Source::new(source, None);
+                    let script = v8::script_compiler::compile_unbound_script(
+                        scope,
+                        source,
+                        v8::script_compiler::CompileOptions::NoCompileOptions,
+                        v8::script_compiler::NoCacheReason::NoReason,
+                    );
+                    self.exception(scope)?;
+                    Ok(v8::Global::new(scope, script.unwrap()))
+                })?;
+                let key = source.to_owned();
+                self.scripts(|scripts| scripts.cache.borrow_mut().insert(key, script.clone()));
+                script
+            },
+        };
+        self.try_catch(|scope| -> Result<_> {
+            let script = v8::Local::new(scope, script);
+            let result = script.bind_to_current_context(scope).run(scope);
+            self.exception(scope)?;
+            Ok(Value::from_v8_value(self, scope, result.unwrap()))
+        })?.into(self)
+    }
+
+    /// Sets the maximum number of scripts held by the cache used by `MiniV8::eval_cached`,
+    /// evicting the least recently used ones if it holds more than that.
+    pub fn set_script_cache_capacity(&self, capacity: usize) {
+        self.scripts(|scripts| scripts.cache.borrow_mut().set_capacity(capacity));
+    }
+
+    /// Returns statistics about the cache used by `MiniV8::eval_cached`. The hit and miss counts
+    /// cover the whole lifetime of this `MiniV8`, and are not reset by
+    /// `MiniV8::clear_script_cache`.
+    pub fn script_cache_stats(&self) -> ScriptCacheStats {
+        self.scripts(|scripts| ScriptCacheStats {
+            hits: scripts.hits.get(),
+            misses: scripts.misses.get(),
+            size: scripts.cache.borrow().len(),
+        })
+    }
+
+    /// Removes all scripts from the cache used by `MiniV8::eval_cached`.
+    pub fn clear_script_cache(&self) {
+        self.scripts(|scripts| scripts.cache.borrow_mut().clear());
+    }
+}
+
+// The scripts cached by `MiniV8::eval_cached`, by source, stored in an isolate slot.
+pub(crate) struct Scripts {
+    cache: RefCell<Lru<StdString, v8::Global<v8::UnboundScript>>>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+impl Default for Scripts {
+    fn default() -> Scripts {
+        Scripts {
+            cache: RefCell::new(Lru::new(DEFAULT_CAPACITY)),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+}
+
+impl Scripts {
+    fn get(&self, source: &str) -> Option<v8::Global<v8::UnboundScript>> {
+        let script = self.cache.borrow_mut().get(source);
+        let counter = if script.is_some() { &self.hits } else { &self.misses };
+        counter.set(counter.get() + 1);
+        script
+    }
+}
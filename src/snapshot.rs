@@ -0,0 +1,88 @@
+use crate::*;
+use crate::mini_v8::{initialize_v8, Intrinsics, V8_FLAGS};
+
+// Snapshots start with this, followed by the identity of the V8 that produced them and a hash of
+// V8's blob, which V8 would otherwise take on trust (it aborts the process on a mismatch).
+const MAGIC: &[u8] = b"mini-v8 snapshot\n";
+
+impl MiniV8 {
+    /// Runs `init_source` in a fresh global context and serializes the resulting heap into a
+    /// snapshot, which `MiniV8::from_snapshot` can create any number of `MiniV8`s from (in this
+    /// process or another one) without running `init_source` again. This is useful for making
+    /// large libraries start up quickly.
+    ///
+    /// Only plain JavaScript state can be captured: `init_source` can't use anything provided by
+    /// Rust (e.g. functions created with `MiniV8::create_function`). It also must not replace or
+    /// delete the built-in functions that `MiniV8` relies on (such as `RegExp`,
+    /// `Function.prototype.toString`, or the `Intl` constructors), which `MiniV8` captures from
+    /// the snapshot's context rather than from a pristine one. Returns `Error::SnapshotFailed` if
+    /// `init_source` throws or tampers with those built-ins. A snapshot is only accepted by the
+    /// same V8 version with the same flags. Returns `Error::Disposed` if V8 has been disposed (see
+    /// `dispose`).
+    pub fn create_snapshot(init_source: &str) -> Result<Vec<u8>> {
+        let _live = initialize_v8()?;
+        let mut isolate = v8::Isolate::snapshot_creator(None);
+        let result = {
+            let scope = &mut v8::HandleScope::new(&mut isolate);
+            let context = v8::Context::new(scope);
+            let result = {
+                let scope = &mut v8::ContextScope::new(scope, context);
+                let scope = &mut v8::TryCatch::new(scope);
+                let intrinsics = Intrinsics::capture(scope).unwrap();
+                let source = create_string(scope, init_source);
+                let run = v8::Script::compile(scope, source, None).and_then(|s| s.run(scope));
+                match (run, scope.exception()) {
+                    (Some(_), _) if !intrinsics.unchanged(scope) => Err(Error::SnapshotFailed(
+                        "initialization replaced or deleted built-in functions".to_owned(),
+                    )),
+                    (Some(_), _) => Ok(()),
+                    (None, exception) => {
+                        let message = exception
+                            .map(|e| e.to_rust_string_lossy(scope))
+                            .unwrap_or_else(|| "execution terminated".to_owned());
+                        Err(Error::SnapshotFailed(format!("initialization failed: {}", message)))
+                    },
+                }
+            };
+            scope.set_default_context(context);
+            result
+        };
+        // The snapshot creator must produce a blob before it is dropped, even if it's discarded:
+        let blob = isolate.create_blob(v8::FunctionCodeHandling::Keep);
+        result?;
+        let blob = blob.ok_or_else(|| Error::SnapshotFailed("V8 produced no blob".to_owned()))?;
+        let mut snapshot = header();
+        snapshot.extend_from_slice(&fnv1a(&blob).to_le_bytes());
+        snapshot.extend_from_slice(&blob);
+        Ok(snapshot)
+    }
+
+    /// Creates a default `MiniV8` from a snapshot produced by `MiniV8::create_snapshot`. See
+    /// `MiniV8Builder::build_from_snapshot`.
+    pub fn from_snapshot(snapshot: &[u8]) -> Result<MiniV8> {
+        MiniV8::builder().build_from_snapshot(snapshot)
+    }
+}
+
+// Validates a snapshot produced by `MiniV8::create_snapshot`, and returns V8's blob within it.
+pub(crate) fn snapshot_blob(snapshot: &[u8]) -> Result<&[u8]> {
+    let rest = snapshot.strip_prefix(&header()[..]).ok_or(Error::InvalidSnapshot)?;
+    if rest.len() < 8 {
+        return Err(Error::InvalidSnapshot);
+    }
+    let (hash, blob) = rest.split_at(8);
+    if hash != fnv1a(blob).to_le_bytes() {
+        return Err(Error::InvalidSnapshot);
+    }
+    Ok(blob)
+}
+
+fn header() -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.extend_from_slice(format!("{} {}\n", v8::V8::get_version(), V8_FLAGS).as_bytes());
+    header
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
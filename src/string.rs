@@ -1,5 +1,6 @@
 use crate::*;
 use std::fmt;
+use std::io;
 use std::string::String as StdString;
 
 #[derive(Clone)]
@@ -9,12 +10,90 @@ pub struct String {
 }
 
 impl String {
-    /// Returns a Rust string converted from the V8 string.
+    /// Returns a Rust string converted from the V8 string. This is the same as
+    /// `String::to_rust_string_lossy`.
     pub fn to_string(&self) -> StdString {
+        self.to_rust_string_lossy()
+    }
+
+    /// Returns a Rust string converted from the V8 string, replacing each unpaired surrogate
+    /// (which can't be represented in UTF-8) with U+FFFD REPLACEMENT CHARACTER. Strings that
+    /// differ only in their unpaired surrogates are therefore converted to the same Rust string.
+    pub fn to_rust_string_lossy(&self) -> StdString {
         self.mv8.scope(|scope| {
             v8::Local::new(scope, self.handle.clone()).to_rust_string_lossy(scope)
         })
     }
+
+    /// Returns a Rust string converted from the V8 string, or `Error::FromJsConversionError` if
+    /// the string contains an unpaired surrogate, which can't be represented in UTF-8. Use
+    /// `String::to_utf16` to read such strings without losing data.
+    pub fn to_rust_string(&self) -> Result<StdString> {
+        char::decode_utf16(self.to_utf16())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| Error::from_js_conversion("string", "String"))
+    }
+
+    /// Returns the length of the string in UTF-16 code units, which is the same as its `length`
+    /// in JavaScript.
+    pub fn len_utf16(&self) -> usize {
+        self.mv8.scope(|scope| v8::Local::new(scope, self.handle.clone()).length())
+    }
+
+    /// Returns the string's UTF-16 code units, exactly as JavaScript sees them (including any
+    /// unpaired surrogates).
+    pub fn to_utf16(&self) -> Vec<u16> {
+        self.mv8.scope(|scope| {
+            let string = v8::Local::new(scope, self.handle.clone());
+            let mut units = vec![0; string.length()];
+            string.write(scope, &mut units, 0, v8::WriteOptions::NO_NULL_TERMINATION);
+            units
+        })
+    }
+
+    /// Writes the string to `w` as UTF-8, converting and writing at most `chunk_size` bytes at a
+    /// time, so that even very large strings never have to be held in memory as a whole. Returns
+    /// the number of bytes written.
+    ///
+    /// Surrogate pairs are never split between chunks. As with `String::to_string`, unpaired
+    /// surrogates (which can't be represented in UTF-8) are written as U+FFFD REPLACEMENT
+    /// CHARACTER, so the output is always valid UTF-8.
+    ///
+    /// Returns `Error::ExternalError` if writing to `w` fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is less than 6, the most bytes that a surrogate pair can take up in
+    /// UTF-16 (as the string is read) or in UTF-8 (as it is written).
+    pub fn write_utf8_to<W: io::Write>(&self, mut w: W, chunk_size: usize) -> Result<u64> {
+        assert!(chunk_size >= 6, "chunk size must be at least 6 bytes");
+        // Every UTF-16 code unit takes up at most 3 bytes in UTF-8:
+        let mut units = vec![0; chunk_size / 3];
+        let mut chunk = StdString::with_capacity(chunk_size);
+        let mut written = 0;
+        self.mv8.scope(|scope| {
+            let string = v8::Local::new(scope, self.handle.clone());
+            let len = string.length();
+            let mut start = 0;
+            while start < len {
+                let mut count = units.len().min(len - start);
+                let options = v8::WriteOptions::NO_NULL_TERMINATION;
+                string.write(scope, &mut units[..count], start, options);
+                // Leave a high surrogate at the end of the window for the next chunk, where it
+                // will be decoded together with the low surrogate that follows it:
+                if start + count < len && (0xd800..0xdc00).contains(&units[count - 1]) {
+                    count -= 1;
+                }
+                chunk.clear();
+                chunk.extend(char::decode_utf16(units[..count].iter().copied())
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)));
+                w.write_all(chunk.as_bytes()).map_err(|e| Error::ExternalError(Box::new(e)))?;
+                written += chunk.len() as u64;
+                start += count;
+            }
+            Ok(written)
+        })
+    }
 }
 
 impl fmt::Debug for String {
@@ -22,3 +101,62 @@ impl fmt::Debug for String {
         write!(f, "{:?}", self.to_string())
     }
 }
+
+/// Builds up a string managed by V8 out of many smaller pieces, created by
+/// `MiniV8::create_string_builder`.
+///
+/// Each piece is concatenated onto the string within V8 itself, which represents the intermediate
+/// results as ropes that share the storage of their parts. This avoids both building a large string
+/// in Rust only to copy it into V8 afterwards, and reallocating the string on each append.
+pub struct StringBuilder {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::String>,
+}
+
+impl StringBuilder {
+    /// Appends a Rust string.
+    ///
+    /// Returns an error if the resulting string would exceed V8's maximum string length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is longer than `(1 << 28) - 16` bytes.
+    pub fn append_str(&mut self, value: &str) -> Result<()> {
+        let string = self.mv8.create_string(value);
+        self.append(&string)
+    }
+
+    /// Appends a value, coercing it to a string first (see `Value::coerce_string`).
+    ///
+    /// Returns an error if `ToValue::to_value` fails for the value, if the value could not be
+    /// coerced to a string, or if the resulting string would exceed V8's maximum string length.
+    pub fn append_value<V: ToValue>(&mut self, value: V) -> Result<()> {
+        let string = value.to_value(&self.mv8)?.coerce_string(&self.mv8)?;
+        self.append(&string)
+    }
+
+    /// Consumes the builder and returns the built string.
+    pub fn finish(self) -> String {
+        String { mv8: self.mv8, handle: self.handle }
+    }
+
+    fn append(&mut self, string: &String) -> Result<()> {
+        let intrinsics = self.mv8.intrinsics();
+        self.mv8.try_catch(|scope| {
+            let concat = v8::Local::new(scope, intrinsics.string_concat.clone());
+            let this = v8::Local::new(scope, self.handle.clone());
+            let string = v8::Local::new(scope, string.handle.clone());
+            let result = concat.call(scope, this.into(), &[string.into()]);
+            self.mv8.exception(scope)?;
+            let result: v8::Local<v8::String> = result.unwrap().try_into().unwrap();
+            self.handle = v8::Global::new(scope, result);
+            Ok(())
+        })
+    }
+}
+
+impl fmt::Debug for StringBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<string builder>")
+    }
+}
@@ -0,0 +1,270 @@
+use crate::*;
+use crate::object::lookup_data_property;
+use std::string::String as StdString;
+
+/// Limits on the size of a `ValueSummary`, as produced by `Value::summarize`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SummaryOptions {
+    /// The maximum nesting depth of arrays and objects whose contents are summarized. Arrays and
+    /// objects nested any deeper are summarized as `ValueSummary::Truncated`.
+    pub max_depth: usize,
+    /// The maximum number of elements or properties summarized for each array or object.
+    pub max_breadth: usize,
+    /// The maximum length of string prefixes, in UTF-16 code units (as with JavaScript's
+    /// `length`).
+    pub max_string_len: usize,
+    /// The approximate maximum number of bytes taken by the summary as a whole. Once this is used
+    /// up, remaining elements and properties are summarized as `ValueSummary::Truncated`.
+    pub max_bytes: usize,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> SummaryOptions {
+        SummaryOptions { max_depth: 2, max_breadth: 16, max_string_len: 64, max_bytes: 4096 }
+    }
+}
+
+/// A bounded structural summary of a JavaScript value, as produced by `Value::summarize`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ValueSummary {
+    /// The JavaScript value `undefined`.
+    Undefined,
+    /// The JavaScript value `null`.
+    Null,
+    /// A JavaScript boolean.
+    Boolean(bool),
+    /// A JavaScript number.
+    Number(f64),
+    /// A JavaScript date, as milliseconds since the Unix epoch.
+    Date(f64),
+    /// A JavaScript string.
+    String {
+        /// The start of the string.
+        prefix: StdString,
+        /// The length of the whole string, in UTF-16 code units.
+        len: usize,
+    },
+    /// A JavaScript symbol, with its description (if it has one).
+    Symbol(Option<StdString>),
+    /// A JavaScript array.
+    Array {
+        /// The length of the whole array.
+        len: u32,
+        /// Summaries of the array's first elements.
+        elements: Vec<ValueSummary>,
+    },
+    /// A JavaScript function.
+    Function {
+        /// The function's name, which may be empty.
+        name: StdString,
+    },
+    /// Any other JavaScript object.
+    Object {
+        /// The number of the object's own enumerable properties.
+        len: u32,
+        /// Summaries of the object's first own enumerable properties, keyed by their names.
+        properties: Vec<(StdString, ValueSummary)>,
+    },
+    /// A JavaScript proxy, whose contents can't be read without running its traps.
+    Proxy,
+    /// A property whose value is computed by a getter, which is not called.
+    Accessor,
+    /// A value left out because a limit in `SummaryOptions` was reached.
+    Truncated,
+}
+
+// The approximate size of any summary node, not counting its strings.
+const NODE_BYTES: usize = 16;
+
+impl Value {
+    /// Returns a bounded structural summary of the value, suitable for logging: Its type, its
+    /// length or size, and summaries of its first elements or properties (to the limits given by
+    /// `options`), with strings cut down to prefixes.
+    ///
+    /// Summarizing never runs any JavaScript, so getters are not called (see
+    /// `ValueSummary::Accessor`), and proxies are not looked into (see `ValueSummary::Proxy`).
+    /// Apart from enumerating the keys of summarized objects, the cost of summarizing is bounded
+    /// by `options` rather than by the size of the value.
+    pub fn summarize(&self, options: SummaryOptions) -> ValueSummary {
+        let mv8 = match self {
+            Value::Undefined => return ValueSummary::Undefined,
+            Value::Null => return ValueSummary::Null,
+            Value::Boolean(b) => return ValueSummary::Boolean(*b),
+            Value::Number(n) => return ValueSummary::Number(*n),
+            Value::Date(d) => return ValueSummary::Date(*d),
+            Value::Array(Array { mv8, .. }) |
+            Value::Function(Function { mv8, .. }) |
+            Value::Object(Object { mv8, .. }) |
+            Value::String(String { mv8, .. }) |
+            Value::Symbol(Symbol { mv8, .. }) => mv8,
+        };
+
+        let mut summarizer = Summarizer { options, bytes: 0 };
+        mv8.try_catch(|scope| {
+            let value = self.to_v8_value(scope);
+            summarizer.summarize(scope, value, 0)
+        })
+    }
+}
+
+struct Summarizer {
+    options: SummaryOptions,
+    bytes: usize,
+}
+
+impl Summarizer {
+    fn exhausted(&self) -> bool {
+        self.bytes + NODE_BYTES > self.options.max_bytes
+    }
+
+    // Reserves the given number of bytes, returning `false` if that would exceed the budget.
+    fn reserve(&mut self, bytes: usize) -> bool {
+        if self.bytes + bytes > self.options.max_bytes {
+            return false;
+        }
+        self.bytes += bytes;
+        true
+    }
+
+    fn summarize<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        value: v8::Local<'s, v8::Value>,
+        depth: usize,
+    ) -> ValueSummary {
+        if !self.reserve(NODE_BYTES) {
+            return ValueSummary::Truncated;
+        }
+
+        if value.is_undefined() {
+            ValueSummary::Undefined
+        } else if value.is_null() {
+            ValueSummary::Null
+        } else if value.is_boolean() {
+            ValueSummary::Boolean(value.boolean_value(scope))
+        } else if value.is_number() {
+            ValueSummary::Number(value.number_value(scope).unwrap())
+        } else if value.is_date() {
+            let date: v8::Local<v8::Date> = value.try_into().unwrap();
+            ValueSummary::Date(date.value_of())
+        } else if value.is_string() {
+            let string: v8::Local<v8::String> = value.try_into().unwrap();
+            self.summarize_string(scope, string)
+        } else if value.is_symbol() {
+            let symbol: v8::Local<v8::Symbol> = value.try_into().unwrap();
+            let description = symbol.description(scope);
+            if !description.is_string() {
+                return ValueSummary::Symbol(None);
+            }
+            let description = description.to_rust_string_lossy(scope);
+            match self.reserve(description.len()) {
+                true => ValueSummary::Symbol(Some(description)),
+                false => ValueSummary::Symbol(None),
+            }
+        } else if value.is_proxy() {
+            ValueSummary::Proxy
+        } else if value.is_function() {
+            let function: v8::Local<v8::Function> = value.try_into().unwrap();
+            let name = function.get_name(scope).to_rust_string_lossy(scope);
+            match self.reserve(name.len()) {
+                true => ValueSummary::Function { name },
+                false => ValueSummary::Function { name: StdString::new() },
+            }
+        } else if value.is_array() {
+            let array: v8::Local<v8::Array> = value.try_into().unwrap();
+            self.summarize_array(scope, array, depth)
+        } else if value.is_object() {
+            let object: v8::Local<v8::Object> = value.try_into().unwrap();
+            self.summarize_object(scope, object, depth)
+        } else {
+            // BigInts and the like, which summaries don't represent:
+            ValueSummary::Undefined
+        }
+    }
+
+    fn summarize_string<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        string: v8::Local<'s, v8::String>,
+    ) -> ValueSummary {
+        let len = string.length();
+        // Only the prefix itself is ever read out of V8, no matter how long the string is:
+        let mut buffer = vec![0; len.min(self.options.max_string_len)];
+        string.write(scope, &mut buffer, 0, v8::WriteOptions::NO_NULL_TERMINATION);
+        let mut prefix = StdString::from_utf16_lossy(&buffer);
+        while !self.reserve(prefix.len()) {
+            prefix.pop();
+        }
+        ValueSummary::String { prefix, len }
+    }
+
+    fn summarize_array<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        array: v8::Local<'s, v8::Array>,
+        depth: usize,
+    ) -> ValueSummary {
+        let len = array.length();
+        if depth >= self.options.max_depth {
+            return ValueSummary::Truncated;
+        }
+        let count = (len as usize).min(self.options.max_breadth);
+        let mut elements = Vec::with_capacity(count);
+        for i in 0..count {
+            if self.exhausted() {
+                elements.push(ValueSummary::Truncated);
+                break;
+            }
+            let key = v8::Integer::new_from_unsigned(scope, i as u32);
+            let key = key.to_string(scope).unwrap().into();
+            let element = self.summarize_property(scope, array.into(), key, depth);
+            elements.push(element);
+        }
+        ValueSummary::Array { len, elements }
+    }
+
+    fn summarize_object<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        object: v8::Local<'s, v8::Object>,
+        depth: usize,
+    ) -> ValueSummary {
+        if depth >= self.options.max_depth {
+            return ValueSummary::Truncated;
+        }
+        let keys = match object.get_own_property_names(scope, Default::default()) {
+            Some(keys) => keys,
+            None => return ValueSummary::Truncated,
+        };
+        let len = keys.length();
+        let count = (len as usize).min(self.options.max_breadth);
+        let mut properties = Vec::with_capacity(count);
+        for i in 0..count {
+            let key = keys.get_index(scope, i as u32).unwrap().to_string(scope).unwrap();
+            let name = key.to_rust_string_lossy(scope);
+            if self.exhausted() || !self.reserve(name.len()) {
+                properties.push((StdString::new(), ValueSummary::Truncated));
+                break;
+            }
+            let property = self.summarize_property(scope, object, key.into(), depth);
+            properties.push((name, property));
+        }
+        ValueSummary::Object { len, properties }
+    }
+
+    fn summarize_property<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        object: v8::Local<'s, v8::Object>,
+        key: v8::Local<'s, v8::Name>,
+        depth: usize,
+    ) -> ValueSummary {
+        match lookup_data_property(scope, object, key) {
+            Some((_, Some(value))) => self.summarize(scope, value, depth + 1),
+            Some((PropertyKind::Accessor, None)) => ValueSummary::Accessor,
+            Some((_, None)) => ValueSummary::Undefined,
+            None => ValueSummary::Truncated,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::*;
+use std::fmt;
+use std::string::String as StdString;
+
+/// Reference to a JavaScript symbol.
+#[derive(Clone)]
+pub struct Symbol {
+    pub(crate) mv8: MiniV8,
+    pub(crate) handle: v8::Global<v8::Symbol>,
+}
+
+impl Symbol {
+    /// Returns the symbol's description, or `None` if the symbol was created without one.
+    pub fn description(&self) -> Option<StdString> {
+        self.mv8.scope(|scope| {
+            let symbol = v8::Local::new(scope, self.handle.clone());
+            let description = symbol.description(scope);
+            if description.is_undefined() {
+                None
+            } else {
+                Some(description.to_rust_string_lossy(scope))
+            }
+        })
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(description) => write!(f, "Symbol({})", description),
+            None => write!(f, "Symbol()"),
+        }
+    }
+}
+
+/// The well-known symbols that are shared by all realms, which JavaScript exposes as static
+/// properties of `Symbol` (e.g. `Symbol.iterator`), and which can be obtained with
+/// `MiniV8::well_known_symbol`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WellKnownSymbol {
+    /// `Symbol.asyncIterator`.
+    AsyncIterator,
+    /// `Symbol.hasInstance`.
+    HasInstance,
+    /// `Symbol.isConcatSpreadable`.
+    IsConcatSpreadable,
+    /// `Symbol.iterator`.
+    Iterator,
+    /// `Symbol.match`.
+    Match,
+    /// `Symbol.replace`.
+    Replace,
+    /// `Symbol.search`.
+    Search,
+    /// `Symbol.split`.
+    Split,
+    /// `Symbol.toPrimitive`.
+    ToPrimitive,
+    /// `Symbol.toStringTag`.
+    ToStringTag,
+    /// `Symbol.unscopables`.
+    Unscopables,
+}
+
+impl MiniV8 {
+    /// Creates a new unique symbol with an optional description, like `Symbol(description)` does
+    /// in JavaScript.
+    pub fn create_symbol(&self, description: Option<&str>) -> Symbol {
+        self.scope(|scope| {
+            let description = description.map(|d| create_string(scope, d));
+            let symbol = v8::Symbol::new(scope, description);
+            Symbol { mv8: self.clone(), handle: v8::Global::new(scope, symbol) }
+        })
+    }
+
+    /// Returns the given well-known symbol, which can be used as a property key, e.g. to read an
+    /// object's `Symbol.iterator` method.
+    pub fn well_known_symbol(&self, symbol: WellKnownSymbol) -> Symbol {
+        self.scope(|scope| {
+            let symbol = match symbol {
+                WellKnownSymbol::AsyncIterator => v8::Symbol::get_async_iterator(scope),
+                WellKnownSymbol::HasInstance => v8::Symbol::get_has_instance(scope),
+                WellKnownSymbol::IsConcatSpreadable => v8::Symbol::get_is_concat_spreadable(scope),
+                WellKnownSymbol::Iterator => v8::Symbol::get_iterator(scope),
+                WellKnownSymbol::Match => v8::Symbol::get_match(scope),
+                WellKnownSymbol::Replace => v8::Symbol::get_replace(scope),
+                WellKnownSymbol::Search => v8::Symbol::get_search(scope),
+                WellKnownSymbol::Split => v8::Symbol::get_split(scope),
+                WellKnownSymbol::ToPrimitive => v8::Symbol::get_to_primitive(scope),
+                WellKnownSymbol::ToStringTag => v8::Symbol::get_to_string_tag(scope),
+                WellKnownSymbol::Unscopables => v8::Symbol::get_unscopables(scope),
+            };
+            Symbol { mv8: self.clone(), handle: v8::Global::new(scope, symbol) }
+        })
+    }
+
+    /// Returns the symbol registered under `key` in the global symbol registry, registering a new
+    /// symbol if there is none yet. This is the same symbol that `Symbol.for(key)` returns in
+    /// JavaScript.
+    pub fn symbol_for(&self, key: &str) -> Symbol {
+        self.scope(|scope| {
+            let key = create_string(scope, key);
+            let symbol = v8::Symbol::for_global(scope, key);
+            Symbol { mv8: self.clone(), handle: v8::Global::new(scope, symbol) }
+        })
+    }
+
+    /// Returns the key that `symbol` is registered under in the global symbol registry, or `None`
+    /// if it isn't registered. This is equivalent to `Symbol.keyFor(symbol)` in JavaScript.
+    pub fn symbol_key_for(&self, symbol: &Symbol) -> Option<StdString> {
+        let intrinsics = self.intrinsics();
+        self.scope(|scope| {
+            let key_for = v8::Local::new(scope, intrinsics.symbol_key_for.clone());
+            let undefined = v8::undefined(scope).into();
+            let symbol = v8::Local::new(scope, symbol.handle.clone());
+            let key = key_for.call(scope, undefined, &[symbol.into()])?;
+            let key: v8::Local<v8::String> = key.try_into().ok()?;
+            Some(key.to_rust_string_lossy(scope))
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use crate::*;
+use std::result::Result as StdResult;
+use std::string::String as StdString;
+
+/// A syntax error found by `MiniV8::check_syntax` or `MiniV8::check_module_syntax`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxDiagnostic {
+    /// The error message, e.g. "Unexpected token ')'".
+    pub message: StdString,
+    /// The (one-based) line of the error, offset by the origin's line offset if an origin was
+    /// given.
+    pub line: usize,
+    /// The (zero-based) column within the line at which the error starts.
+    pub start_column: usize,
+    /// The (zero-based) column within the line at which the error ends.
+    pub end_column: usize,
+}
+
+impl MiniV8 {
+    /// Checks a classic script for syntax errors without running it, e.g. to validate scripts as
+    /// they are being edited.
+    ///
+    /// The script is compiled in a throwaway context, so the check can't affect (or be observed
+    /// by) the global context, and no handles to the compiled script are kept.
+    ///
+    /// V8 stops parsing at the first syntax error, so the returned diagnostics hold exactly one
+    /// entry when the script is invalid.
+    pub fn check_syntax(
+        &self,
+        source: &str,
+        origin: Option<ScriptOrigin>,
+    ) -> StdResult<(), Vec<SyntaxDiagnostic>> {
+        self.check(source, origin, false)
+    }
+
+    /// Checks a module for syntax errors without instantiating or evaluating it, like
+    /// `MiniV8::check_syntax` does for classic scripts. The module's imports are not resolved, so
+    /// a module that imports a missing module passes the check.
+    pub fn check_module_syntax(
+        &self,
+        source: &str,
+        origin: Option<ScriptOrigin>,
+    ) -> StdResult<(), Vec<SyntaxDiagnostic>> {
+        self.check(source, origin, true)
+    }
+
+    fn check(
+        &self,
+        source: &str,
+        origin: Option<ScriptOrigin>,
+        is_module: bool,
+    ) -> StdResult<(), Vec<SyntaxDiagnostic>> {
+        self.scope(|scope| {
+            let context = v8::Context::new(scope);
+            let scope = &mut v8::ContextScope::new(scope, context);
+            let scope = &mut v8::TryCatch::new(scope);
+            let source = create_string(scope, source);
+            let origin = origin.unwrap_or_default();
+            let origin = create_origin(scope, &origin, is_module);
+            if is_module {
+                let source = v8::script_compiler::Source::new(source, Some(&origin));
+                v8::script_compiler::compile_module(scope, source);
+            } else {
+                v8::Script::compile(scope, source, Some(&origin));
+            }
+            let (exception, message) = match (scope.exception(), scope.message()) {
+                (Some(exception), Some(message)) => (exception, message),
+                _ => return Ok(()),
+            };
+            let key = create_string(scope, "message");
+            let text = exception.to_object(scope)
+                .and_then(|exception| exception.get(scope, key.into()))
+                .map(|text| text.to_rust_string_lossy(scope))
+                .unwrap_or_default();
+            Err(vec![SyntaxDiagnostic {
+                message: text,
+                line: message.get_line_number(scope).unwrap_or(0),
+                start_column: message.get_start_column(),
+                end_column: message.get_end_column(),
+            }])
+        })
+    }
+}
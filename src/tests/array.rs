@@ -56,3 +56,156 @@ fn elements() {
     let list: Result<Vec<usize>> = array.elements().collect();
     assert_eq!(list.unwrap(), vec![0, 1, 0, 3, 4]);
 }
+
+#[test]
+fn create_array_from() {
+    let mv8 = MiniV8::new();
+    let array = mv8.create_array_from((1..=3).map(|n| n * 10)).unwrap();
+    assert_eq!(array.len(), 3);
+    let mut sum = 0;
+    for value in &array {
+        sum += value.unwrap().as_number().unwrap() as u32;
+    }
+    assert_eq!(sum, 60);
+
+    // Holes are read as `undefined`:
+    let array: Array = mv8.eval("[1, , 3]").unwrap();
+    let values: Vec<Value> = (&array).into_iter().collect::<Result<_>>().unwrap();
+    assert_eq!(values.len(), 3);
+    assert!(values[1].is_undefined());
+
+    let numbers: Vec<f64> = (0..100_000).map(|n| n as f64 / 2.0).collect();
+    mv8.global().set("numbers", numbers.clone()).unwrap();
+    let sum: f64 = mv8.eval("numbers.reduce((a, b) => a + b)").unwrap();
+    assert_eq!(sum, numbers.iter().sum::<f64>());
+    let slice: Array = (&[1, 2][..]).to_value(&mv8).unwrap().into(&mv8).unwrap();
+    assert_eq!(slice.len(), 2);
+
+    // Values are converted and set in batches, which keeps them in order:
+    let strings: Vec<StdString> = (0..2500).map(|n| n.to_string()).collect();
+    mv8.global().set("strings", strings).unwrap();
+    let ordered: bool = mv8.eval("strings.every((s, i) => s === String(i))").unwrap();
+    assert!(ordered);
+
+    // Elements are set like `Array::set` does, so setters inherited from `Array.prototype` run,
+    // and their exceptions are reported:
+    mv8.eval::<_, ()>(r#"
+        Object.defineProperty(Array.prototype, 1, {
+            set(value) { throw new Error(`rejected ${value}`); },
+            configurable: true,
+        })
+    "#).unwrap();
+    let error = mv8.create_array_from(vec![1, 2, 3]).unwrap_err();
+    assert_eq!(error.js_error().unwrap().message, "rejected 2");
+    mv8.eval::<_, ()>("delete Array.prototype[1]").unwrap();
+    assert_eq!(mv8.create_array_from(vec![1, 2, 3]).unwrap().len(), 3);
+}
+
+#[test]
+fn slice() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval("[0, 1, 2, 3, 4]").unwrap();
+
+    let slice: Result<Vec<usize>> = array.slice(1, 3).unwrap().elements().collect();
+    assert_eq!(slice.unwrap(), vec![1, 2]);
+    assert_eq!(array.slice(5, 5).unwrap().len(), 0);
+    assert_eq!(array.slice(0, 5).unwrap().len(), 5);
+
+    match array.slice(3, 6) {
+        Err(Error::InvalidRange { start: 3, end: 6, len: 5 }) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match array.slice(3, 2) {
+        Err(Error::InvalidRange { start: 3, end: 2, len: 5 }) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // The source array is left untouched:
+    assert_eq!(array.len(), 5);
+}
+
+#[test]
+fn slice_js() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval("[0, 1, 2, 3, 4]").unwrap();
+
+    let slice: Result<Vec<usize>> = array.slice_js(-2, None).unwrap().elements().collect();
+    assert_eq!(slice.unwrap(), vec![3, 4]);
+    let slice: Result<Vec<usize>> = array.slice_js(1, Some(-1)).unwrap().elements().collect();
+    assert_eq!(slice.unwrap(), vec![1, 2, 3]);
+    let slice: Result<Vec<usize>> = array.slice_js(3, Some(100)).unwrap().elements().collect();
+    assert_eq!(slice.unwrap(), vec![3, 4]);
+    assert_eq!(array.slice_js(4, Some(1)).unwrap().len(), 0);
+}
+
+#[test]
+fn slice_untampered() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval(r#"
+        Array.prototype.slice = function() { throw new Error('tampered'); };
+        [0, 1, 2]
+    "#).unwrap();
+    assert_eq!(array.slice(0, 2).unwrap().len(), 2);
+}
+
+#[test]
+fn copy_range_to_vec() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval("[0, 1, 2, 3, {}]").unwrap();
+    // Only the requested window is converted, so the trailing object doesn't cause an error:
+    let window: Vec<Function> = array.copy_range_to_vec(4..4).unwrap();
+    assert!(window.is_empty());
+    let window: Vec<usize> = array.copy_range_to_vec(1..4).unwrap();
+    assert_eq!(window, vec![1, 2, 3]);
+    assert!(array.copy_range_to_vec::<usize>(3..6).is_err());
+    assert!(array.copy_range_to_vec::<Function>(3..5).is_err());
+}
+
+#[test]
+fn index_bounds() {
+    let mv8 = MiniV8::new();
+    let array = mv8.create_array();
+    // 2^32-1 is not an array index, so it is stored as an ordinary property:
+    array.set(u32::MAX, "max").unwrap();
+    assert_eq!(array.len(), 0);
+    assert_eq!(array.get::<StdString>(u32::MAX).unwrap(), "max");
+    assert!(array.clone().into_object().has("4294967295").unwrap());
+    // 2^32-2 is the largest array index:
+    array.set(u32::MAX - 1, "last").unwrap();
+    assert_eq!(array.len(), u32::MAX);
+    assert_eq!(array.get::<StdString>(u32::MAX - 1).unwrap(), "last");
+    let within: bool = mv8.eval::<_, Function>("a => a.length === 2 ** 32 - 1").unwrap()
+        .call((array,)).unwrap();
+    assert!(within);
+}
+
+#[test]
+fn sort_with() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval(r#"
+        globalThis.objects = [{ n: 3 }, { n: 1 }, { n: 2 }];
+        objects.slice()
+    "#).unwrap();
+    array.sort_with(|a, b| {
+        let a: f64 = a.as_object().unwrap().get("n")?;
+        let b: f64 = b.as_object().unwrap().get("n")?;
+        Ok(a.partial_cmp(&b).unwrap())
+    }).unwrap();
+    mv8.global().set("sorted", array).unwrap();
+    let identical: bool = mv8.eval(r#"
+        sorted[0] === objects[1] && sorted[1] === objects[2] && sorted[2] === objects[0]
+    "#).unwrap();
+    assert!(identical);
+
+    let array: Array = mv8.eval("[3, 1, 2]").unwrap();
+    let mut calls = 0;
+    let result = array.sort_with(|_, _| {
+        calls += 1;
+        Err(Error::ExternalError("cannot compare".into()))
+    });
+    match result {
+        Err(Error::ExternalError(e)) => assert_eq!(e.to_string(), "cannot compare"),
+        _ => panic!("unexpected result: {:?}", result),
+    }
+    assert_eq!(calls, 1);
+}
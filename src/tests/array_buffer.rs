@@ -0,0 +1,55 @@
+use crate::*;
+
+#[test]
+fn create_array_buffer() {
+    let mv8 = MiniV8::new();
+    let buffer = mv8.create_array_buffer(&[1, 2, 3]);
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.to_vec(), vec![1, 2, 3]);
+    mv8.global().set("buffer", buffer.clone()).unwrap();
+    let sum: u32 = mv8.eval("new Uint8Array(buffer).reduce((a, b) => a + b)").unwrap();
+    assert_eq!(sum, 6);
+
+    // Changes made by JavaScript are visible to Rust:
+    mv8.eval::<_, ()>("new Uint8Array(buffer)[0] = 255").unwrap();
+    assert_eq!(buffer.to_vec(), vec![255, 2, 3]);
+
+    let buffer: ArrayBuffer = mv8.eval("new ArrayBuffer(4)").unwrap();
+    assert_eq!(buffer.to_vec(), vec![0; 4]);
+    assert!(mv8.create_array_buffer(&[]).is_empty());
+    assert!(mv8.eval::<_, ArrayBuffer>("new Uint8Array(4)").is_err());
+    assert!(mv8.eval::<_, ArrayBuffer>("({})").is_err());
+}
+
+#[test]
+fn bytes() {
+    let mv8 = MiniV8::new();
+    mv8.global().set("bytes", Bytes(vec![1, 2, 3])).unwrap();
+    let is_uint8_array: bool = mv8.eval("bytes instanceof Uint8Array").unwrap();
+    assert!(is_uint8_array);
+    let json: String = mv8.eval("JSON.stringify(Array.from(bytes))").unwrap();
+    assert_eq!(json.to_string(), "[1,2,3]");
+
+    let bytes: Bytes = mv8.eval("new Uint8Array([4, 5, 6])").unwrap();
+    assert_eq!(bytes, Bytes(vec![4, 5, 6]));
+    let bytes: Bytes = mv8.eval("new Uint8Array([0, 1, 2, 3, 4]).subarray(1, 3)").unwrap();
+    assert_eq!(bytes.into_vec(), vec![1, 2]);
+    let bytes: Bytes = mv8.eval("new Uint16Array([0x0102]).buffer").unwrap();
+    assert_eq!(bytes.0.len(), 2);
+    let bytes: Bytes = mv8.eval("new DataView(new Uint8Array([7, 8, 9]).buffer, 1)").unwrap();
+    assert_eq!(bytes, Bytes(vec![8, 9]));
+    assert!(mv8.eval::<_, Bytes>("[1, 2, 3]").is_err());
+    assert!(mv8.eval::<_, Bytes>("'abc'").is_err());
+}
+
+#[test]
+fn bytes_callback() {
+    let mv8 = MiniV8::new();
+    let checksum = mv8.create_function(|inv| {
+        let bytes: Bytes = inv.args.from(&inv.mv8, 0)?;
+        Ok(bytes.0.iter().map(|&b| b as u32).sum::<u32>())
+    });
+    mv8.global().set("checksum", checksum).unwrap();
+    let sum: u32 = mv8.eval("checksum(Uint8Array.of(97, 98, 99))").unwrap();
+    assert_eq!(sum, 97 + 98 + 99);
+}
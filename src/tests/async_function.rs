@@ -0,0 +1,130 @@
+use crate::*;
+use futures::executor::block_on;
+use futures::task::noop_waker_ref;
+use std::cell::Cell;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::string::String as StdString;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A timer that wakes its task from another thread, standing in for an async runtime's sleep.
+struct Sleep(Instant);
+
+fn sleep(duration: Duration) -> Sleep {
+    Sleep(Instant::now() + duration)
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let remaining = self.0.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Poll::Ready(());
+        }
+        let waker = cx.waker().clone();
+        thread::spawn(move || {
+            thread::sleep(remaining);
+            waker.wake();
+        });
+        Poll::Pending
+    }
+}
+
+fn install_sleep_double(mv8: &MiniV8) {
+    let sleep_double = mv8.create_async_function(|inv| {
+        let millis: Result<u64> = inv.args.from(&inv.mv8, 0);
+        async move {
+            let millis = millis?;
+            sleep(Duration::from_millis(millis)).await;
+            Ok(millis * 2)
+        }
+    });
+    mv8.global().set("sleepDouble", sleep_double).unwrap();
+}
+
+#[test]
+fn await_async_function() {
+    let mv8 = MiniV8::new();
+    install_sleep_double(&mv8);
+    let promise: Promise = mv8.eval(r#"
+        (async () => {
+            const a = await sleepDouble(20);
+            const b = await sleepDouble(a);
+            return a + b;
+        })()
+    "#).unwrap();
+    assert_eq!(promise.state(), PromiseState::Pending);
+    block_on(poll_fn(|cx| mv8.poll_pending(cx)));
+    assert_eq!(promise.state(), PromiseState::Fulfilled);
+    assert_eq!(promise.result().unwrap().as_number(), Some(120.0));
+
+    // Nothing is pending anymore:
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert!(mv8.poll_pending(&mut cx).is_ready());
+}
+
+#[test]
+fn reject() {
+    let mv8 = MiniV8::new();
+    install_sleep_double(&mv8);
+    let fail = mv8.create_async_function(|_| async {
+        sleep(Duration::from_millis(10)).await;
+        Err::<(), _>(Error::external("connection refused"))
+    });
+    mv8.global().set("fail", fail).unwrap();
+    let promise: Promise = mv8.eval(r#"
+        Promise.all([
+            fail().catch(e => e.message),
+            sleepDouble('soon').catch(e => e.name),
+        ])
+    "#).unwrap();
+    block_on(poll_fn(|cx| mv8.poll_pending(cx)));
+    let messages: Vec<StdString> = promise.result().unwrap().into(&mv8).unwrap();
+    assert_eq!(messages, vec!["connection refused", "TypeError"]);
+}
+
+#[test]
+fn cancel_on_drop() {
+    struct Guard(Rc<Cell<bool>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let cancelled = Rc::new(Cell::new(false));
+    let mv8 = MiniV8::new();
+    let guard = cancelled.clone();
+    let wait = mv8.create_async_function(move |_| {
+        let guard = Guard(guard.clone());
+        async move {
+            sleep(Duration::from_secs(60)).await;
+            drop(guard);
+            Ok(())
+        }
+    });
+    wait.call::<_, Promise>(()).unwrap();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert!(mv8.poll_pending(&mut cx).is_pending());
+    assert!(!cancelled.get());
+    drop(wait);
+    drop(mv8);
+    assert!(cancelled.get());
+}
+
+#[test]
+fn location() {
+    let mv8 = MiniV8::new();
+    let line = line!() + 1;
+    let func = mv8.create_async_function(|_| async { Ok(()) });
+    let report = mv8.leak_check();
+    assert_eq!(report.functions.len(), 1);
+    assert_eq!(report.functions[0].file(), file!());
+    assert_eq!(report.functions[0].line(), line);
+    drop(func);
+}
@@ -0,0 +1,85 @@
+use crate::*;
+use std::string::String as StdString;
+
+const CONCAT: &str = r#"
+    globalThis.log = [];
+    (async () => {
+        let text = '';
+        try {
+            for await (const chunk of source) {
+                log.push('chunk ' + chunk);
+                text += chunk;
+            }
+            log.push('done ' + text);
+        } catch (e) {
+            log.push('error ' + e.message);
+        }
+    })();
+    log.push('started');
+"#;
+
+fn log(mv8: &MiniV8) -> Vec<StdString> {
+    mv8.eval("log").unwrap()
+}
+
+#[test]
+fn iterator() {
+    let mv8 = MiniV8::new();
+    let source = mv8.create_async_iterable(["a", "b", "c", "d", "e"].into_iter().map(Ok));
+    mv8.global().set("source", source).unwrap();
+    let _: Value = mv8.eval(CONCAT).unwrap();
+    assert_eq!(log(&mv8), vec![
+        "started", "chunk a", "chunk b", "chunk c", "chunk d", "chunk e", "done abcde",
+    ]);
+}
+
+#[test]
+fn iterator_error() {
+    let mv8 = MiniV8::new();
+    let items = vec![Ok("a"), Err(Error::ExternalError("broken".into())), Ok("b")];
+    mv8.global().set("source", mv8.create_async_iterable(items.into_iter())).unwrap();
+    let _: Value = mv8.eval(CONCAT).unwrap();
+    assert_eq!(log(&mv8), vec!["started", "chunk a", "error broken"]);
+}
+
+#[test]
+fn channel() {
+    let mv8 = MiniV8::new();
+    let (source, sender) = mv8.create_async_channel();
+    mv8.global().set("source", source).unwrap();
+    sender.send(Ok(1));
+    let _: Value = mv8.eval(CONCAT).unwrap();
+    assert_eq!(log(&mv8), vec!["started", "chunk 1"]);
+    sender.send(Ok(2));
+    sender.send(Ok(3));
+    // Evaluating anything runs the pending promise reactions once it completes:
+    let _: Value = mv8.eval("0").unwrap();
+    assert_eq!(log(&mv8), vec!["started", "chunk 1", "chunk 2", "chunk 3"]);
+    drop(sender);
+    let _: Value = mv8.eval("0").unwrap();
+    assert_eq!(log(&mv8), vec!["started", "chunk 1", "chunk 2", "chunk 3", "done 123"]);
+}
+
+#[test]
+fn channel_reentrant_then() {
+    let mv8 = MiniV8::new();
+    let (source, sender) = mv8.create_async_channel();
+    mv8.global().set("source", source).unwrap();
+    // Resolving a promise reads the `then` property of the result, which calls `next()` again
+    // while the item is being sent:
+    let _: Value = mv8.eval(r#"
+        globalThis.reentered = 0;
+        Object.defineProperty(Object.prototype, 'then', {
+            get() {
+                if (!reentered++) source.next();
+                return undefined;
+            },
+            configurable: true,
+        });
+        source.next();
+    "#).unwrap();
+    sender.send(Ok(1));
+    drop(sender);
+    let reentered: u32 = mv8.eval("delete Object.prototype.then; reentered").unwrap();
+    assert!(reentered >= 1);
+}
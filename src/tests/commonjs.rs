@@ -0,0 +1,141 @@
+use crate::*;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::string::String as StdString;
+
+// Installs a loader that serves scripts from `sources`, resolving "./" specifiers relative to the
+// requiring module's directory and all other specifiers as absolute paths.
+fn install(mv8: &MiniV8, sources: &[(&'static str, &'static str)]) {
+    let sources: HashMap<PathBuf, &'static str> = sources.iter()
+        .map(|(path, source)| (PathBuf::from(path), *source))
+        .collect();
+    mv8.install_commonjs(move |specifier, referrer| {
+        let path = match specifier.strip_prefix("./") {
+            Some(relative) => referrer.parent().unwrap_or(Path::new("/")).join(relative),
+            None => PathBuf::from(specifier),
+        };
+        match sources.get(&path) {
+            Some(source) => Ok(ModuleSource::Script { path, source: source.to_string() }),
+            None => Err(Error::ExternalError(format!("cannot find module {:?}", specifier).into())),
+        }
+    }).unwrap();
+}
+
+#[test]
+fn require() {
+    let mv8 = MiniV8::new();
+    install(&mv8, &[("/math.js", "exports.add = (a, b) => a + b;")]);
+    let result: usize = mv8.eval("require('/math.js').add(1, 2)").unwrap();
+    assert_eq!(result, 3);
+}
+
+#[test]
+fn cache() {
+    let mv8 = MiniV8::new();
+    install(&mv8, &[("/counter.js", r#"
+        globalThis.loads = (globalThis.loads || 0) + 1;
+        module.exports = function() { return 'hi'; };
+    "#)]);
+    let same: bool = mv8.eval("require('/counter.js') === require('/counter.js')").unwrap();
+    assert!(same);
+    let loads: usize = mv8.eval("loads").unwrap();
+    assert_eq!(loads, 1);
+    let loaded: bool = mv8.eval("module.loaded").unwrap();
+    assert!(!loaded);
+}
+
+#[test]
+fn loader_calls() {
+    let mv8 = MiniV8::new();
+    let calls = Rc::new(Cell::new(0));
+    let loader_calls = calls.clone();
+    mv8.install_commonjs(move |specifier, _| {
+        loader_calls.set(loader_calls.get() + 1);
+        let source = match specifier {
+            "/a.js" => "globalThis.loads = (globalThis.loads || 0) + 1;",
+            "/b.js" => "require('/a.js'); require('/a.js');",
+            _ => return Err(Error::ExternalError("no such module".into())),
+        };
+        Ok(ModuleSource::Script { path: specifier.into(), source: source.to_owned() })
+    }).unwrap();
+    let _: () = mv8.eval("require('/a.js'); require('/a.js'); require('/b.js'); require('/b.js')")
+        .unwrap();
+    // Once for each specifier and referrer:
+    assert_eq!(calls.get(), 3);
+    assert_eq!(mv8.eval::<_, usize>("loads").unwrap(), 1);
+}
+
+#[test]
+fn circular() {
+    let mv8 = MiniV8::new();
+    install(&mv8, &[
+        ("/a.js", r#"
+            exports.early = 'a';
+            const b = require('/b.js');
+            exports.late = 'a';
+            exports.b = b;
+        "#),
+        ("/b.js", r#"
+            const a = require('/a.js');
+            exports.sawEarly = a.early;
+            exports.sawLate = a.late;
+        "#),
+    ]);
+    let b: Object = mv8.eval("require('/a.js').b").unwrap();
+    assert_eq!(b.get::<_, StdString>("sawEarly").unwrap(), "a");
+    assert!(b.get::<_, Value>("sawLate").unwrap().is_undefined());
+}
+
+#[test]
+fn filename_and_dirname() {
+    let mv8 = MiniV8::new();
+    install(&mv8, &[
+        ("/lib/a.js", "module.exports = [__filename, __dirname, require('./b.js'), \
+            this === exports];"),
+        ("/lib/b.js", "module.exports = 'b';"),
+    ]);
+    let (filename, dirname, b, this): (StdString, StdString, StdString, bool) =
+        mv8.eval::<_, Array>("require('/lib/a.js')").unwrap().elements()
+            .collect::<Result<Vec<Value>>>().unwrap()
+            .into_iter().collect::<Values>().into(&mv8).unwrap();
+    assert_eq!(filename, "/lib/a.js");
+    assert_eq!(dirname, "/lib");
+    assert_eq!(b, "b");
+    assert!(this);
+}
+
+#[test]
+fn native_exports() {
+    let mv8 = MiniV8::new();
+    let exports = mv8.create_object();
+    exports.set("version", 3).unwrap();
+    mv8.install_commonjs(move |specifier, _| match specifier {
+        "native" => Ok(ModuleSource::Exports { path: "native".into(), exports: exports.clone() }),
+        _ => Err(Error::ExternalError("no such module".into())),
+    }).unwrap();
+    let version: usize = mv8.eval("require('native').version").unwrap();
+    assert_eq!(version, 3);
+}
+
+#[test]
+fn errors() {
+    let mv8 = MiniV8::new();
+    install(&mv8, &[("/throws.js", r#"
+        globalThis.attempts = (globalThis.attempts || 0) + 1;
+        throw new Error('oops');
+    "#)]);
+    let message: StdString = mv8.eval(r#"
+        try { require('/missing.js'); } catch (e) { e.message }
+    "#).unwrap();
+    assert!(message.contains("cannot find module"));
+    let (first, second, attempts): (StdString, StdString, usize) = mv8.eval::<_, Array>(r#"
+        const attempt = () => { try { require('/throws.js'); } catch (e) { return e.message; } };
+        [attempt(), attempt(), attempts]
+    "#).unwrap().elements().collect::<Result<Vec<Value>>>().unwrap()
+        .into_iter().collect::<Values>().into(&mv8).unwrap();
+    assert_eq!(first, "oops");
+    assert_eq!(second, "oops");
+    assert_eq!(attempts, 2);
+}
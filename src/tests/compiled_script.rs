@@ -0,0 +1,62 @@
+use crate::*;
+use std::time::Duration;
+
+#[test]
+fn compile() {
+    let mv8 = MiniV8::new();
+    let _: () = mv8.eval("var counter = 0").unwrap();
+    let script = mv8.compile("++counter").unwrap();
+    for i in 1..=3 {
+        let value: u32 = script.run().unwrap();
+        assert_eq!(value, i);
+    }
+
+    // Runs see the current state of the global context:
+    let script = mv8.compile("typeof later").unwrap();
+    let _: () = mv8.eval("var later = 1").unwrap();
+    let kind: String = script.run().unwrap();
+    assert_eq!(kind.to_string(), "number");
+
+    match mv8.compile("1 +") {
+        Err(Error::Value(_)) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let script = mv8.compile("throw new Error('boom')").unwrap();
+    assert!(script.run::<Value>().is_err());
+}
+
+#[test]
+fn compile_timeout() {
+    let mv8 = MiniV8::new();
+    let script = mv8.compile(Script {
+        source: "for (;;) {}".to_string(),
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    }).unwrap();
+    match script.run::<Value>() {
+        Err(Error::Timeout) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn compile_with_cache() {
+    let source = "function add(a, b) { return a + b; } add(1, 2)";
+    let cache = {
+        let mv8 = MiniV8::new();
+        let script = mv8.compile(source).unwrap();
+        let _: u32 = script.run().unwrap();
+        script.cache_data()
+    };
+    assert!(!cache.is_empty());
+
+    let mv8 = MiniV8::new();
+    let value: u32 = mv8.compile_with_cache(source, &cache).unwrap().run().unwrap();
+    assert_eq!(value, 3);
+
+    // Rejected caches fall back to compiling the script from scratch:
+    let value: u32 = mv8.compile_with_cache("2 * 3", &cache).unwrap().run().unwrap();
+    assert_eq!(value, 6);
+    let value: u32 = mv8.compile_with_cache(source, &[1, 2, 3, 4]).unwrap().run().unwrap();
+    assert_eq!(value, 3);
+}
@@ -0,0 +1,49 @@
+use crate::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::string::String as StdString;
+
+#[test]
+fn init_console() {
+    let mv8 = MiniV8::new();
+    let messages = Rc::new(RefCell::new(Vec::new()));
+    let sink_messages = messages.clone();
+    mv8.init_console(move |mv8, level, args| {
+        sink_messages.borrow_mut().push((level, mv8.format_console(&args)));
+    }).unwrap();
+    mv8.eval::<_, ()>(r#"
+        console.log('a', 1, true);
+        console.info({ a: 1 });
+        console.warn([1, 'b']);
+        console.error();
+        console.debug('%s is %d years and %f days', 'x', 4.7, 1.5, 'extra');
+        console.trace('%o %c%% %s', { b: null }, 'color: red');
+    "#).unwrap();
+    assert_eq!(*messages.borrow(), vec![
+        (ConsoleLevel::Log, "a 1 true".to_owned()),
+        (ConsoleLevel::Info, r#"{"a":1}"#.to_owned()),
+        (ConsoleLevel::Warn, r#"[1,"b"]"#.to_owned()),
+        (ConsoleLevel::Error, "".to_owned()),
+        (ConsoleLevel::Debug, "x is 4 years and 1.5 days extra".to_owned()),
+        (ConsoleLevel::Trace, r#"{"b":null} % %s"#.to_owned()),
+    ]);
+}
+
+#[test]
+fn format_console() {
+    let mv8 = MiniV8::new();
+    let format = |script: &str| -> StdString {
+        let args: Vec<Value> = mv8.eval(script).unwrap();
+        mv8.format_console(&args)
+    };
+    // A lone string isn't a format string:
+    assert_eq!(format("['100%%']"), "100%%");
+    assert_eq!(format("['%d', 'abc']"), "NaN");
+    assert_eq!(format("['%i', Symbol()]"), "NaN");
+    assert_eq!(format("[Symbol('s'), undefined, null]"), "Symbol(s) undefined null");
+    assert_eq!(format("[1, '%s', 2]"), "1 %s 2");
+    // Objects that can't be serialized as JSON are coerced to strings instead:
+    assert_eq!(format("(() => { const a = []; a.push(a); return [a]; })()"), "");
+    assert_eq!(format("[Object.create(null)]"), "{}");
+    assert_eq!(format("[() => 1]"), "() => 1");
+}
@@ -1,5 +1,7 @@
 use crate::*;
 use std::collections::{BTreeMap, HashMap, BTreeSet, HashSet};
+use std::string::String as StdString;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[test]
 fn option() {
@@ -22,6 +24,50 @@ fn option() {
     assert_eq!(num, 123);
     let num_zero: usize = FromValue::from_value(none_val.clone(), &mv8).unwrap();
     assert_eq!(num_zero, 0);
+
+    // Missing elements and properties are read as `None` too:
+    let nested: Vec<Option<u32>> = mv8.eval("[1, null, undefined, , 5]").unwrap();
+    assert_eq!(nested, vec![Some(1), None, None, None, Some(5)]);
+    let map: HashMap<StdString, Option<u32>> = mv8.eval("({ a: 1, b: null })").unwrap();
+    assert_eq!(map["a"], Some(1));
+    assert_eq!(map["b"], None);
+    let array: Array = vec![Some(1), None].to_value(&mv8).unwrap().into(&mv8).unwrap();
+    assert!(array.get::<Value>(1).unwrap().is_null());
+}
+
+#[test]
+fn char() {
+    let mv8 = MiniV8::new();
+    for c in ['a', 'é', '😊'] {
+        let value = c.to_value(&mv8).unwrap();
+        assert_eq!(value.as_string().unwrap().to_string(), c.to_string());
+        assert_eq!(char::from_value(value, &mv8).unwrap(), c);
+    }
+    assert_eq!(mv8.eval::<_, char>("7").unwrap(), '7');
+    for source in ["''", "'ab'", "'\\ud800'", "'😊😊'"] {
+        let result = mv8.eval::<_, char>(source);
+        let expected = matches!(result, Err(Error::FromJsConversionError { to: "char", .. }));
+        assert!(expected, "{}", source);
+    }
+}
+
+#[test]
+fn result() {
+    let mv8 = MiniV8::new();
+    let ok: std::result::Result<u32, StdString> = Ok(1);
+    assert_eq!(ok.to_value(&mv8).unwrap().as_number(), Some(1.0));
+
+    // An `Err` returned from a callback is thrown:
+    let parse = mv8.create_function(|inv| {
+        let input: StdString = inv.args.from(&inv.mv8, 0)?;
+        let error = inv.mv8.create_object();
+        error.set("code", "EPARSE")?;
+        Ok(input.parse::<u32>().map_err(|_| error))
+    });
+    mv8.global().set("parse", parse).unwrap();
+    assert_eq!(mv8.eval::<_, u32>("parse('12')").unwrap(), 12);
+    let code: StdString = mv8.eval("try { parse('x') } catch (e) { e.code }").unwrap();
+    assert_eq!(code, "EPARSE");
 }
 
 #[test]
@@ -106,6 +152,39 @@ fn vec() {
     assert_eq!(list.unwrap(), vec![1, 2, 3]);
 }
 
+#[test]
+fn fixed_size_array() {
+    let mv8 = MiniV8::new();
+    let value = [1, 2, 3].to_value(&mv8).unwrap();
+    let sum: u32 = mv8.create_function(|inv| {
+        let (array,): (Array,) = inv.args.into(&inv.mv8)?;
+        array.elements::<u32>().sum::<Result<u32>>()
+    }).call((value.clone(),)).unwrap();
+    assert_eq!(sum, 6);
+    let array: [u32; 3] = value.clone().into(&mv8).unwrap();
+    assert_eq!(array, [1, 2, 3]);
+    assert!(value.clone().into::<[u32; 2]>(&mv8).is_err());
+    assert!(value.into::<[u32; 4]>(&mv8).is_err());
+}
+
+#[test]
+fn tuple_value() {
+    let mv8 = MiniV8::new();
+    let value = ("a", 1, true).to_value(&mv8).unwrap();
+    let json: StdString = mv8.global().get::<_, Object>("JSON").unwrap()
+        .call_prop("stringify", (value.clone(),)).unwrap();
+    assert_eq!(json, r#"["a",1,true]"#);
+    let tuple: (StdString, u32, bool) = value.into(&mv8).unwrap();
+    assert_eq!(tuple, ("a".to_string(), 1, true));
+
+    // Missing elements are read as `undefined`, and extra elements are ignored:
+    let (a, b): (u32, Option<u32>) = mv8.eval("[1]").unwrap();
+    assert_eq!((a, b), (1, None));
+    let (a,): (u32,) = mv8.eval("[1, 2]").unwrap();
+    assert_eq!(a, 1);
+    assert!(mv8.eval::<_, (u32,)>("1").is_err());
+}
+
 #[test]
 fn btree_set() {
     let btree_set: BTreeSet<_> = vec![1, 2, 3].into_iter().collect();
@@ -123,3 +202,134 @@ fn hash_set() {
         .unwrap().elements().collect();
     assert_eq!(list.unwrap(), vec![1, 2, 3].into_iter().collect());
 }
+
+#[test]
+fn pointer_sized_bounds() {
+    let mv8 = MiniV8::new();
+    let convert = |n: f64| usize::from_value(Value::Number(n), &mv8);
+    assert_eq!(convert(0.0).unwrap(), 0);
+    assert_eq!(convert(4294967295.0).unwrap(), 4294967295);
+    assert_eq!(convert(9007199254740991.0).unwrap() as u64, 9007199254740991);
+    assert!(convert(-1.0).is_err());
+    assert!(convert(2f64.powi(usize::BITS as i32)).is_err());
+    if usize::BITS == 32 {
+        assert!(convert(4294967296.0).is_err());
+    }
+
+    let convert = |n: f64| isize::from_value(Value::Number(n), &mv8);
+    assert_eq!(convert(-1.0).unwrap(), -1);
+    assert_eq!(convert(-9007199254740991.0).unwrap() as i64, -9007199254740991);
+    assert!(convert(f64::NEG_INFINITY).is_err());
+}
+
+#[test]
+fn integer_strictness() {
+    let mv8 = MiniV8::new();
+    let number = |n: f64| Value::Number(n);
+
+    // Values that every integer type rejects:
+    for n in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.5, -0.5, 1.5] {
+        assert!(i8::from_value(number(n), &mv8).is_err(), "{}", n);
+        assert!(u32::from_value(number(n), &mv8).is_err(), "{}", n);
+        assert!(i64::from_value(number(n), &mv8).is_err(), "{}", n);
+        assert!(usize::from_value(number(n), &mv8).is_err(), "{}", n);
+    }
+
+    // Negative zero is zero:
+    assert_eq!(u8::from_value(number(-0.0), &mv8).unwrap(), 0);
+    assert_eq!(i32::from_value(number(-0.0), &mv8).unwrap(), 0);
+
+    // Numbers beyond the range of safe integers may have been rounded, so they are rejected:
+    let max_safe = 9007199254740991.0;
+    assert_eq!(i64::from_value(number(max_safe), &mv8).unwrap(), 9007199254740991);
+    assert_eq!(i64::from_value(number(-max_safe), &mv8).unwrap(), -9007199254740991);
+    assert_eq!(u64::from_value(number(max_safe), &mv8).unwrap(), 9007199254740991);
+    assert!(i64::from_value(number(max_safe + 1.0), &mv8).is_err());
+    assert!(i64::from_value(number(-max_safe - 1.0), &mv8).is_err());
+    assert!(u64::from_value(number(max_safe + 1.0), &mv8).is_err());
+    assert!(mv8.eval::<_, i64>("9007199254740993").is_err());
+
+    // Numbers outside of the type's range, including negative numbers into unsigned types:
+    assert_eq!(u8::from_value(number(255.0), &mv8).unwrap(), 255);
+    assert!(u8::from_value(number(256.0), &mv8).is_err());
+    assert!(mv8.eval::<_, u8>("-1").is_err());
+    assert!(u64::from_value(number(-1.0), &mv8).is_err());
+    assert_eq!(i16::from_value(number(-32768.0), &mv8).unwrap(), i16::MIN);
+    assert!(i16::from_value(number(-32769.0), &mv8).is_err());
+    assert_eq!(u32::from_value(number(4294967295.0), &mv8).unwrap(), u32::MAX);
+    assert!(u32::from_value(number(4294967296.0), &mv8).is_err());
+
+    // Non-numbers are still coerced first:
+    assert_eq!(u32::from_value(Value::Null, &mv8).unwrap(), 0);
+    assert_eq!(mv8.eval::<_, u32>("'42'").unwrap(), 42);
+    assert!(u32::from_value(Value::Undefined, &mv8).is_err());
+    match mv8.eval::<_, u32>("'4.2'") {
+        Err(Error::FromJsConversionError { from: "string", to: "u32" }) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // `Lossy` opts out, truncating and saturating like `as` casts:
+    let lossy = |n: f64| Lossy::<u8>::from_value(number(n), &mv8).unwrap().into_inner();
+    assert_eq!([lossy(300.0), lossy(-1.0), lossy(1.9), lossy(f64::NAN)], [255, 0, 1, 0]);
+    let Lossy(big): Lossy<i64> = mv8.eval("9007199254740993").unwrap();
+    assert_eq!(big, 9007199254740992);
+}
+
+#[test]
+fn system_time() {
+    let mv8 = MiniV8::new();
+    let check: Function = mv8.eval("(d, t) => d instanceof Date && d.getTime() === t").unwrap();
+    for &millis in &[0i64, 1_500, -1_500, 8_640_000_000_000_000, -8_640_000_000_000_000] {
+        let time = match millis < 0 {
+            true => UNIX_EPOCH - Duration::from_millis(-millis as u64),
+            false => UNIX_EPOCH + Duration::from_millis(millis as u64),
+        };
+        let date = mv8.create_date(time);
+        assert_eq!(date.as_date(), Some(millis as f64));
+        assert!(check.call::<_, bool>((date.clone(), millis as f64)).unwrap());
+        assert_eq!(date.as_system_time(), Some(time));
+        assert_eq!(SystemTime::from_value(date, &mv8).unwrap(), time);
+    }
+
+    // Dates round-trip through JavaScript as genuine `Date`s:
+    let identity: Function = mv8.eval("d => d").unwrap();
+    let time = UNIX_EPOCH + Duration::from_millis(1_600_000_000_123);
+    assert_eq!(identity.call::<_, SystemTime>((time,)).unwrap(), time);
+    let time = UNIX_EPOCH - Duration::from_millis(86_400_000);
+    let date: Value = mv8.eval("new Date('1969-12-31T00:00:00Z')").unwrap();
+    assert_eq!(date.as_system_time(), Some(time));
+
+    // Sub-millisecond precision is truncated, and times out of range are invalid dates:
+    let date = mv8.create_date(UNIX_EPOCH + Duration::from_micros(1_999));
+    assert_eq!(date.as_date(), Some(1.0));
+    let date = mv8.create_date(UNIX_EPOCH + Duration::from_millis(8_640_000_000_000_001));
+    assert!(date.as_date().unwrap().is_nan());
+
+    let invalid: Value = mv8.eval("new Date(NaN)").unwrap();
+    assert!(invalid.is_date());
+    assert_eq!(invalid.as_system_time(), None);
+    assert!(SystemTime::from_value(invalid.clone(), &mv8).is_err());
+    assert!(Duration::from_value(invalid, &mv8).is_err());
+    assert!(SystemTime::from_value(Value::Number(0.0), &mv8).is_err());
+}
+
+#[test]
+fn duration() {
+    let mv8 = MiniV8::new();
+    let value = Duration::from_millis(1_500).to_value(&mv8).unwrap();
+    assert_eq!(value.as_date(), Some(1_500.0));
+    assert_eq!(Duration::from_value(value, &mv8).unwrap(), Duration::from_millis(1_500));
+
+    let before_epoch: Value = mv8.eval("new Date(-1)").unwrap();
+    assert!(Duration::from_value(before_epoch, &mv8).is_err());
+
+    // `Millis` converts to and from numbers instead:
+    let value = Millis(Duration::from_micros(1_500_250)).to_value(&mv8).unwrap();
+    assert_eq!(value.as_number(), Some(1_500.25));
+    let millis = Millis::from_value(Value::Number(1_500.0), &mv8).unwrap();
+    assert_eq!(millis.into_inner(), Duration::from_millis(1_500));
+    for source in ["-1", "NaN", "Infinity", "1e300", "new Date(0)", "'1'"] {
+        let value: Value = mv8.eval(source).unwrap();
+        assert!(Millis::from_value(value, &mv8).is_err(), "{}", source);
+    }
+}
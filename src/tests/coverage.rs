@@ -0,0 +1,69 @@
+use crate::*;
+
+const SOURCE: &str = r#"
+    function check(x) {
+        if (x) {
+            return 'taken';
+        } else {
+            return 'not taken';
+        }
+    }
+    check(true);
+    check(true);
+"#;
+
+// Returns the execution count of the innermost range of the named function that covers the first
+// occurrence of `needle` in `SOURCE`.
+fn count_at(coverage: &[ScriptCoverage], url: &str, function: &str, needle: &str) -> u64 {
+    let offset = SOURCE.find(needle).unwrap();
+    let script = coverage.iter().find(|script| script.url == url).unwrap();
+    let function = script.functions.iter().find(|f| f.function_name == function).unwrap();
+    function.ranges.iter()
+        .rfind(|range| range.start_offset <= offset && offset < range.end_offset)
+        .unwrap()
+        .count
+}
+
+fn eval_script(mv8: &MiniV8) {
+    let origin = ScriptOrigin { name: "check.js".to_owned(), ..Default::default() };
+    let script = Script { source: SOURCE.to_owned(), origin: Some(origin), ..Default::default() };
+    let _: Value = mv8.eval(script).unwrap();
+}
+
+#[test]
+fn block_count() {
+    let mv8 = MiniV8::new();
+    mv8.start_coverage(CoverageMode::BlockCount);
+    eval_script(&mv8);
+    let _: Value = mv8.compile_module("check.mjs", SOURCE).unwrap().evaluate().unwrap();
+
+    let coverage = mv8.take_coverage();
+    for url in ["check.js", "check.mjs"] {
+        assert_eq!(count_at(&coverage, url, "check", "function check"), 2);
+        assert_eq!(count_at(&coverage, url, "check", "'taken'"), 2);
+        assert_eq!(count_at(&coverage, url, "check", "'not taken'"), 0);
+        let script = coverage.iter().find(|script| script.url == url).unwrap();
+        assert!(script.functions.iter().all(|f| f.is_block_coverage));
+        assert!(script.to_json().contains(r#""functionName":"check""#));
+    }
+
+    // Taking coverage resets the counts:
+    let _: Value = mv8.eval("check(false)").unwrap();
+    let coverage = mv8.take_coverage();
+    assert_eq!(count_at(&coverage, "check.js", "check", "'taken'"), 0);
+    assert_eq!(count_at(&coverage, "check.js", "check", "'not taken'"), 1);
+
+    mv8.stop_coverage();
+    assert!(mv8.take_coverage().is_empty());
+}
+
+#[test]
+fn precise_count() {
+    let mv8 = MiniV8::new();
+    mv8.start_coverage(CoverageMode::PreciseCount);
+    eval_script(&mv8);
+    let coverage = mv8.take_coverage();
+    assert_eq!(count_at(&coverage, "check.js", "check", "'not taken'"), 2);
+    let script = coverage.iter().find(|script| script.url == "check.js").unwrap();
+    assert!(script.functions.iter().all(|f| !f.is_block_coverage));
+}
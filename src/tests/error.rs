@@ -0,0 +1,175 @@
+use crate::*;
+
+#[test]
+fn js_error() {
+    let mv8 = MiniV8::builder().capture_stack_traces(10).build();
+    let script = Script {
+        source: "function f() { null.x; }\nf();".to_owned(),
+        origin: Some(ScriptOrigin { name: "script.js".to_owned(), ..Default::default() }),
+        ..Default::default()
+    };
+    let error = mv8.eval::<_, Value>(script.clone()).unwrap_err();
+    let js_error = error.js_error().unwrap();
+    assert_eq!(js_error.name, "TypeError");
+    assert_eq!(js_error.message, "Cannot read properties of null (reading 'x')");
+    assert!(js_error.stack.as_ref().unwrap().starts_with("TypeError: Cannot read properties"));
+    assert!(js_error.first_frame().unwrap().starts_with("at f (script.js:1:"));
+    assert_eq!(js_error.frames.len(), 2);
+    assert_eq!(js_error.frames[0].function_name.as_deref(), Some("f"));
+    assert_eq!(js_error.frames[0].script_name.as_deref(), Some("script.js"));
+    assert_eq!(js_error.frames[0].line, 1);
+    assert_eq!((js_error.frames[1].function_name.as_ref(), js_error.frames[1].line), (None, 2));
+    assert!(js_error.value.is_object());
+    let display = error.to_string();
+    assert!(display.starts_with(
+        "JavaScript runtime error (TypeError: Cannot read properties of null (reading 'x') \
+        (at f (script.js:1:"), "{}", display);
+
+    // Stack traces aren't captured by default:
+    let error = MiniV8::new().eval::<_, Value>(script).unwrap_err();
+    let js_error = error.js_error().unwrap();
+    assert_eq!(js_error.name, "TypeError");
+    assert_eq!((js_error.stack, js_error.frames), (None, Vec::new()));
+}
+
+#[test]
+fn js_error_not_error_like() {
+    let mv8 = MiniV8::new();
+    let error = mv8.eval::<_, Value>("throw 'oops'").unwrap_err();
+    assert!(error.js_error().is_none());
+    assert_eq!(error.to_string(), "JavaScript runtime error (string)");
+
+    let error = mv8.eval::<_, Value>("throw { message: 'custom' }").unwrap_err();
+    let js_error = error.js_error().unwrap();
+    assert_eq!((js_error.name.as_str(), js_error.stack), ("Error", None));
+    assert!(js_error.frames.is_empty());
+    assert_eq!(error.to_string(), "JavaScript runtime error (Error: custom)");
+
+    // Getters are never called:
+    let error = mv8.eval::<_, Value>(r#"
+        globalThis.called = false;
+        throw { get message() { called = true; return "getter"; } };
+    "#).unwrap_err();
+    assert!(error.js_error().is_none());
+    assert_eq!(error.to_string(), "JavaScript runtime error (object)");
+    let called: bool = mv8.eval("called").unwrap();
+    assert!(!called);
+
+    assert!(Error::Timeout.js_error().is_none());
+}
+
+#[derive(Debug, PartialEq)]
+struct QuotaExceeded {
+    limit: u32,
+}
+
+impl std::fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "quota of {} exceeded", self.limit)
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+#[test]
+fn external_error() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let error = Error::external(QuotaExceeded { limit: 3 });
+    assert_eq!(error.downcast_ref::<QuotaExceeded>(), Some(&QuotaExceeded { limit: 3 }));
+    assert!(error.downcast_ref::<std::fmt::Error>().is_none());
+    assert_eq!(error.to_string(), "quota of 3 exceeded");
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "quota of 3 exceeded");
+    if let Error::ExternalError(boxed) = &error {
+        assert_send_sync(boxed);
+    }
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = "plain message".into();
+    let error = Error::from(boxed);
+    assert_eq!(error.to_string(), "plain message");
+    assert!(Error::Timeout.downcast_ref::<QuotaExceeded>().is_none());
+}
+
+#[test]
+fn external_error_round_trip() {
+    let mv8 = MiniV8::new();
+    let check = mv8.create_function(|_| -> Result<()> {
+        Err(Error::external(QuotaExceeded { limit: 5 }))
+    });
+    mv8.global().set("check", check).unwrap();
+
+    // JavaScript sees an error-like object with the original message:
+    let message: std::string::String = mv8.eval("try { check() } catch (e) { e.message }")
+        .unwrap();
+    assert_eq!(message, "quota of 5 exceeded");
+
+    // Passing through JavaScript back into Rust restores the original error:
+    let error = mv8.eval::<_, Value>("(function outer() { check(); })()").unwrap_err();
+    assert_eq!(error.downcast_ref::<QuotaExceeded>(), Some(&QuotaExceeded { limit: 5 }));
+
+    let relay: Function = mv8.eval("(function(f) { f(); })").unwrap();
+    let nested = mv8.create_function(move |inv| {
+        let check: Function = inv.mv8.global().get("check")?;
+        relay.call::<_, ()>((check,))
+    });
+    let error = nested.call::<_, ()>(()).unwrap_err();
+    assert_eq!(error.downcast_ref::<QuotaExceeded>(), Some(&QuotaExceeded { limit: 5 }));
+}
+
+#[test]
+fn to_value_error_classes() {
+    let mv8 = MiniV8::new();
+    let fail = mv8.create_function(|inv| -> Result<()> {
+        let kind: u32 = inv.args.from(&inv.mv8, 0)?;
+        Err(match kind {
+            0 => Error::external("plain failure"),
+            1 => Error::from_js_conversion("string", "u32"),
+            _ => Error::ReentrancyLimit,
+        })
+    });
+    mv8.global().set("fail", fail).unwrap();
+    let result: Vec<bool> = mv8.eval(r#"
+        const caught = [0, 1, 2].map(kind => {
+            try { (function caller() { fail(kind); })(); } catch (e) { return e; }
+        });
+        [
+            caught[0] instanceof Error && caught[0].name === 'Error',
+            caught[0].toString() === 'Error: plain failure',
+            caught[0].stack.includes('caller'),
+            caught[1] instanceof TypeError,
+            caught[2] instanceof RangeError,
+        ]
+    "#).unwrap();
+    assert_eq!(result, [true; 5]);
+}
+
+#[test]
+fn to_value_through_callbacks() {
+    let mv8 = MiniV8::new();
+    let inner = mv8.create_function(|inv| -> Result<()> {
+        let thrower: Function = inv.args.from(&inv.mv8, 0)?;
+        thrower.call(())
+    });
+    let outer = mv8.create_function(|inv| -> Result<()> {
+        let inner: Function = inv.mv8.global().get("inner")?;
+        inner.call((inv.args.get(0),))
+    });
+    let fail = mv8.create_function(|_| -> Result<()> { Err(Error::external("quota exceeded")) });
+    mv8.global().set("inner", inner).unwrap();
+    mv8.global().set("outer", outer).unwrap();
+    mv8.global().set("fail", fail).unwrap();
+
+    // A JavaScript error rethrown by two layers of callbacks arrives as the very same object:
+    let same: bool = mv8.eval(r#"
+        const original = new TypeError('original');
+        try { outer(() => { throw original; }); } catch (e) { e === original }
+    "#).unwrap();
+    assert!(same);
+
+    // A Rust error's message survives two layers of callbacks, and arrives as a genuine error:
+    let message: std::string::String = mv8.eval(r#"
+        try { outer(() => fail()); } catch (e) { e instanceof Error && e.message }
+    "#).unwrap();
+    assert_eq!(message, "quota exceeded");
+}
@@ -0,0 +1,109 @@
+use crate::*;
+use futures::executor::block_on;
+use futures::task::{waker, ArcWake};
+use futures::{FutureExt, StreamExt};
+use std::string::String as StdString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+#[test]
+fn emit() {
+    let mv8 = MiniV8::new();
+    let (emit, mut events) = mv8.create_event_channel::<StdString>(8, Backpressure::Error);
+    mv8.global().set("emit", emit).unwrap();
+    let _: () = mv8.eval("emit('pending'); emit('active'); emit('closed');").unwrap();
+    let events: Vec<StdString> = block_on(async {
+        let mut list = Vec::new();
+        for _ in 0..3 {
+            list.push(events.next().await.unwrap().unwrap());
+        }
+        list
+    });
+    assert_eq!(events, vec!["pending", "active", "closed"]);
+}
+
+#[test]
+fn emit_conversion_error() {
+    let mv8 = MiniV8::new();
+    let (emit, mut events) = mv8.create_event_channel::<Function>(8, Backpressure::Error);
+    emit.call::<_, ()>((1,)).unwrap();
+    assert!(block_on(events.next()).unwrap().is_err());
+    assert!(events.next().now_or_never().is_none());
+}
+
+#[test]
+fn backpressure() {
+    let mv8 = MiniV8::new();
+    let collect = |events: &mut EventStream<usize>| {
+        let mut list = Vec::new();
+        while let Some(Some(event)) = events.next().now_or_never() {
+            list.push(event.unwrap());
+        }
+        list
+    };
+
+    let (emit, mut events) = mv8.create_event_channel::<usize>(2, Backpressure::DropOldest);
+    for i in 0..4 {
+        emit.call::<_, ()>((i,)).unwrap();
+    }
+    assert_eq!(collect(&mut events), vec![2, 3]);
+
+    let (emit, mut events) = mv8.create_event_channel::<usize>(2, Backpressure::DropNewest);
+    for i in 0..4 {
+        emit.call::<_, ()>((i,)).unwrap();
+    }
+    assert_eq!(collect(&mut events), vec![0, 1]);
+
+    let (emit, mut events) = mv8.create_event_channel::<usize>(2, Backpressure::Error);
+    mv8.global().set("emit", emit).unwrap();
+    let message: StdString = mv8.eval(r#"
+        emit(0);
+        emit(1);
+        try { emit(2); } catch (e) { e.message }
+    "#).unwrap();
+    assert_eq!(message, "event channel is full");
+    let (emit, _events) = mv8.create_event_channel::<usize>(1, Backpressure::Error);
+    emit.call::<_, ()>((0,)).unwrap();
+    assert!(matches!(emit.call::<_, ()>((1,)), Err(Error::ChannelFull)));
+    assert_eq!(collect(&mut events), vec![0, 1]);
+}
+
+#[test]
+fn close() {
+    let mv8 = MiniV8::new();
+    let (emit, mut events) = mv8.create_event_channel::<usize>(8, Backpressure::Error);
+    mv8.global().set("emit", emit).unwrap();
+    let _: () = mv8.eval("emit(1)").unwrap();
+    events.close();
+    let message: StdString = mv8.eval("try { emit(2); } catch (e) { e.message }").unwrap();
+    assert_eq!(message, "event channel is closed");
+    // Events emitted before closing are still received, and then the stream ends:
+    assert_eq!(block_on(events.next()).unwrap().unwrap(), 1);
+    assert!(block_on(events.next()).is_none());
+
+    drop(events);
+    let (emit, _) = mv8.create_event_channel::<usize>(8, Backpressure::Error);
+    assert!(matches!(emit.call::<_, ()>((1,)), Err(Error::ChannelClosed)));
+}
+
+#[test]
+fn close_wakes_stream() {
+    struct Flag(AtomicBool);
+
+    impl ArcWake for Flag {
+        fn wake_by_ref(flag: &Arc<Flag>) {
+            flag.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let mv8 = MiniV8::new();
+    let (_emit, mut events) = mv8.create_event_channel::<usize>(8, Backpressure::Error);
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = waker(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    assert!(events.poll_next_unpin(&mut cx).is_pending());
+    events.close();
+    assert!(flag.0.load(Ordering::SeqCst));
+    assert!(matches!(events.poll_next_unpin(&mut cx), Poll::Ready(None)));
+}
@@ -0,0 +1,65 @@
+use crate::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+struct Resource {
+    id: u32,
+    closed: Rc<Cell<u32>>,
+}
+
+impl Drop for Resource {
+    fn drop(&mut self) {
+        self.closed.set(self.closed.get() + 1);
+    }
+}
+
+#[test]
+fn create_external() {
+    let mv8 = MiniV8::new();
+    let closed = Rc::new(Cell::new(0));
+    let resource = mv8.create_external(Resource { id: 7, closed: closed.clone() });
+    assert!(resource.external::<u32>().is_none());
+    assert!(mv8.create_object().external::<Resource>().is_none());
+
+    let read_id = mv8.create_function(|inv| {
+        let object: Object = inv.args.from(&inv.mv8, 0)?;
+        match object.external::<Resource>() {
+            Some(resource) => Ok(resource.id),
+            None => Err(Error::external("not a resource")),
+        }
+    });
+    mv8.global().set("readId", read_id).unwrap();
+    mv8.global().set("resource", resource).unwrap();
+    let id: u32 = mv8.eval("readId(resource)").unwrap();
+    assert_eq!(id, 7);
+    assert!(mv8.eval::<_, u32>("readId({})").is_err());
+
+    // The value is dropped once the object is collected:
+    mv8.eval::<_, ()>("delete globalThis.resource").unwrap();
+    mv8.low_memory_notification();
+    assert_eq!(closed.get(), 1);
+
+    // ...or when the `MiniV8` is dropped, whichever comes first:
+    let resource = mv8.create_external(Resource { id: 8, closed: closed.clone() });
+    mv8.global().set("resource", resource).unwrap();
+    mv8.low_memory_notification();
+    assert_eq!(closed.get(), 1);
+    drop(mv8);
+    assert_eq!(closed.get(), 2);
+}
+
+#[test]
+fn set_finalizer() {
+    let mv8 = MiniV8::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let object: Object = mv8.eval("globalThis.kept = {}; ({})").unwrap();
+    let kept: Object = mv8.global().get("kept").unwrap();
+    for (object, name) in [(object, "collected"), (kept, "kept")] {
+        let log = log.clone();
+        object.set_finalizer(move || log.borrow_mut().push(name));
+    }
+    mv8.low_memory_notification();
+    assert_eq!(*log.borrow(), ["collected"]);
+    drop(mv8);
+    assert_eq!(*log.borrow(), ["collected", "kept"]);
+}
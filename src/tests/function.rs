@@ -21,6 +21,48 @@ fn js_constructor() {
     assert_eq!(10, value.get("x").unwrap());
 }
 
+#[test]
+fn js_class_constructor() {
+    let mv8 = MiniV8::new();
+    let map: Function = mv8.global().get("Map").unwrap();
+    let map: Object = map.call_new((vec![("a", 1)],)).unwrap();
+    let value: u32 = map.call_prop("get", ("a",)).unwrap();
+    assert_eq!(value, 1);
+
+    let class: Function = mv8.eval("(class Point { constructor(x) { this.x = x; } })").unwrap();
+    assert!(class.call::<_, Value>((1,)).is_err());
+    let point: Object = class.call_new((1,)).unwrap();
+    assert_eq!(1, point.get("x").unwrap());
+}
+
+#[test]
+fn rust_constructor() {
+    let mv8 = MiniV8::new();
+    let func = mv8.create_function(|inv| {
+        if !inv.is_construct_call() {
+            return Err(Error::ExternalError("must be called with new".into()));
+        }
+        let this: Object = inv.this.into(&inv.mv8)?;
+        this.set("constructed", true)?;
+        Ok(())
+    });
+    mv8.global().set("Thing", func.clone()).unwrap();
+
+    let constructed: bool = mv8.eval("new Thing().constructed").unwrap();
+    assert!(constructed);
+    let thing: Object = func.call_new(()).unwrap();
+    assert!(thing.get::<_, bool>("constructed").unwrap());
+    assert!(mv8.eval::<_, Value>("Thing()").is_err());
+
+    let new_target = mv8.create_function(|inv| Ok(inv.new_target));
+    mv8.global().set("newTarget", new_target).unwrap();
+    // The constructor itself is returned, since it is an object:
+    let matches: bool = mv8.eval("new newTarget() === newTarget").unwrap();
+    assert!(matches);
+    let is_undefined: bool = mv8.eval("newTarget() === undefined").unwrap();
+    assert!(is_undefined);
+}
+
 #[test]
 fn rust_function() {
     fn add(inv: Invocation) -> Result<usize> {
@@ -144,3 +186,258 @@ fn number_this() {
     let value: f64 = mv8.eval("add(5)").unwrap();
     assert!(value.is_nan());
 }
+
+// Builds `outer.a`, a Rust getter that calls the JS function `middle`, which reads `inner.b`, a
+// Rust getter that calls the JS function `thrower`. `inner_handles` decides whether the innermost
+// Rust callback handles the exception thrown by `thrower`, and `middle` decides for itself whether
+// to catch what escapes from `inner.b`.
+fn nested_getters(mv8: &MiniV8, middle: &str, inner_handles: bool) -> Object {
+    let thrower: Function = mv8.eval("(function thrower() { throw new Error('innermost'); })")
+        .unwrap();
+    let inner_getter = mv8.create_function(move |_| {
+        match thrower.call::<_, Value>(()) {
+            Ok(_) => panic!("expected an exception"),
+            Err(_) if inner_handles => Ok("handled by inner".to_owned()),
+            Err(e) => Err(e),
+        }
+    });
+    let middle: Function = mv8.eval(middle).unwrap();
+    let outer_getter = mv8.create_function(move |_| {
+        let result: StdString = middle.call(())?;
+        Ok(format!("outer saw: {}", result))
+    });
+    let define: Function = mv8.eval(r#"
+        (function(innerGetter, outerGetter) {
+            globalThis.inner = Object.defineProperty({}, 'b', { get: innerGetter });
+            return Object.defineProperty({}, 'a', { get: outerGetter });
+        })
+    "#).unwrap();
+    define.call((inner_getter, outer_getter)).unwrap()
+}
+
+#[test]
+fn nested_exception_handled_by_inner_callback() {
+    let mv8 = MiniV8::new();
+    let outer = nested_getters(&mv8, "(function middle() { return inner.b; })", true);
+    let result: StdString = outer.get("a").unwrap();
+    assert_eq!(result, "outer saw: handled by inner");
+}
+
+#[test]
+fn nested_exception_handled_by_js() {
+    let mv8 = MiniV8::new();
+    let outer = nested_getters(&mv8, r#"
+        (function middle() {
+            try { return inner.b; } catch (e) { return 'handled by middle: ' + e.message; }
+        })
+    "#, false);
+    let result: StdString = outer.get("a").unwrap();
+    assert_eq!(result, "outer saw: handled by middle: innermost");
+}
+
+#[test]
+fn nested_exception_unhandled() {
+    let mv8 = MiniV8::new();
+    let outer = nested_getters(&mv8, "(function middle() { return inner.b; })", false);
+    match outer.get::<_, Value>("a") {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert_eq!(message, "innermost");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Every layer has been unwound, so nothing is left pending for later operations:
+    let result: usize = mv8.eval("1 + 1").unwrap();
+    assert_eq!(result, 2);
+}
+
+fn typed_repeat(mv8: &MiniV8, mode: CoercionMode) -> Function {
+    mv8.create_function_typed(mode, &["text", "count", "separator"], |_, args| {
+        let (text, count, separator): (StdString, u32, Option<StdString>) = args;
+        Ok(vec![text; count as usize].join(&separator.unwrap_or_default()))
+    })
+}
+
+#[test]
+fn typed_function_lenient() {
+    let mv8 = MiniV8::new();
+    mv8.global().set("repeat", typed_repeat(&mv8, CoercionMode::Lenient)).unwrap();
+    let result: StdString = mv8.eval("repeat('ab', 2, '-')").unwrap();
+    assert_eq!(result, "ab-ab");
+    let result: StdString = mv8.eval("repeat(12, '3', undefined, 'extra')").unwrap();
+    assert_eq!(result, "121212");
+    let result: StdString = mv8.eval("repeat(true, { valueOf: () => 2 })").unwrap();
+    assert_eq!(result, "truetrue");
+}
+
+#[test]
+fn typed_function_strict() {
+    let mv8 = MiniV8::new();
+    mv8.global().set("repeat", typed_repeat(&mv8, CoercionMode::Strict)).unwrap();
+    let result: StdString = mv8.eval("repeat('ab', 2, '-')").unwrap();
+    assert_eq!(result, "ab-ab");
+    let result: StdString = mv8.eval("repeat('ab', 2, null, 'extra')").unwrap();
+    assert_eq!(result, "abab");
+    let (name, message): (StdString, StdString) = mv8.eval::<_, Array>(r#"
+        try { repeat(12, '3'); } catch (e) { [e.name, e.message] }
+    "#).unwrap().elements().collect::<Result<Vec<Value>>>().unwrap()
+        .into_iter().collect::<Values>().into(&mv8).unwrap();
+    assert_eq!(name, "TypeError");
+    assert_eq!(message, r#"parameter "text" (argument 1) must be a string, but number was given"#);
+    let message: StdString = mv8.eval("try { repeat('ab'); } catch (e) { e.message }").unwrap();
+    let expected = r#"parameter "count" (argument 2) must be a number, but undefined was given"#;
+    assert_eq!(message, expected);
+}
+
+#[test]
+fn reentrancy_limit() {
+    let mv8 = MiniV8::new();
+    mv8.set_max_reentrancy_depth(8);
+    let host = mv8.create_function(|inv| {
+        let (depth,): (u32,) = inv.args.into(&inv.mv8)?;
+        let js: Function = inv.mv8.global().get("js")?;
+        js.call::<_, u32>((depth + 1,))
+    });
+    mv8.global().set("host", host).unwrap();
+    let (depth, name): (u32, StdString) = mv8.eval::<_, Array>(r#"
+        let deepest = 0;
+        function js(depth) {
+            deepest = depth;
+            return host(depth);
+        }
+        let name;
+        try { js(1); } catch (e) { name = e.name; }
+        [deepest, name]
+    "#).unwrap().elements().collect::<Result<Vec<Value>>>().unwrap()
+        .into_iter().collect::<Values>().into(&mv8).unwrap();
+    assert_eq!(depth, 9);
+    assert_eq!(name, "RangeError");
+
+    // Calls within the limit still work afterwards:
+    let result: u32 = mv8.eval("function js(depth) { return depth; }; host(1)").unwrap();
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn create_function_fast() {
+    fn sum(mv8: &MiniV8, args: &[Value]) -> Result<Value> {
+        let mut sum = 0.0;
+        for arg in args {
+            sum += arg.coerce_number(mv8)?;
+        }
+        Ok(Value::Number(sum))
+    }
+
+    fn fail(_: &MiniV8, _: &[Value]) -> Result<Value> {
+        Err(Error::ExternalError("failed".into()))
+    }
+
+    let mv8 = MiniV8::new();
+    mv8.global().set("sum", mv8.create_function_fast(sum)).unwrap();
+    mv8.global().set("fail", mv8.create_function_fast(fail)).unwrap();
+    let result: f64 = mv8.eval("sum(1, 2, '3')").unwrap();
+    assert_eq!(result, 6.0);
+    let message: StdString = mv8.eval("try { fail() } catch (e) { e.message }").unwrap();
+    assert_eq!(message, "failed");
+}
+
+#[test]
+fn create_function_fast_f64() {
+    let mv8 = MiniV8::new();
+    let hypot = mv8.create_function_fast_f64(|a, b| (a * a + b * b).sqrt());
+    mv8.global().set("hypot", hypot).unwrap();
+    // Enough calls with numbers for the loop to be optimized, which takes the fast path:
+    let result: f64 = mv8.eval(r#"
+        let total = 0;
+        for (let i = 0; i < 1000000; i++) {
+            total += hypot(3, 4);
+        }
+        total
+    "#).unwrap();
+    assert_eq!(result, 5_000_000.0);
+
+    // Arguments that aren't numbers take the ordinary path:
+    let result: f64 = mv8.eval("hypot('3', { valueOf() { return 4; } })").unwrap();
+    assert_eq!(result, 5.0);
+    let result: f64 = mv8.eval("hypot(3)").unwrap();
+    assert!(result.is_nan());
+    let length: u32 = mv8.eval("hypot.length").unwrap();
+    assert_eq!(length, 2);
+    let thrown: StdString = mv8.eval(r#"
+        try { hypot({ valueOf() { throw 'thrown'; } }, 0) } catch (e) { e }
+    "#).unwrap();
+    assert_eq!(thrown, "thrown");
+}
+
+#[test]
+fn create_function_shaped() {
+    let mv8 = MiniV8::new();
+    let shape = ObjectShape::new(&["index", "square", "label", "even"]);
+    let func = mv8.create_function_shaped(&shape, |inv, result| {
+        let (index,): (u32,) = inv.args.into(&inv.mv8)?;
+        result.set("index", index)?;
+        result.set("square", index * index)?;
+        result.set("label", format!("#{}", index))?;
+        // Fields that aren't set stay `undefined` rather than carrying over from earlier calls:
+        if index % 2 == 0 {
+            result.set("even", true)?;
+        }
+        if index == 999 {
+            return Err(Error::ExternalError("too big".into()));
+        }
+        Ok(())
+    });
+    mv8.global().set("func", func).unwrap();
+    let ok: bool = mv8.eval(r#"
+        const results = [];
+        for (let i = 0; i < 999; i++) results.push(func(i));
+        results.every((r, i) => r.index === i && r.square === i * i && r.label === '#' + i &&
+            r.even === (i % 2 === 0 ? true : undefined) &&
+            JSON.stringify(Object.keys(r)) === '["index","square","label","even"]') &&
+            new Set(results).size === 999
+    "#).unwrap();
+    assert!(ok);
+    let message: StdString = mv8.eval("try { func(999) } catch (e) { e.message }").unwrap();
+    assert_eq!(message, "too big");
+}
+
+#[test]
+fn metadata() {
+    let mv8 = MiniV8::new();
+    let origin = ScriptOrigin { name: "plugin.js".to_owned(), ..Default::default() };
+    let source = "\n\nfunction handle(event, context) {\n  return event;\n}\nhandle";
+    let script = Script { source: source.to_owned(), origin: Some(origin), ..Default::default() };
+    let handle: Function = mv8.eval(script).unwrap();
+    assert_eq!(handle.name(), "handle");
+    assert_eq!(handle.arity(), 2);
+    assert_eq!(handle.source().unwrap(), "function handle(event, context) {\n  return event;\n}");
+    assert_eq!(handle.script_origin(), Some(("plugin.js".to_owned(), 3)));
+
+    let anonymous: Function = mv8.eval("(a, b = 1, ...rest) => a").unwrap();
+    assert_eq!(anonymous.name(), "");
+    assert_eq!(anonymous.arity(), 1);
+    assert_eq!(anonymous.source().unwrap(), "(a, b = 1, ...rest) => a");
+    assert_eq!(anonymous.script_origin(), Some((StdString::new(), 1)));
+
+    // Redefining `name` and `length` doesn't run any JavaScript:
+    let redefined: Function = mv8.eval(r#"
+        function f(a) {}
+        Object.defineProperty(f, 'name', { value: 'other' });
+        Object.defineProperty(f, 'length', { get() { throw new Error('called'); } });
+        f
+    "#).unwrap();
+    assert_eq!(redefined.name(), "f");
+    assert_eq!(redefined.arity(), 0);
+
+    // Functions that weren't defined by a script have no source or origin:
+    let builtin: Function = mv8.eval("Math.max").unwrap();
+    assert_eq!(builtin.name(), "max");
+    assert_eq!(builtin.arity(), 2);
+    let bound: Function = mv8.eval("(function g(a, b) {}).bind(null, 1)").unwrap();
+    assert_eq!(bound.arity(), 1);
+    let native = mv8.create_function(|_| Ok(()));
+    for function in [builtin, bound, native] {
+        assert_eq!(function.source(), None);
+        assert_eq!(function.script_origin(), None);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::*;
+
+#[test]
+fn heap_statistics() {
+    let mv8 = MiniV8::new();
+    mv8.low_memory_notification();
+    let before = mv8.heap_statistics();
+    assert!(before.used_heap_size > 0);
+    assert!(before.used_heap_size <= before.total_heap_size);
+    assert!(before.total_heap_size <= before.heap_size_limit);
+    assert!(before.number_of_native_contexts >= 1);
+
+    let _: () = mv8.eval("globalThis.garbage = Array.from({ length: 100000 }, (_, i) => ({ i }))")
+        .unwrap();
+    let grown = mv8.heap_statistics();
+    assert!(grown.used_heap_size > before.used_heap_size + 1_000_000);
+
+    let _: () = mv8.eval("delete globalThis.garbage").unwrap();
+    mv8.low_memory_notification();
+    let shrunk = mv8.heap_statistics();
+    assert!(shrunk.used_heap_size < grown.used_heap_size - 1_000_000);
+}
+
+#[test]
+fn adjust_external_memory() {
+    let mv8 = MiniV8::new();
+    let before = mv8.heap_statistics().external_memory as i64;
+    let total = mv8.adjust_external_memory(10_000_000);
+    assert_eq!(total, before + 10_000_000);
+    assert_eq!(mv8.heap_statistics().external_memory as i64, total);
+    assert_eq!(mv8.adjust_external_memory(-10_000_000), before);
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use std::string::String as StdString;
+
+#[test]
+fn format_number() {
+    let mv8 = MiniV8::new();
+    for locale in &["en-US", "de-DE", "hi-IN"] {
+        let expected: StdString = mv8.eval(format!("(1234567.891).toLocaleString('{}')", locale))
+            .unwrap();
+        let formatted = mv8.format_number(1234567.891, locale, &Default::default()).unwrap();
+        assert_eq!(formatted, expected);
+    }
+
+    let options = NumberFormatOptions {
+        style: Some("currency".to_string()),
+        currency: Some("EUR".to_string()),
+        ..Default::default()
+    };
+    let expected: StdString = mv8.eval(r#"
+        (0.5).toLocaleString('de-DE', { style: 'currency', currency: 'EUR' })
+    "#).unwrap();
+    assert_eq!(mv8.format_number(0.5, "de-DE", &options).unwrap(), expected);
+    // Cached formatters give the same results:
+    assert_eq!(mv8.format_number(0.5, "de-DE", &options).unwrap(), expected);
+}
+
+#[test]
+fn format_date() {
+    let mv8 = MiniV8::new();
+    let options = DateFormatOptions {
+        date_style: Some("long".to_string()),
+        time_style: Some("short".to_string()),
+        time_zone: Some("UTC".to_string()),
+        ..Default::default()
+    };
+    for locale in &["en-US", "fr-FR"] {
+        let expected: StdString = mv8.eval(format!(r#"
+            new Date(1e12).toLocaleString('{}', {{
+                dateStyle: 'long',
+                timeStyle: 'short',
+                timeZone: 'UTC',
+            }})
+        "#, locale)).unwrap();
+        assert_eq!(mv8.format_date(Value::Date(1e12), locale, &options).unwrap(), expected);
+    }
+}
+
+#[test]
+fn format_tampered() {
+    let mv8 = MiniV8::new();
+    let expected = mv8.format_number(1234.5, "en-US", &Default::default()).unwrap();
+    mv8.eval::<_, ()>(r#"
+        Object.defineProperty(Intl.NumberFormat.prototype, 'format', { get: () => 1 });
+        Object.defineProperty(Intl.DateTimeFormat.prototype, 'format', {
+            get: () => () => 'tampered',
+        });
+    "#).unwrap();
+    // Scripts replacing the `format` getters affect neither new nor cached formatters:
+    assert_eq!(mv8.format_number(1234.5, "en-US", &Default::default()).unwrap(), expected);
+    let formatted = mv8.format_number(1234.5, "de-DE", &Default::default()).unwrap();
+    assert_eq!(formatted, "1.234,5");
+    let formatted = mv8.format_date(Value::Date(0.0), "en-US", &Default::default()).unwrap();
+    assert_ne!(formatted, "tampered");
+}
+
+#[test]
+fn format_invalid() {
+    let mv8 = MiniV8::new();
+    let error = mv8.format_number(1.0, "not a locale", &Default::default()).unwrap_err();
+    assert_range_error(&error);
+    let options = DateFormatOptions {
+        time_zone: Some("Mars/Olympus".to_string()),
+        ..Default::default()
+    };
+    let error = mv8.format_date(Value::Date(0.0), "en-US", &options).unwrap_err();
+    assert_range_error(&error);
+    let error = mv8.format_date(Value::Number(f64::NAN), "en-US", &Default::default()).unwrap_err();
+    assert_range_error(&error);
+}
+
+fn assert_range_error(error: &Error) {
+    match error {
+        Error::Value(Value::Object(object)) => {
+            let name: StdString = object.get("name").unwrap();
+            assert_eq!(name, "RangeError");
+        },
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
@@ -0,0 +1,107 @@
+use crate::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn exec() {
+    let pool = IsolatePool::new(2);
+    assert_eq!(pool.size(), 2);
+    // Calls alternate between the pool's isolates:
+    let contexts: Vec<u64> = (0..4).map(|_| pool.exec(|mv8| mv8.context_id()).unwrap()).collect();
+    assert_ne!(contexts[0], contexts[1]);
+    assert_eq!(contexts[0], contexts[2]);
+    assert_eq!(contexts[1], contexts[3]);
+
+    let pool = Arc::new(pool);
+    let threads: Vec<_> = (0..4u32).map(|i| {
+        let pool = pool.clone();
+        thread::spawn(move || {
+            pool.exec(move |mv8| mv8.eval::<_, u32>(format!("{} * 2", i)).unwrap()).unwrap()
+        })
+    }).collect();
+    let results: Vec<u32> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    assert_eq!(results, [0, 2, 4, 6]);
+}
+
+#[test]
+fn exec_panic() {
+    let pool = IsolatePool::new(1);
+    pool.exec(|mv8| mv8.eval::<_, ()>("globalThis.a = 1").unwrap()).unwrap();
+    let exec = AssertUnwindSafe(|| pool.exec(|_| panic!("oops")));
+    let result = panic::catch_unwind(exec);
+    assert!(result.is_err());
+    // The isolate is replaced after a panic:
+    let a = pool.exec(|mv8| mv8.global().get::<_, Value>("a").unwrap().is_undefined()).unwrap();
+    assert!(a);
+}
+
+#[test]
+fn drop_on_another_thread() {
+    let pool = IsolatePool::with_builder(2, MiniV8::builder().max_heap_size(64 << 20));
+    pool.exec(|mv8| {
+        let object: Object = mv8.eval("({ a: 1 })").unwrap();
+        mv8.set_user_data("object", object);
+    }).unwrap();
+    thread::spawn(move || drop(pool)).join().unwrap();
+
+    // Pools can be created, used and dropped on threads other than the main one, too:
+    thread::spawn(|| {
+        let pool = IsolatePool::new(1);
+        assert_eq!(pool.exec(|mv8| mv8.eval::<_, u32>("1 + 1").unwrap()).unwrap(), 2);
+    }).join().unwrap();
+}
+
+#[test]
+fn exec_from_own_job() {
+    let pool = Arc::new(IsolatePool::new(2));
+    let inner = pool.clone();
+    let reentered = pool.exec(move |_| {
+        matches!(inner.exec(|mv8| mv8.context_id()), Err(Error::PoolReentrancy))
+    }).unwrap();
+    assert!(reentered);
+
+    // Other pools can still be used from within a job:
+    let other = Arc::new(IsolatePool::new(1));
+    let two = pool.exec(move |_| other.exec(|mv8| mv8.eval::<_, u32>("1 + 1").unwrap()).unwrap())
+        .unwrap();
+    assert_eq!(two, 2);
+}
+
+#[test]
+fn drop_from_own_thread() {
+    // Holds a pool until `release` is signalled, and then drops it:
+    struct Holder {
+        pool: Option<Arc<IsolatePool>>,
+        release: mpsc::Receiver<()>,
+        dropped: mpsc::Sender<()>,
+    }
+
+    impl Drop for Holder {
+        fn drop(&mut self) {
+            let _ = self.release.recv();
+            self.pool.take();
+            let _ = self.dropped.send(());
+        }
+    }
+
+    let pool = Arc::new(IsolatePool::new(1));
+    let (release, release_receiver) = mpsc::channel();
+    let (dropped_sender, dropped) = mpsc::channel();
+    let held = pool.clone();
+    pool.exec(move |mv8| {
+        mv8.set_user_data("holder", Holder {
+            pool: Some(held),
+            release: release_receiver,
+            dropped: dropped_sender,
+        });
+    }).unwrap();
+    // A panic makes the pool's thread replace its `MiniV8`, dropping the holder on that thread:
+    let result = thread::spawn(move || pool.exec(|_| panic!("oops")).is_ok()).join();
+    assert!(result.is_err());
+    // The holder now has the last reference to the pool, which it drops without waiting for the
+    // thread that it is dropped on:
+    release.send(()).unwrap();
+    dropped.recv_timeout(Duration::from_secs(10)).unwrap();
+}
@@ -0,0 +1,58 @@
+use crate::*;
+use std::string::String as StdString;
+use std::sync::Arc;
+
+#[test]
+fn create_lazy_array() {
+    let mv8 = MiniV8::new();
+    let data: Vec<StdString> = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let array = mv8.create_lazy_array(Arc::new(data));
+    mv8.global().set("lazy", array).unwrap();
+    let result: StdString = mv8.eval(r#"
+        JSON.stringify([
+            lazy.length,
+            lazy[0],
+            lazy[2],
+            lazy[3],
+            [...lazy],
+            Object.keys(lazy),
+            1 in lazy,
+            3 in lazy,
+            Array.isArray(lazy),
+        ])
+    "#).unwrap();
+    assert_eq!(result, r#"[3,"a","c",null,["a","b","c"],["0","1","2"],true,false,false]"#);
+
+    for source in &["lazy[0] = 'x'", "lazy[5] = 'x'", "delete lazy[0]",
+        "Object.defineProperty(lazy, 0, { value: 'x' })"] {
+        let name: StdString = mv8.eval(format!("try {{ {}; }} catch (e) {{ e.name }}", source))
+            .unwrap();
+        assert_eq!(name, "TypeError", "{}", source);
+    }
+    let first: StdString = mv8.eval("lazy[0]").unwrap();
+    assert_eq!(first, "a");
+}
+
+#[test]
+fn create_lazy_array_memory() {
+    let mv8 = MiniV8::new();
+    let data: Vec<Vec<u32>> = (0..500_000).map(|i| vec![i, i * 2]).collect();
+    let used_heap_size = |mv8: &MiniV8| mv8.scope(|scope| {
+        scope.low_memory_notification();
+        let mut stats = v8::HeapStatistics::default();
+        scope.get_heap_statistics(&mut stats);
+        stats.used_heap_size()
+    });
+    let before = used_heap_size(&mv8);
+    let array = mv8.create_lazy_array(Arc::new(data));
+    mv8.global().set("lazy", array).unwrap();
+    let sum: u32 = mv8.eval(r#"
+        globalThis.touched = [];
+        for (let i = 0; i < 10; i++) touched.push(lazy[i * 50000]);
+        touched.reduce((sum, pair) => sum + pair[1], 0) + lazy.length
+    "#).unwrap();
+    assert_eq!(sum, 4_500_000 + 500_000);
+    // Converting all 500,000 elements would take tens of megabytes:
+    let after = used_heap_size(&mv8);
+    assert!(after < before + 1024 * 1024, "heap grew from {} to {}", before, after);
+}
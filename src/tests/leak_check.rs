@@ -0,0 +1,48 @@
+use crate::*;
+
+#[test]
+fn leak_check() {
+    let mv8 = MiniV8::new();
+    let report = mv8.leak_check();
+    assert!(report.is_clean(), "{}", report);
+    assert_eq!(report.to_string(), "no leaks");
+
+    let line = line!() + 1;
+    let func = mv8.create_function(|_| Ok(()));
+    mv8.global().set("func", func).unwrap();
+    mv8.set_user_data("data", 1);
+    let _: u32 = mv8.eval_cached("1").unwrap();
+    let report = mv8.leak_check();
+    assert!(!report.is_clean());
+    assert_eq!(report.functions.len(), 1);
+    assert_eq!(report.functions[0].file(), file!());
+    assert_eq!(report.functions[0].line(), line);
+    assert_eq!(report.user_data, vec!["data".to_string()]);
+    assert_eq!(report.cached_scripts, 1);
+    // The cached script is held through a global handle:
+    assert!(report.global_handles_size > 0);
+    assert_eq!(report.to_string(), format!(
+        "leaks:\n  function created at {}\n  user data \"data\"\n  1 cached script(s)\n  \
+        {} byte(s) of global handles",
+        report.functions[0],
+        report.global_handles_size,
+    ));
+
+    let _: bool = mv8.eval("delete globalThis.func").unwrap();
+    mv8.remove_user_data("data");
+    mv8.clear_script_cache();
+    let report = mv8.leak_check();
+    assert!(report.is_clean(), "{}", report);
+
+    // Values still held on the Rust side are reported through their global handles:
+    let object = mv8.create_object();
+    let report = mv8.leak_check();
+    assert!(report.global_handles_size > 0);
+    assert_eq!(report.to_string(), format!(
+        "leaks:\n  {} byte(s) of global handles",
+        report.global_handles_size,
+    ));
+    drop(object);
+    let report = mv8.leak_check();
+    assert!(report.is_clean(), "{}", report);
+}
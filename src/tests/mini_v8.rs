@@ -2,6 +2,7 @@ use crate::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::string::String as StdString;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[test]
@@ -20,6 +21,36 @@ fn eval_origin() {
     assert_eq!("ReferenceError: MISSING_VAR is not defined at eval_origin:124:463", result);
 }
 
+#[test]
+fn error_location() {
+    let mv8 = MiniV8::new();
+    let error = mv8.eval::<_, Value>(Script {
+        source: "\n  MISSING_VAR".to_owned(),
+        origin: Some(ScriptOrigin {
+            name: "error_location".to_owned(),
+            line_offset: 10,
+            column_offset: 0,
+        }),
+        ..Default::default()
+    }).unwrap_err();
+    let expected = ErrorLocation {
+        resource_name: "error_location".to_owned(),
+        line: 12,
+        column: 2,
+    };
+    assert_eq!(error.location(&mv8), Some(expected));
+
+    // Syntax errors are located too, and scripts without an origin have no resource name:
+    let error = mv8.eval::<_, Value>("1 +\n)").unwrap_err();
+    let location = error.location(&mv8).unwrap();
+    assert_eq!(location.resource_name, "");
+    assert_eq!((location.line, location.column), (2, 0));
+
+    let error = mv8.eval::<_, Value>("throw 'oops'").unwrap_err();
+    assert_eq!(error.location(&mv8), None);
+    assert_eq!(Error::Timeout.location(&mv8), None);
+}
+
 #[test]
 fn eval_timeout() {
     let mv8 = MiniV8::new();
@@ -39,6 +70,179 @@ fn eval_timeout() {
     assert!(a > 0.0);
 }
 
+#[test]
+fn interrupt_handle() {
+    let mv8 = MiniV8::new();
+    let handle = mv8.interrupt_handle();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(handle.terminate());
+    });
+    let result = mv8.eval::<_, Value>("a = 0; while (true) { a++; }");
+    thread.join().unwrap();
+    match result {
+        Err(Error::Terminated) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+
+    // Make sure we can still evaluate and call functions again:
+    let a: f64 = mv8.eval("a").unwrap();
+    assert!(a > 0.0);
+    let spin: Function = mv8.eval("() => { while (true) {} }").unwrap();
+    let handle = mv8.interrupt_handle();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        handle.terminate();
+    });
+    let result = spin.call::<_, Value>(());
+    thread.join().unwrap();
+    match result {
+        Err(Error::Terminated) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+    let two: u32 = mv8.eval("1 + 1").unwrap();
+    assert_eq!(two, 2);
+
+    // Timeouts are still reported as such:
+    let result = mv8.eval::<_, Value>(Script {
+        source: "while (true) {}".to_owned(),
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+    match result {
+        Err(Error::Timeout) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+
+    // Terminating while nothing is running doesn't affect what runs next:
+    assert!(mv8.interrupt_handle().terminate());
+    let two: u32 = mv8.eval("1 + 1").unwrap();
+    assert_eq!(two, 2);
+}
+
+#[test]
+fn terminate_racing_idle() {
+    let mv8 = MiniV8::new();
+    let handle = mv8.interrupt_handle();
+    let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let thread = std::thread::spawn({
+        let done = done.clone();
+        move || {
+            while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                handle.terminate();
+            }
+        }
+    });
+    // Scripts that happen to be running when `terminate` is called are terminated, but a
+    // termination requested while idle must never leak into the next script as a timeout:
+    for _ in 0..10_000 {
+        match mv8.eval::<_, u32>("1 + 1") {
+            Ok(2) | Err(Error::Terminated) => {},
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+    done.store(true, std::sync::atomic::Ordering::Relaxed);
+    thread.join().unwrap();
+    let two: u32 = mv8.eval("1 + 1").unwrap();
+    assert_eq!(two, 2);
+}
+
+#[test]
+#[allow(deprecated)]
+fn terminate_handle() {
+    let mv8 = MiniV8::new();
+    let handle: TerminateHandle = mv8.terminate_handle();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(handle.terminate());
+    });
+    let result = mv8.eval::<_, Value>("while (true) {}");
+    thread.join().unwrap();
+    match result {
+        Err(Error::Terminated) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn request_interrupt() {
+    let mv8 = MiniV8::new();
+    let handle = mv8.interrupt_handle();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        let terminate = handle.clone();
+        assert!(handle.request_interrupt(move || {
+            sender.send(std::thread::current().id()).unwrap();
+            terminate.terminate();
+        }));
+    });
+    let result = mv8.eval::<_, Value>("while (true) {}");
+    thread.join().unwrap();
+    match result {
+        Err(Error::Terminated) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+    // The interrupt ran on the thread that was running the JavaScript:
+    assert_eq!(receiver.recv().unwrap(), std::thread::current().id());
+}
+
+#[test]
+fn request_interrupt_never_run() {
+    let mv8 = MiniV8::new();
+    let handle = mv8.interrupt_handle();
+    let captured = Arc::new(());
+    let held = captured.clone();
+    assert!(handle.request_interrupt(move || drop(held)));
+    assert_eq!(Arc::strong_count(&captured), 2);
+    // No JavaScript runs before the `MiniV8` is dropped, which drops the closure:
+    drop(mv8);
+    assert_eq!(Arc::strong_count(&captured), 1);
+    assert!(!handle.request_interrupt(|| {}));
+}
+
+#[test]
+fn max_heap_size() {
+    let mv8 = MiniV8::builder().max_heap_size(32 << 20).build();
+    for _ in 0..2 {
+        let result = mv8.eval::<_, Value>(r#"
+            (() => {
+                const arrays = [];
+                while (true) {
+                    arrays.push(new Array(100000).fill(1.5));
+                }
+            })()
+        "#);
+        match result {
+            Err(Error::OutOfMemory) => {},
+            _ => panic!("unexpected result: {:?}", result),
+        }
+
+        // Make sure we can still evaluate small scripts:
+        let sum: u32 = mv8.eval("[1, 2, 3].reduce((a, b) => a + b)").unwrap();
+        assert_eq!(sum, 6);
+    }
+}
+
+#[test]
+fn stack_overflow() {
+    let mv8 = MiniV8::new();
+    let recurse: Function = mv8.eval("function f() { return f(); } f").unwrap();
+    for _ in 0..2 {
+        let from_eval = mv8.eval::<_, Value>("f()").unwrap_err();
+        let from_call = recurse.call::<_, Value>(()).unwrap_err();
+        for error in [from_eval, from_call] {
+            let js_error = error.js_error().unwrap();
+            assert_eq!(js_error.name, "RangeError");
+            assert_eq!(js_error.message, "Maximum call stack size exceeded");
+        }
+
+        // Make sure we can still evaluate scripts, including ones that recurse a little:
+        let depth: u32 = mv8.eval("(function g(n) { return n && 1 + g(n - 1); })(1000)").unwrap();
+        assert_eq!(depth, 1000);
+    }
+}
+
 #[test]
 fn eval_wasm() {
     let mv8 = MiniV8::new();
@@ -95,6 +299,22 @@ fn user_data_get() {
     });
 }
 
+#[test]
+fn user_data_from_callback() {
+    let mv8 = MiniV8::new();
+    mv8.set_user_data("prefix", "item-".to_string());
+    let label = mv8.create_function(|inv| {
+        let (index,): (u32,) = inv.args.into(&inv.mv8)?;
+        let prefix = inv.mv8.use_user_data("prefix", |prefix: Option<&StdString>| {
+            prefix.cloned().unwrap_or_default()
+        });
+        Ok(format!("{}{}", prefix, index))
+    });
+    mv8.global().set("label", label).unwrap();
+    let value: StdString = mv8.eval("label(3)").unwrap();
+    assert_eq!(value, "item-3");
+}
+
 #[test]
 fn user_data_remove() {
     let mv8 = MiniV8::new();
@@ -109,6 +329,49 @@ fn user_data_remove() {
     assert_eq!(*count.borrow(), 1000);
 }
 
+#[test]
+fn idle_tasks() {
+    let mv8 = MiniV8::new();
+    // Nothing to do yet:
+    assert!(mv8.run_idle_tasks(Duration::from_secs(5)) < Duration::from_secs(5));
+    assert!(mv8.pump_platform_tasks(Duration::from_secs(5)) < Duration::from_secs(5));
+
+    let _: () = mv8.eval("for (let i = 0; i < 100000; i++) { ({ i }); }").unwrap();
+    let _ = mv8.run_idle_tasks(Duration::from_millis(10));
+    let _ = mv8.pump_platform_tasks(Duration::from_millis(10));
+    let value: u32 = mv8.eval("1 + 1").unwrap();
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn teardown_order() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mv8 = MiniV8::new();
+    mv8.set_user_data("data", DropLog("user data", log.clone()));
+    let callback_log = DropLog("callback", log.clone());
+    let persistent = mv8.scope(|scope| {
+        let object = v8::Object::new(scope);
+        v8::Global::new(scope, object)
+    });
+    let func = mv8.create_function(move |_| {
+        let _ = (&callback_log, &persistent);
+        Ok(())
+    });
+    mv8.global().set("func", func).unwrap();
+    let _: () = mv8.eval("func()").unwrap();
+    assert!(log.borrow().is_empty());
+    drop(mv8);
+    assert_eq!(*log.borrow(), vec!["callback", "user data"]);
+}
+
+struct DropLog(&'static str, Rc<RefCell<Vec<&'static str>>>);
+
+impl Drop for DropLog {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
 struct TestUserData {
     count: Rc<RefCell<usize>>,
 }
@@ -133,3 +396,228 @@ fn make_test_user_data() -> (Rc<RefCell<usize>>, TestUserData) {
     let count = Rc::new(RefCell::new(0));
     (count.clone(), TestUserData { count })
 }
+
+#[test]
+fn global_allowlist() {
+    let allowlist = ["JSON", "Math", "Array", "Object", "String", "Number", "Boolean", "Error"];
+    let mv8 = MiniV8::builder().global_allowlist(&allowlist).build();
+
+    for name in &allowlist {
+        assert!(mv8.global().has(*name).unwrap(), "{} should be present", name);
+    }
+
+    for name in &["Date", "Function", "WebAssembly", "Atomics", "Intl", "eval", "globalThis"] {
+        assert!(!mv8.global().has(*name).unwrap(), "{} should be absent", name);
+        let type_of: StdString = mv8.eval(format!("typeof {}", name)).unwrap();
+        assert_eq!(type_of, "undefined");
+    }
+
+    // The function constructors can't be reached through function instances either:
+    let escaped: bool = mv8.eval(r#"
+        [
+            ({}).constructor.constructor,
+            (function() {}).constructor,
+            (() => {}).constructor,
+            (async function() {}).constructor,
+            (function*() {}).constructor,
+            (async function*() {}).constructor,
+        ].some(c => c !== Object)
+    "#).unwrap();
+    assert!(!escaped);
+
+    // Allowed globals still work, as do the crate's own operations:
+    let json: StdString = mv8.eval("JSON.stringify([Math.max(1, 2), 'a'.repeat(2)])").unwrap();
+    assert_eq!(json, "[2,\"aa\"]");
+    let array: Array = mv8.eval("[1, 2, 3]").unwrap();
+    assert_eq!(array.slice(1, 3).unwrap().len(), 2);
+}
+
+#[test]
+fn global_allowlist_function() {
+    let mv8 = MiniV8::builder().global_allowlist(&["Function"]).build();
+    assert!(!mv8.global().has("Object").unwrap());
+    let value: f64 = mv8.eval("(function() {}).constructor('return 1 + 1')()").unwrap();
+    assert_eq!(value, 2.0);
+}
+
+#[test]
+fn compile_function() {
+    let mv8 = MiniV8::new();
+    let add = mv8.compile_function("return a + b;", &["a", "b"], None).unwrap();
+    let result: usize = add.call((1, 2)).unwrap();
+    assert_eq!(result, 3);
+
+    // Compiling many functions leaves no handles behind once the functions are dropped:
+    let used_global_handles = |mv8: &MiniV8| mv8.scope(|scope| {
+        let mut stats = v8::HeapStatistics::default();
+        scope.get_heap_statistics(&mut stats);
+        stats.used_global_handles_size()
+    });
+    let before = used_global_handles(&mv8);
+    for i in 0..10_000 {
+        let func = mv8.compile_function(&format!("return k + {};", i), &["k"], None).unwrap();
+        let result: usize = func.call((1,)).unwrap();
+        assert_eq!(result, i + 1);
+    }
+    assert_eq!(used_global_handles(&mv8), before);
+}
+
+#[test]
+fn compile_function_origin() {
+    let mv8 = MiniV8::new();
+    let origin = ScriptOrigin {
+        name: "compile_origin".to_owned(),
+        line_offset: 10,
+        column_offset: 0,
+    };
+    let body = "\ntry { MISSING_VAR } catch (e) { return e.stack; }";
+    let func = mv8.compile_function(body, &[], Some(origin)).unwrap();
+    let stack: StdString = func.call(()).unwrap();
+    assert!(stack.contains("compile_origin:12:"), "unexpected stack: {}", stack);
+}
+
+#[test]
+fn compile_function_syntax_error() {
+    let mv8 = MiniV8::new();
+    match mv8.compile_function("return )", &[], None) {
+        Err(Error::Value(value)) => {
+            let name: StdString = value.as_object().unwrap().get("name").unwrap();
+            assert_eq!(name, "SyntaxError");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(mv8.compile_function("", &["not a name"], None).is_err());
+}
+
+#[test]
+fn compile_function_with_extensions() {
+    let mv8 = MiniV8::new();
+    let outer = mv8.create_object();
+    outer.set("x", 1).unwrap();
+    outer.set("y", 10).unwrap();
+    let inner = mv8.create_object();
+    inner.set("x", 2).unwrap();
+    let extensions = [outer, inner.clone()];
+    let func = mv8.compile_function_with_extensions("return x + y;", &[], &extensions, None)
+        .unwrap();
+    let result: usize = func.call(()).unwrap();
+    assert_eq!(result, 12);
+    inner.set("x", 3).unwrap();
+    let result: usize = func.call(()).unwrap();
+    assert_eq!(result, 13);
+}
+
+#[test]
+fn cached_value() {
+    let mv8 = MiniV8::new();
+    let inits = Rc::new(RefCell::new(0));
+    let get = || mv8.cached_value("status", |mv8| {
+        *inits.borrow_mut() += 1;
+        mv8.eval("Object.freeze({ status: 'active' })")
+    }).unwrap();
+
+    let is_same: Function = mv8.eval("(a, b) => a === b").unwrap();
+    let first = get();
+    for _ in 0..1000 {
+        let same: bool = is_same.call((first.clone(), get())).unwrap();
+        assert!(same);
+        let _: () = mv8.eval("for (let i = 0; i < 10; i++) [i]").unwrap();
+    }
+    assert_eq!(*inits.borrow(), 1);
+
+    mv8.clear_value_cache();
+    let same: bool = is_same.call((first, get())).unwrap();
+    assert!(!same);
+    assert_eq!(*inits.borrow(), 2);
+
+    // Failed initializations cache nothing:
+    assert!(mv8.cached_value("error", |_| Err(Error::InvalidTimeout)).is_err());
+    let value = mv8.cached_value("error", |_| Ok(Value::Number(1.0))).unwrap();
+    assert_eq!(value.as_number(), Some(1.0));
+}
+
+#[test]
+fn context_ids() {
+    let a = MiniV8::new();
+    let b = MiniV8::new();
+    assert_ne!(a.context_id(), b.context_id());
+    assert_eq!(a.context_id(), a.clone().context_id());
+
+    let object = Value::Object(a.create_object());
+    assert_eq!(object.origin_context(), Some(a.context_id()));
+    assert_eq!(Value::Number(1.0).origin_context(), None);
+    assert!(a.assert_owns(&object).is_ok());
+    assert!(b.assert_owns(&Value::Number(1.0)).is_ok());
+    match b.assert_owns(&object) {
+        Err(Error::ForeignValue { context, origin }) => {
+            assert_eq!(context, b.context_id());
+            assert_eq!(origin, a.context_id());
+        },
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn foreign_values() {
+    let a = MiniV8::new();
+    let b = MiniV8::new();
+    let foreign = a.create_object();
+    let is_foreign = |result: Result<()>| matches!(result, Err(Error::ForeignValue { .. }));
+
+    let object = b.create_object();
+    assert!(is_foreign(object.set("a", foreign.clone())));
+    assert!(is_foreign(object.set(foreign.clone(), 1)));
+    assert!(is_foreign(object.get::<_, Value>(foreign.clone()).map(|_| ())));
+    let descriptor = PropertyDescriptor::Data {
+        value: Value::Object(foreign.clone()),
+        writable: true,
+        enumerable: true,
+        configurable: true,
+    };
+    assert!(is_foreign(object.define_property("a", descriptor).map(|_| ())));
+    let function: Function = b.eval("(x) => x").unwrap();
+    assert!(is_foreign(function.call::<_, ()>((foreign.clone(),))));
+    assert!(is_foreign(function.call_method::<_, _, ()>(foreign.clone(), ())));
+    assert!(is_foreign(b.create_array_from(vec![foreign.clone()]).map(|_| ())));
+    assert!(is_foreign(b.json_stringify(Value::Object(foreign.clone())).map(|_| ())));
+    assert!(is_foreign(Value::Object(foreign.clone()).coerce_number(&b).map(|_| ())));
+
+    // A callback that returns a foreign value throws instead:
+    let callback = foreign.clone();
+    let leak = b.create_function(move |_| Ok(callback.clone()));
+    let error = leak.call::<_, Value>(()).unwrap_err();
+    assert!(error.to_string().contains("used in context"), "unexpected error: {}", error);
+
+    // `b` is still usable, and transferred values are accepted:
+    let copy = b.transfer(&Value::Object(foreign)).unwrap();
+    object.set("a", copy).unwrap();
+}
+
+#[test]
+fn json() {
+    let mv8 = MiniV8::new();
+    let value: Value = mv8.eval("({ a: [1, 'two', null], b: { toJSON() { return 3; } } })")
+        .unwrap();
+    let json = mv8.json_stringify(value).unwrap();
+    assert_eq!(json, r#"{"a":[1,"two",null],"b":3}"#);
+    assert_eq!(mv8.json_stringify(Value::String(mv8.create_string("undefined"))).unwrap(),
+        r#""undefined""#);
+
+    let parsed: Object = mv8.json_parse(&json).unwrap().into(&mv8).unwrap();
+    let second: StdString = parsed.get::<_, Array>("a").unwrap().get(1).unwrap();
+    assert_eq!(second, "two");
+    assert!(mv8.json_parse("{ a: 1 }").is_err());
+
+    let circular: Value = mv8.eval("const circular = {}; circular.self = circular; circular")
+        .unwrap();
+    match mv8.json_stringify(circular) {
+        Err(Error::Value(_)) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let function: Value = mv8.eval("(() => {})").unwrap();
+    match mv8.json_stringify(function) {
+        Err(Error::FromJsConversionError { from: "function", to: "JSON" }) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+    assert!(mv8.json_stringify(Value::Undefined).is_err());
+}
@@ -1,7 +1,34 @@
 mod array;
+mod array_buffer;
+mod async_function;
+mod async_iterable;
+#[cfg(feature = "commonjs")] mod commonjs;
+mod compiled_script;
+mod console;
 mod conversion;
+mod coverage;
+mod error;
+#[cfg(feature = "futures")] mod event_channel;
+mod external;
 mod function;
+mod heap;
+#[cfg(feature = "intl")] mod intl;
+mod isolate_pool;
+mod lazy_array;
+mod leak_check;
 mod mini_v8;
+mod module;
 mod object;
+mod patch;
+mod pluck;
+mod promise;
+mod regex_cache;
+mod sandbox;
+mod script_cache;
+mod snapshot;
 mod string;
+mod symbol;
+mod syntax;
+mod transfer;
 mod value;
+#[cfg(feature = "serde")] mod value_serde;
@@ -0,0 +1,294 @@
+use crate::*;
+use std::string::String as StdString;
+
+#[test]
+fn evaluate() {
+    let mv8 = MiniV8::new();
+    mv8.compile_module("math", "export function add(a, b) { return a + b; }").unwrap();
+    let module = mv8.compile_module("main", r#"
+        import { add } from "math";
+        export const value = add(1, 2);
+    "#).unwrap();
+    assert_eq!(module.specifier(), "main");
+    let _: Value = module.evaluate().unwrap();
+    let value: usize = module.namespace().unwrap().get("value").unwrap();
+    assert_eq!(value, 3);
+
+    // A module keeps its specifier after another module replaces it:
+    let replaced = mv8.compile_module("main", "export const value = 4;").unwrap();
+    assert_eq!(module.specifier(), "main");
+    assert_eq!(replaced.specifier(), "main");
+}
+
+#[test]
+fn evaluate_error() {
+    let mv8 = MiniV8::new();
+    let module = mv8.compile_module("main", "throw new Error('oops');").unwrap();
+    match module.evaluate::<Value>() {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert_eq!(message, "oops");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn unresolved_import() {
+    let mv8 = MiniV8::new();
+    let module = mv8.compile_module("main", "import { x } from 'missing';").unwrap();
+    match module.instantiate() {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert_eq!(message, "unable to resolve module \"missing\"");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn syntax_error() {
+    let mv8 = MiniV8::new();
+    assert!(mv8.compile_module("main", "export const = 1;").is_err());
+}
+
+#[test]
+fn import_meta() {
+    let mv8 = MiniV8::new();
+    mv8.set_import_meta_callback(|mv8, module, meta| {
+        meta.set("url", format!("file:///{}.js", module.specifier()))?;
+        let env = mv8.create_object();
+        env.set("MODE", "test")?;
+        meta.set("env", env)
+    });
+
+    let source = "export const url = import.meta.url, mode = import.meta.env.MODE;";
+    let a = mv8.compile_module("a", source).unwrap();
+    let b = mv8.compile_module("b", source).unwrap();
+    let _: Value = a.evaluate().unwrap();
+    let _: Value = b.evaluate().unwrap();
+
+    let url: StdString = a.namespace().unwrap().get("url").unwrap();
+    assert_eq!(url, "file:///a.js");
+    let url: StdString = b.namespace().unwrap().get("url").unwrap();
+    assert_eq!(url, "file:///b.js");
+    let mode: StdString = b.namespace().unwrap().get("mode").unwrap();
+    assert_eq!(mode, "test");
+}
+
+#[test]
+fn import_meta_lazy() {
+    let mv8 = MiniV8::new();
+    mv8.set_import_meta_callback(|mv8, _, _| {
+        let calls: f64 = mv8.global().get("calls")?;
+        mv8.global().set("calls", calls + 1.0)
+    });
+    mv8.global().set("calls", 0).unwrap();
+
+    let untouched = mv8.compile_module("untouched", "export const x = 1;").unwrap();
+    let _: Value = untouched.evaluate().unwrap();
+    assert_eq!(mv8.global().get::<_, usize>("calls").unwrap(), 0);
+
+    let touched = mv8.compile_module("touched", "import.meta; import.meta;").unwrap();
+    let _: Value = touched.evaluate().unwrap();
+    assert_eq!(mv8.global().get::<_, usize>("calls").unwrap(), 1);
+}
+
+#[test]
+fn import_meta_error() {
+    let mv8 = MiniV8::new();
+    mv8.set_import_meta_callback(|_, _, _| Err(Error::ExternalError("no meta".into())));
+    let module = mv8.compile_module("main", r#"
+        export let message;
+        try { import.meta } catch (e) { message = e.message; }
+    "#).unwrap();
+    let _: Value = module.evaluate().unwrap();
+    let message: StdString = module.namespace().unwrap().get("message").unwrap();
+    assert_eq!(message, "no meta");
+}
+
+#[test]
+fn requests() {
+    let mv8 = MiniV8::new();
+    let module = mv8.compile_module("main", r#"
+        import { a } from "a";
+        import data from "./data.json" assert { type: "json" };
+        export { b } from "b";
+        import "c" assert { type: "css", extra: "yes" };
+    "#).unwrap();
+
+    let requests = module.requests();
+    let specifiers: Vec<_> = requests.iter().map(|r| r.specifier.as_str()).collect();
+    assert_eq!(specifiers, vec!["a", "./data.json", "b", "c"]);
+    assert!(requests[0].import_assertions.is_empty());
+    assert_eq!(requests[1].import_assertions, vec![("type".to_string(), "json".to_string())]);
+    assert!(requests[2].import_assertions.is_empty());
+    assert_eq!(requests[3].import_assertions, vec![
+        ("type".to_string(), "css".to_string()),
+        ("extra".to_string(), "yes".to_string()),
+    ]);
+
+    // Requests are available without instantiating, and the module is left untouched:
+    assert_eq!(module.status(), ModuleStatus::Uninstantiated);
+    assert!(mv8.compile_module("empty", "").unwrap().requests().is_empty());
+}
+
+#[test]
+fn status() {
+    let mv8 = MiniV8::new();
+    let module = mv8.compile_module("main", "export const x = 1;").unwrap();
+    assert_eq!(module.status(), ModuleStatus::Uninstantiated);
+    module.instantiate().unwrap();
+    assert_eq!(module.status(), ModuleStatus::Instantiated);
+    let _: Value = module.evaluate().unwrap();
+    assert_eq!(module.status(), ModuleStatus::Evaluated);
+    assert!(module.error().is_none());
+}
+
+#[test]
+fn error() {
+    let mv8 = MiniV8::new();
+    let module = mv8.compile_module("main", "throw new RangeError('bad');").unwrap();
+    assert!(module.error().is_none());
+    assert!(module.evaluate::<Value>().is_err());
+    assert_eq!(module.status(), ModuleStatus::Errored);
+    let error = module.error().unwrap();
+    let name: StdString = error.as_object().unwrap().get("name").unwrap();
+    assert_eq!(name, "RangeError");
+
+    // Modules that depend on an errored module are errored as well:
+    let dependent = mv8.compile_module("dependent", "import 'main';").unwrap();
+    assert!(dependent.evaluate::<Value>().is_err());
+    assert_eq!(dependent.status(), ModuleStatus::Errored);
+    assert!(dependent.error().is_some());
+}
+
+#[test]
+fn eval_module() {
+    let mv8 = MiniV8::new();
+    mv8.register_inline_module("math", "export const add = (a, b) => a + b;").unwrap();
+    let namespace = mv8.eval_module(r#"
+        import { add } from "inline:math";
+        import two from "data:text/javascript,export%20default%202";
+        import three from "data:text/javascript;base64,ZXhwb3J0IGRlZmF1bHQgMw==";
+        export const value = add(two, three);
+    "#).unwrap();
+    let value: usize = namespace.get("value").unwrap();
+    assert_eq!(value, 5);
+}
+
+#[test]
+fn eval_module_top_level_await() {
+    let mv8 = MiniV8::new();
+    let namespace = mv8.eval_module(r#"
+        const value = await Promise.resolve(1).then(v => v + 1);
+        export { value };
+    "#).unwrap();
+    let value: usize = namespace.get("value").unwrap();
+    assert_eq!(value, 2);
+
+    match mv8.eval_module("await new Promise(() => {});") {
+        Err(Error::NotSettled) => {},
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match mv8.eval_module("await null; throw new Error('oops');") {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert_eq!(message, "oops");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn eval_module_unresolved() {
+    let mv8 = MiniV8::new();
+    match mv8.eval_module(r#"import "inline:missing";"#) {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert!(message.contains("inline:missing"));
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn module_loader() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mv8 = MiniV8::new();
+    let loads = Rc::new(RefCell::new(Vec::new()));
+    mv8.set_module_loader({
+        let loads = loads.clone();
+        move |_, referrer, specifier| {
+            loads.borrow_mut().push((referrer.to_string(), specifier.to_string()));
+            Ok(match specifier {
+                "/left.js" => "import { base } from '/base.js'; export const left = base + 1;",
+                "/right.js" => "import { base } from '/base.js'; export const right = base + 2;",
+                "/base.js" => "export const base = 10;",
+                _ => return Err(Error::ExternalError("not found".into())),
+            }.to_string())
+        }
+    });
+
+    let namespace = mv8.eval_module_as("/main.js", r#"
+        import { left } from "/left.js";
+        import { right } from "/right.js";
+        export const value = left + right;
+    "#).unwrap();
+    let value: usize = namespace.get("value").unwrap();
+    assert_eq!(value, 23);
+    // A module's requests are all resolved before its dependencies' requests, and the shared
+    // dependency is only loaded once:
+    assert_eq!(*loads.borrow(), [
+        ("/main.js".to_string(), "/left.js".to_string()),
+        ("/main.js".to_string(), "/right.js".to_string()),
+        ("/left.js".to_string(), "/base.js".to_string()),
+    ]);
+
+    match mv8.eval_module("import '/missing.js';") {
+        Err(Error::ExternalError(e)) => assert_eq!(e.to_string(), "not found"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(loads.borrow().last().unwrap(), &(StdString::new(), "/missing.js".to_string()));
+
+    // A module that another module has replaced is still the referrer of its own imports:
+    let replaced = mv8.compile_module("/replaced.js", "import '/missing.js';").unwrap();
+    mv8.compile_module("/replaced.js", "").unwrap();
+    assert!(replaced.instantiate().is_err());
+    assert_eq!(loads.borrow().last().unwrap(), &(
+        "/replaced.js".to_string(),
+        "/missing.js".to_string(),
+    ));
+}
+
+#[test]
+fn module_loader_cycle() {
+    let mv8 = MiniV8::new();
+    mv8.set_module_loader(|_, _, specifier| Ok(match specifier {
+        "/even.js" => r#"
+            import { isOdd } from "/odd.js";
+            export const isEven = n => n === 0 || isOdd(n - 1);
+        "#,
+        "/odd.js" => r#"
+            import { isEven } from "/even.js";
+            import { name } from "/main.js";
+            export const isOdd = n => n !== 0 && isEven(n - 1);
+            export const greeting = () => "hello from " + name;
+        "#,
+        _ => "",
+    }.to_string()));
+
+    let namespace = mv8.eval_module_as("/main.js", r#"
+        import { isEven } from "/even.js";
+        import { greeting } from "/odd.js";
+        export const name = "main";
+        export const value = isEven(10) && !isEven(7);
+        export const message = greeting();
+    "#).unwrap();
+    let value: bool = namespace.get("value").unwrap();
+    assert!(value);
+    let message: StdString = namespace.get("message").unwrap();
+    assert_eq!(message, "hello from main");
+}
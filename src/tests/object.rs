@@ -1,4 +1,5 @@
 use crate::*;
+use std::collections::HashMap;
 use std::string::String as StdString;
 
 #[test]
@@ -18,6 +19,29 @@ fn set_get() {
 }
 
 
+#[test]
+fn create_object_from() {
+    let mv8 = MiniV8::new();
+    let map: HashMap<StdString, u32> = (0..10_000).map(|n| (format!("key{}", n), n)).collect();
+    let object = mv8.create_object_from(map.clone()).unwrap();
+    assert_eq!(object.len().unwrap(), 10_000);
+    assert_eq!(object.get::<_, u32>("key9999").unwrap(), 9999);
+    let value = map.to_value(&mv8).unwrap();
+    assert_eq!(value.as_object().unwrap().get::<_, u32>("key42").unwrap(), 42);
+
+    // Properties are set like `Object::set` does, so inherited setters run, and their exceptions
+    // are reported:
+    mv8.eval::<_, ()>(r#"
+        Object.defineProperty(Object.prototype, 'b', {
+            set(value) { throw new Error(`rejected ${value}`); },
+            configurable: true,
+        })
+    "#).unwrap();
+    let error = mv8.create_object_from(vec![("a", 1), ("b", 2)]).unwrap_err();
+    assert_eq!(error.js_error().unwrap().message, "rejected 2");
+    mv8.eval::<_, ()>("delete Object.prototype.b").unwrap();
+}
+
 #[test]
 fn remove() {
     let mv8 = MiniV8::new();
@@ -49,6 +73,23 @@ fn keys() {
     assert_eq!(keys.unwrap(), vec!["c".to_string(), "b".to_string(), "a".to_string()])
 }
 
+#[test]
+fn keys_indices() {
+    let mv8 = MiniV8::new();
+    let array: Array = mv8.eval("const array = ['x', 'y']; array.z = 1; array").unwrap();
+    let keys = array.into_object().keys(false).unwrap();
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keys.get::<f64>(0).unwrap(), 0.0);
+    assert_eq!(keys.get::<f64>(1).unwrap(), 1.0);
+    assert_eq!(keys.get::<StdString>(2).unwrap(), "z");
+
+    let object: Object = mv8.eval("({ b: 1, 10: 2, [Symbol()]: 3, 2: 4 })").unwrap();
+    let keys: Vec<Value> = object.keys(false).unwrap().elements().collect::<Result<_>>().unwrap();
+    assert!(keys[0].is_number() && keys[1].is_number() && keys[2].is_string());
+    let keys: Vec<StdString> = keys.into_iter().map(|k| k.into(&mv8).unwrap()).collect();
+    assert_eq!(keys, vec!["2", "10", "b"]);
+}
+
 #[test]
 fn properties() {
     let mv8 = MiniV8::new();
@@ -65,3 +106,319 @@ fn properties() {
 
     assert_eq!(list, vec![("4".to_string(), 0), ("123".to_string(), 456), ("a".to_string(), 123)]);
 }
+
+#[test]
+fn numeric_keys() {
+    let mv8 = MiniV8::new();
+    let object = mv8.create_object();
+    // Numeric keys are converted to strings exactly like JavaScript does, even beyond the range of
+    // array indices:
+    object.set(u32::MAX, 1).unwrap();
+    object.set(4294967296u64, 2).unwrap();
+    object.set(1e21, 3).unwrap();
+    object.set(-1, 4).unwrap();
+    object.set(1.5, 5).unwrap();
+    for key in ["4294967295", "4294967296", "1e+21", "-1", "1.5"] {
+        assert!(object.has(key).unwrap());
+    }
+    assert_eq!(object.get::<_, usize>("4294967296").unwrap(), 2);
+    assert_eq!(object.get::<_, usize>(4294967296u64).unwrap(), 2);
+    assert_eq!(object.get::<_, usize>("1e+21").unwrap(), 3);
+    // Keys that don't survive a round trip through `f64` are rounded like JavaScript numbers:
+    object.set(u64::MAX, 6).unwrap();
+    assert_eq!(object.get::<_, usize>("18446744073709552000").unwrap(), 6);
+}
+
+#[test]
+fn index_keys() {
+    let mv8 = MiniV8::new();
+    let object = mv8.create_object();
+    for i in 0..100 {
+        object.set_index(i * 7, i).unwrap();
+    }
+    assert!(object.has_index(0).unwrap());
+    assert!(object.has_index(693).unwrap());
+    assert!(!object.has_index(1).unwrap());
+    assert_eq!(object.get_index::<u32>(693).unwrap(), 99);
+    assert_eq!(object.get::<_, u32>("693").unwrap(), 99);
+    assert!(object.get_index::<Value>(1).unwrap().is_undefined());
+
+    // Index keys are the same properties as their string keys, including beyond array indices:
+    object.set("5", "five").unwrap();
+    assert_eq!(object.get_index::<StdString>(5).unwrap(), "five");
+    object.set_index(u32::MAX, true).unwrap();
+    assert!(object.get::<_, bool>("4294967295").unwrap());
+
+    // Getters and proxies are honored:
+    let proxy: Object = mv8.eval("new Proxy({}, { get: (_, key) => `got ${key}` })").unwrap();
+    assert_eq!(proxy.get_index::<StdString>(3).unwrap(), "got 3");
+    let throwing: Object = mv8.eval("({ get 0() { throw 'nope'; } })").unwrap();
+    assert!(throwing.get_index::<Value>(0).is_err());
+}
+
+#[test]
+fn data_properties() {
+    let mv8 = MiniV8::new();
+    let object: Object = mv8.eval(r#"
+        globalThis.getterCalls = 0;
+        const proto = { inherited: 'proto' };
+        const object = Object.create(proto);
+        object.data = 1;
+        object[7] = 'seven';
+        Object.defineProperty(object, 'accessor', { get() { getterCalls++; return 2; } });
+        object.proxy = new Proxy({}, {
+            get() { getterCalls++; return 3; },
+            ownKeys() { getterCalls++; return []; },
+        });
+        object
+    "#).unwrap();
+    let proxy: Object = mv8.eval(r#"
+        new Proxy({ a: 1 }, {
+            getOwnPropertyDescriptor() { getterCalls++; },
+            ownKeys() { getterCalls++; throw new Error('ownKeys'); },
+        })
+    "#).unwrap();
+
+    assert_eq!(object.property_kind("data").unwrap(), PropertyKind::Data);
+    assert_eq!(object.property_kind("inherited").unwrap(), PropertyKind::Data);
+    assert_eq!(object.property_kind("accessor").unwrap(), PropertyKind::Accessor);
+    assert_eq!(object.property_kind("missing").unwrap(), PropertyKind::Missing);
+    assert_eq!(proxy.property_kind("a").unwrap(), PropertyKind::Accessor);
+
+    assert_eq!(object.get_data_property("data").unwrap().unwrap().as_number(), Some(1.0));
+    let inherited: StdString = object.get_data_property("inherited").unwrap().unwrap()
+        .into(&mv8).unwrap();
+    assert_eq!(inherited, "proto");
+    assert!(object.get_data_property("accessor").unwrap().is_none());
+    assert!(object.get_data_property("missing").unwrap().is_none());
+    assert!(proxy.get_data_property("a").unwrap().is_none());
+    // Keys that aren't strings are converted to property names like they are by `Object::get`:
+    let seven: StdString = object.get_data_property(7).unwrap().unwrap().into(&mv8).unwrap();
+    assert_eq!(seven, "seven");
+
+    // Neither the getter nor the proxy traps ever ran, not even while formatting:
+    let formatted = format!("{:?}", object);
+    assert!(formatted.contains("\"accessor\": <accessor>"));
+    assert!(formatted.contains("\"proxy\": <proxy>"));
+    assert_eq!(format!("{:?}", proxy), "<proxy>");
+    let calls: usize = mv8.global().get("getterCalls").unwrap();
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn get_normalized() {
+    let mv8 = MiniV8::new();
+    let config: Object = mv8.eval("({ maxretries: 3, retry_delay: 100, Verbose: true })").unwrap();
+
+    let (key, value): (StdString, usize) = config
+        .get_normalized("maxRetries", KeyNormalization::CaseInsensitive).unwrap().unwrap();
+    assert_eq!((key.as_str(), value), ("maxretries", 3));
+    let found: Option<(StdString, usize)> = config
+        .get_normalized("retryDelay", KeyNormalization::CaseInsensitive).unwrap();
+    assert!(found.is_none());
+    let (key, value): (StdString, usize) = config
+        .get_normalized("retryDelay", KeyNormalization::CamelSnakeAgnostic).unwrap().unwrap();
+    assert_eq!((key.as_str(), value), ("retry_delay", 100));
+    let (key, value): (StdString, bool) = config
+        .get_normalized("verbose", KeyNormalization::CamelSnakeAgnostic).unwrap().unwrap();
+    assert_eq!((key.as_str(), value), ("Verbose", true));
+
+    let config: Object = mv8.eval("({ maxRetries: 1, max_retries: 2, other: 3 })").unwrap();
+    match config.get_normalized::<usize>("max-retries", KeyNormalization::CamelSnakeAgnostic) {
+        Err(Error::AmbiguousKey { key, matches }) => {
+            assert_eq!(key, "max-retries");
+            assert_eq!(matches, vec!["maxRetries", "max_retries"]);
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn len() {
+    let mv8 = MiniV8::new();
+    let inherited: Object = mv8.eval("Object.create({ a: 1, b: 2 })").unwrap();
+    assert_eq!(inherited.len().unwrap(), 0);
+    assert!(inherited.is_empty().unwrap());
+
+    let object: Object = mv8.eval(r#"
+        const object = Object.create({ inherited: 1 });
+        object.a = 1;
+        object[0] = 2;
+        object[Symbol.for('symbol')] = 3;
+        Object.defineProperty(object, 'hidden', { value: 4, enumerable: false });
+        object
+    "#).unwrap();
+    assert_eq!(object.len().unwrap(), 2);
+    assert!(!object.is_empty().unwrap());
+    let symbols = KeyCollection { include_symbols: true, ..Default::default() };
+    assert_eq!(object.len_with(symbols).unwrap(), 3);
+    let non_enumerable = KeyCollection { include_non_enumerable: true, ..Default::default() };
+    assert_eq!(object.len_with(non_enumerable).unwrap(), 3);
+    let all = KeyCollection { include_symbols: true, include_non_enumerable: true };
+    assert_eq!(object.len_with(all).unwrap(), 4);
+
+    let symbol_only: Object = mv8.eval("({ [Symbol.for('symbol')]: 1 })").unwrap();
+    assert!(symbol_only.is_empty().unwrap());
+    assert!(!symbol_only.is_empty_with(symbols).unwrap());
+}
+
+#[test]
+fn define_property_data() {
+    let mv8 = MiniV8::new();
+    let global = mv8.global();
+    let defined = global.define_property("VERSION", PropertyDescriptor::Data {
+        value: Value::Number(3.0),
+        writable: false,
+        enumerable: false,
+        configurable: false,
+    }).unwrap();
+    assert!(defined);
+    let value: u32 = mv8.eval("VERSION = 4; VERSION").unwrap();
+    assert_eq!(value, 3);
+    assert!(mv8.eval::<_, Value>("'use strict'; VERSION = 4").is_err());
+    let enumerable: bool = mv8.eval("Object.keys(globalThis).includes('VERSION')").unwrap();
+    assert!(!enumerable);
+
+    // Non-configurable properties can't be redefined:
+    let defined = global.define_property("VERSION", PropertyDescriptor::Data {
+        value: Value::Number(4.0),
+        writable: true,
+        enumerable: true,
+        configurable: true,
+    }).unwrap();
+    assert!(!defined);
+    let value: u32 = global.get("VERSION").unwrap();
+    assert_eq!(value, 3);
+}
+
+#[test]
+fn define_property_accessor() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let mv8 = MiniV8::new();
+    let object = mv8.create_object();
+    let reads = Rc::new(Cell::new(0));
+    let celsius = Rc::new(Cell::new(20.0));
+    let get = mv8.create_function({
+        let (reads, celsius) = (reads.clone(), celsius.clone());
+        move |_| {
+            reads.set(reads.get() + 1);
+            Ok(celsius.get() * 9.0 / 5.0 + 32.0)
+        }
+    });
+    let set = mv8.create_function({
+        let celsius = celsius.clone();
+        move |inv| {
+            let (fahrenheit,): (f64,) = inv.args.into(&inv.mv8)?;
+            if fahrenheit < -459.67 {
+                return Err(Error::ExternalError("below absolute zero".into()));
+            }
+            celsius.set((fahrenheit - 32.0) * 5.0 / 9.0);
+            Ok(())
+        }
+    });
+    object.define_property("fahrenheit", PropertyDescriptor::Accessor {
+        get: Some(get),
+        set: Some(set),
+        enumerable: true,
+        configurable: true,
+    }).unwrap();
+    mv8.global().set("thermometer", object.clone()).unwrap();
+
+    let value: f64 = mv8.eval("thermometer.fahrenheit").unwrap();
+    assert_eq!(value, 68.0);
+    let value: f64 = mv8.eval("thermometer.fahrenheit = 212; thermometer.fahrenheit").unwrap();
+    assert_eq!(value, 212.0);
+    assert_eq!(celsius.get(), 100.0);
+    assert_eq!(reads.get(), 2);
+    assert!(mv8.eval::<_, Value>("thermometer.fahrenheit = -1000").is_err());
+    assert_eq!(object.property_kind("fahrenheit").unwrap(), PropertyKind::Accessor);
+
+    let failing = mv8.create_function(|_| -> Result<()> {
+        Err(Error::ExternalError("not available".into()))
+    });
+    object.define_property("broken", PropertyDescriptor::Accessor {
+        get: Some(failing),
+        set: None,
+        enumerable: false,
+        configurable: false,
+    }).unwrap();
+    let caught: bool = mv8.eval("try { thermometer.broken; false } catch (e) { true }").unwrap();
+    assert!(caught);
+}
+
+#[test]
+fn instance_of() {
+    let mv8 = MiniV8::new();
+    let object: Object = mv8.eval("class Base {}; class Derived extends Base {}; new Derived()")
+        .unwrap();
+    let base: Function = mv8.eval("Base").unwrap();
+    let array: Function = mv8.eval("Array").unwrap();
+    assert!(object.instance_of(&base).unwrap());
+    assert!(!object.instance_of(&array).unwrap());
+
+    let custom: Function = mv8.eval(r#"
+        const Even = function() {};
+        Object.defineProperty(Even, Symbol.hasInstance, { value: o => o.n % 2 === 0 });
+        Even
+    "#).unwrap();
+    let even: Object = mv8.eval("({ n: 2 })").unwrap();
+    assert!(even.instance_of(&custom).unwrap());
+
+    let broken: Function = mv8.eval(r#"
+        const Broken = function() {};
+        Broken.prototype = 1;
+        Broken
+    "#).unwrap();
+    assert!(object.instance_of(&broken).is_err());
+}
+
+#[test]
+fn set_with_attributes() {
+    let mv8 = MiniV8::new();
+    let global = mv8.global();
+    let attributes = PropertyAttributes::READ_ONLY | PropertyAttributes::DONT_DELETE;
+    assert!(global.set_with_attributes("LIMIT", 10, attributes).unwrap());
+    assert!(global.set_with_attributes("hidden", true, PropertyAttributes::DONT_ENUM).unwrap());
+
+    let value: u32 = mv8.eval("LIMIT = 20; delete LIMIT; LIMIT").unwrap();
+    assert_eq!(value, 10);
+    match mv8.eval::<_, Value>("'use strict'; LIMIT = 20") {
+        Err(Error::Value(value)) => {
+            let message: StdString = value.as_object().unwrap().get("message").unwrap();
+            assert!(message.contains("read only"), "unexpected message: {}", message);
+        },
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let keys: bool = mv8.eval("Object.keys(globalThis).includes('hidden')").unwrap();
+    assert!(!keys);
+    let hidden: bool = mv8.eval("hidden = false; hidden").unwrap();
+    assert!(!hidden);
+
+    // Non-configurable properties can't be redefined:
+    assert!(!global.set_with_attributes("LIMIT", 20, PropertyAttributes::NONE).unwrap());
+    assert!(attributes.contains(PropertyAttributes::READ_ONLY));
+    assert!(!attributes.contains(PropertyAttributes::DONT_ENUM));
+}
+
+#[test]
+fn freeze_seal() {
+    let mv8 = MiniV8::new();
+    let frozen: Object = mv8.eval("globalThis.frozen = { a: 1 }").unwrap();
+    let sealed: Object = mv8.eval("globalThis.sealed = { a: 1 }").unwrap();
+    frozen.freeze().unwrap();
+    sealed.seal().unwrap();
+    let result: Vec<Value> = mv8.eval(r#"
+        frozen.a = 2; frozen.b = 2; delete frozen.a;
+        sealed.a = 2; sealed.b = 2; delete sealed.a;
+        [frozen.a, frozen.b, sealed.a, sealed.b, Object.isFrozen(frozen), Object.isSealed(sealed)]
+    "#).unwrap();
+    let result: Vec<StdString> = result.iter().map(|v| format!("{:?}", v)).collect();
+    assert_eq!(result, ["1", "undefined", "2", "undefined", "true", "true"]);
+    assert!(mv8.eval::<_, Value>("'use strict'; frozen.a = 3").is_err());
+    assert!(!frozen.set_with_attributes("b", 1, PropertyAttributes::NONE).unwrap());
+
+    let proxy: Object = mv8.eval("new Proxy({}, { preventExtensions: () => false })").unwrap();
+    assert!(proxy.freeze().is_err());
+}
@@ -0,0 +1,129 @@
+use crate::*;
+use std::string::String as StdString;
+
+fn add(path: &str, value: Value) -> PatchOp {
+    PatchOp::Add { path: path.to_string(), value }
+}
+
+fn remove(path: &str) -> PatchOp {
+    PatchOp::Remove { path: path.to_string() }
+}
+
+fn replace(path: &str, value: Value) -> PatchOp {
+    PatchOp::Replace { path: path.to_string(), value }
+}
+
+fn move_(from: &str, path: &str) -> PatchOp {
+    PatchOp::Move { from: from.to_string(), path: path.to_string() }
+}
+
+fn copy(from: &str, path: &str) -> PatchOp {
+    PatchOp::Copy { from: from.to_string(), path: path.to_string() }
+}
+
+fn test(path: &str, value: Value) -> PatchOp {
+    PatchOp::Test { path: path.to_string(), value }
+}
+
+// Applies the patch to the object that `source` evaluates to, returning the patched object's
+// JSON (or the error).
+fn patch(mv8: &MiniV8, source: &str, ops: &[PatchOp]) -> Result<StdString> {
+    let object: Object = mv8.eval(format!("globalThis.doc = {}", source)).unwrap();
+    object.apply_patch(ops)?;
+    Ok(mv8.eval("JSON.stringify(doc)").unwrap())
+}
+
+fn js(mv8: &MiniV8, source: &str) -> Value {
+    mv8.eval(format!("({})", source)).unwrap()
+}
+
+#[test]
+fn apply_patch() {
+    let mv8 = MiniV8::new();
+    let cases: Vec<(&str, Vec<PatchOp>, &str)> = vec![
+        (r#"{"foo":"bar"}"#, vec![add("/baz", js(&mv8, "'qux'"))], r#"{"foo":"bar","baz":"qux"}"#),
+        (r#"{"foo":["bar","baz"]}"#, vec![add("/foo/1", js(&mv8, "'qux'"))],
+            r#"{"foo":["bar","qux","baz"]}"#),
+        (r#"{"foo":[1]}"#, vec![add("/foo/-", Value::Number(2.0))], r#"{"foo":[1,2]}"#),
+        (r#"{"foo":"bar","baz":"qux"}"#, vec![remove("/baz")], r#"{"foo":"bar"}"#),
+        (r#"{"foo":["bar","qux","baz"]}"#, vec![remove("/foo/1")], r#"{"foo":["bar","baz"]}"#),
+        (r#"{"foo":"bar"}"#, vec![replace("/foo", Value::Number(1.0))], r#"{"foo":1}"#),
+        (r#"{"foo":{"bar":"baz"},"qux":{}}"#, vec![move_("/foo/bar", "/qux/thud")],
+            r#"{"foo":{},"qux":{"thud":"baz"}}"#),
+        (r#"{"foo":["all","grass","cows","eat"]}"#, vec![move_("/foo/1", "/foo/3")],
+            r#"{"foo":["all","cows","eat","grass"]}"#),
+        (r#"{"a":{"b":[1]}}"#, vec![copy("/a", "/c"), add("/c/b/-", Value::Number(2.0))],
+            r#"{"a":{"b":[1]},"c":{"b":[1,2]}}"#),
+        (r#"{"a/b":1,"m~n":2}"#, vec![replace("/a~1b", Value::Number(3.0)), remove("/m~0n")],
+            r#"{"a/b":3}"#),
+        (r#"{"":1}"#, vec![replace("/", Value::Number(2.0))], r#"{"":2}"#),
+        (r#"{"a":[1,{"b":null}]}"#, vec![test("/a", js(&mv8, "[1, { b: null }]"))],
+            r#"{"a":[1,{"b":null}]}"#),
+        (r#"{"a":1}"#, vec![test("", js(&mv8, "{ a: 1 }"))], r#"{"a":1}"#),
+        (r#"{"a":{"1":2,"x":3}}"#, vec![test("/a", js(&mv8, "{ x: 3, 1: 2 }"))],
+            r#"{"a":{"1":2,"x":3}}"#),
+    ];
+    for (source, ops, expected) in cases {
+        assert_eq!(patch(&mv8, source, &ops).unwrap(), expected, "{}", source);
+    }
+}
+
+#[test]
+fn apply_patch_failure() {
+    let mv8 = MiniV8::new();
+    let cases: Vec<(Vec<PatchOp>, usize, &str, &str)> = vec![
+        (vec![remove("/missing")], 0, "/missing", "path does not exist"),
+        (vec![add("/a/x/y", Value::Null)], 0, "/a/x/y", "path does not exist"),
+        (vec![add("/list/01", Value::Null)], 0, "/list/01", "invalid array index"),
+        (vec![add("/list/3", Value::Null)], 0, "/list/3", "array index out of bounds"),
+        (vec![remove("/list/-")], 0, "/list/-", "path does not exist"),
+        (vec![replace("/a/missing", Value::Null)], 0, "/a/missing", "path does not exist"),
+        (vec![add("a", Value::Null)], 0, "a", "invalid pointer"),
+        (vec![remove("/a~2")], 0, "/a~2", "invalid pointer"),
+        (vec![replace("", Value::Null)], 0, "", "cannot target the patched object"),
+        (vec![move_("/a", "/a/b")], 0, "/a/b", "cannot move a value into itself"),
+        (vec![test("/a/b", Value::Number(2.0))], 0, "/a/b", "test failed"),
+        (vec![test("/list", js(&mv8, "[1, 2, 3]"))], 0, "/list", "test failed"),
+        (vec![test("/a", js(&mv8, "{ 1: 1 }"))], 0, "/a", "test failed"),
+        // Earlier operations are rolled back:
+        (vec![
+            add("/list/0", Value::Number(0.0)),
+            remove("/list/2"),
+            replace("/a/b", Value::Number(5.0)),
+            add("/c", Value::Null),
+            move_("/list/0", "/a/d"),
+            remove("/a/missing"),
+        ], 5, "/a/missing", "path does not exist"),
+    ];
+    for (ops, expected_index, expected_path, expected_reason) in cases {
+        let source = r#"{"a":{"b":1},"list":[1,2]}"#;
+        match patch(&mv8, source, &ops) {
+            Err(Error::PatchFailed { index, path, reason }) => {
+                assert_eq!(index, expected_index);
+                assert_eq!(path, expected_path);
+                assert_eq!(reason, expected_reason);
+            },
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let json: StdString = mv8.eval("JSON.stringify(doc)").unwrap();
+        assert_eq!(json, source);
+    }
+}
+
+#[test]
+fn apply_patch_exception() {
+    let mv8 = MiniV8::new();
+    let object: Object = mv8.eval(r#"
+        ({ a: 1, get b() { throw new Error('getter'); } })
+    "#).unwrap();
+    let ops = [replace("/a", Value::Number(2.0)), replace("/b", Value::Null)];
+    match object.apply_patch(&ops) {
+        Err(Error::Value(Value::Object(error))) => {
+            let message: StdString = error.get("message").unwrap();
+            assert_eq!(message, "getter");
+        },
+        result => panic!("unexpected result: {:?}", result),
+    }
+    let a: u32 = object.get("a").unwrap();
+    assert_eq!(a, 1);
+}
@@ -0,0 +1,97 @@
+use crate::*;
+use crate::PathSeg::{Index, Key};
+use std::string::String as StdString;
+
+fn doc(mv8: &MiniV8) -> Value {
+    mv8.eval(r#"({
+        data: { items: [{ name: "first" }, { name: "second" }], nothing: null },
+        "a.b": { "[0]": 1, "back\\slash": 2 },
+        get broken() { throw new Error("broken getter"); },
+    })"#).unwrap()
+}
+
+#[test]
+fn pluck() {
+    let mv8 = MiniV8::new();
+    let doc = doc(&mv8);
+    let name: StdString = doc.pluck_as(&mv8, &[Key("data"), Key("items"), Index(1), Key("name")])
+        .unwrap();
+    assert_eq!(name, "second");
+    assert!(doc.pluck(&mv8, &[]).unwrap().is_object());
+
+    // Missing and nullish links end the chain without an error:
+    assert!(doc.pluck(&mv8, &[Key("missing"), Key("deeper"), Index(0)]).unwrap().is_undefined());
+    assert!(doc.pluck(&mv8, &[Key("data"), Key("nothing")]).unwrap().is_null());
+    assert!(doc.pluck(&mv8, &[Key("data"), Key("nothing"), Key("x")]).unwrap().is_undefined());
+    assert!(doc.pluck(&mv8, &[Key("data"), Key("items"), Index(5), Key("name")]).unwrap()
+        .is_undefined());
+    assert!(Value::Null.pluck(&mv8, &[Key("x")]).unwrap().is_undefined());
+
+    // Properties of primitives are read as in JavaScript:
+    let len: u32 = doc.pluck_as(&mv8, &[Key("data"), Key("items"), Index(0), Key("name"),
+        Key("length")]).unwrap();
+    assert_eq!(len, 5);
+    assert!(Value::Number(1.0).pluck(&mv8, &[Key("x")]).unwrap().is_undefined());
+}
+
+#[test]
+fn pluck_errors() {
+    let mv8 = MiniV8::new();
+    let doc = doc(&mv8);
+    match doc.pluck(&mv8, &[Key("broken"), Key("x")]) {
+        Err(Error::Value(Value::Object(error))) => {
+            let message: StdString = error.get("message").unwrap();
+            assert_eq!(message, "broken getter");
+        },
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match doc.pluck_as::<Object>(&mv8, &[Key("data"), Key("missing")]) {
+        Err(Error::FromJsConversionError { from: "undefined", to: "Object" }) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn pluck_path() {
+    let mv8 = MiniV8::new();
+    let doc = doc(&mv8);
+    let name: StdString = doc.pluck_path(&mv8, "data.items[0].name").unwrap().into(&mv8).unwrap();
+    assert_eq!(name, "first");
+    let one: u32 = doc.pluck_path(&mv8, r"a\.b.\[0\]").unwrap().into(&mv8).unwrap();
+    assert_eq!(one, 1);
+    let two: u32 = doc.pluck_path(&mv8, r"a\.b.back\\slash").unwrap().into(&mv8).unwrap();
+    assert_eq!(two, 2);
+    assert!(doc.pluck_path(&mv8, "data.nothing.items[0]").unwrap().is_undefined());
+    assert!(doc.pluck_path(&mv8, "").unwrap().is_object());
+
+    let items: Value = mv8.eval("[[1, [2, 3]]]").unwrap();
+    let three: u32 = items.pluck_path(&mv8, "[0][1][1]").unwrap().into(&mv8).unwrap();
+    assert_eq!(three, 3);
+}
+
+#[test]
+fn pluck_path_invalid() {
+    let mv8 = MiniV8::new();
+    let doc = doc(&mv8);
+    let cases = [
+        (".data", 0),
+        ("data.", 5),
+        ("data..items", 5),
+        ("data[x]", 5),
+        ("data[01]", 5),
+        ("data[]", 5),
+        ("data[0", 4),
+        ("data]", 4),
+        ("data[0]items", 7),
+        ("data\\", 4),
+    ];
+    for (path, expected) in cases {
+        match doc.pluck_path(&mv8, path) {
+            Err(Error::InvalidPath { path: p, offset }) => {
+                assert_eq!(p, path);
+                assert_eq!(offset, expected, "{:?}", path);
+            },
+            result => panic!("unexpected result for {:?}: {:?}", path, result),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::*;
+
+#[test]
+fn promise_state() {
+    let mv8 = MiniV8::new();
+    let promise: Promise = mv8.eval("globalThis.p = new Promise(r => globalThis.resolve = r)")
+        .unwrap();
+    assert_eq!(promise.state(), PromiseState::Pending);
+    assert!(promise.result().is_none());
+    mv8.eval::<_, ()>("resolve(42)").unwrap();
+    assert_eq!(promise.state(), PromiseState::Fulfilled);
+    let result: u32 = promise.result().unwrap().into(&mv8).unwrap();
+    assert_eq!(result, 42);
+
+    let promise: Promise = mv8.eval("Promise.reject(new Error('nope'))").unwrap();
+    assert_eq!(promise.state(), PromiseState::Rejected);
+    let reason: Object = promise.result().unwrap().into(&mv8).unwrap();
+    let message: String = reason.get("message").unwrap();
+    assert_eq!(message.to_string(), "nope");
+
+    assert!(mv8.eval::<_, Promise>("({ then() {} })").is_err());
+    assert!(mv8.eval::<_, Promise>("42").is_err());
+}
+
+#[test]
+fn run_microtasks() {
+    let mv8 = MiniV8::new();
+    let resolve: Function = mv8.eval(r#"
+        globalThis.log = [];
+        let resolve;
+        new Promise(r => resolve = r).then(v => log.push(v)).then(() => log.push("done"));
+        resolve
+    "#).unwrap();
+    resolve.call::<_, ()>((1,)).unwrap();
+    mv8.run_microtasks();
+    let log: String = mv8.eval("log.join()").unwrap();
+    assert_eq!(log.to_string(), "1,done");
+}
+
+#[test]
+fn eval_async() {
+    let mv8 = MiniV8::new();
+    let value: u32 = mv8.eval_async("(async () => 42)()").unwrap();
+    assert_eq!(value, 42);
+    let value: u32 = mv8.eval_async(r#"
+        (async () => {
+            const a = await Promise.resolve(1);
+            const b = await new Promise(r => Promise.resolve().then(() => r(2)));
+            return a + b;
+        })()
+    "#).unwrap();
+    assert_eq!(value, 3);
+    // Non-promise results are returned as they are:
+    let value: u32 = mv8.eval_async("7").unwrap();
+    assert_eq!(value, 7);
+
+    match mv8.eval_async::<_, Value>("(async () => { throw 'oops'; })()") {
+        Err(Error::Value(Value::String(reason))) => assert_eq!(reason.to_string(), "oops"),
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match mv8.eval_async::<_, Value>("new Promise(() => {})") {
+        Err(Error::NotSettled) => {},
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn unhandled_rejection() {
+    let mv8 = MiniV8::new();
+    mv8.eval::<_, ()>("Promise.reject(new Error('unhandled')); undefined").unwrap();
+    mv8.run_microtasks();
+    let value: u32 = mv8.eval("1 + 1").unwrap();
+    assert_eq!(value, 2);
+}
@@ -0,0 +1,54 @@
+use crate::*;
+use std::time::Duration;
+
+#[test]
+fn test_and_replace() {
+    let mv8 = MiniV8::new();
+    let cache = mv8.regex_cache();
+    assert!(cache.test("^a+b$", "", "aaab").unwrap());
+    assert!(!cache.test("^a+b$", "", "aaac").unwrap());
+    assert!(cache.test("^A+B$", "i", "aaab").unwrap());
+    assert_eq!(cache.replace("(\\w+)@", "", "me@x me@y", "<$1>").unwrap(), "<me>x me@y");
+    assert_eq!(cache.replace("(\\w+)@", "g", "me@x me@y", "<$1>").unwrap(), "<me>x <me>y");
+    // Global regular expressions don't carry their `lastIndex` over between uses:
+    assert!(cache.test("b", "g", "ab").unwrap());
+    assert!(cache.test("b", "g", "ab").unwrap());
+    assert!(cache.test("(", "", "").is_err());
+}
+
+#[test]
+fn eviction() {
+    let mv8 = MiniV8::new();
+    let cache = mv8.regex_cache();
+    cache.set_capacity(2);
+    cache.test("a", "", "a").unwrap();
+    cache.test("b", "", "b").unwrap();
+    cache.test("a", "", "a").unwrap();
+    cache.test("c", "", "c").unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains("a", ""));
+    assert!(!cache.contains("b", ""));
+    assert!(!cache.contains("a", "i"));
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn catastrophic_backtracking() {
+    let mv8 = MiniV8::new();
+    let matches = mv8.create_function(|inv| {
+        let (pattern, input): (String, String) = inv.args.into(&inv.mv8)?;
+        inv.mv8.regex_cache().test(&pattern.to_string(), "", &input.to_string())
+    });
+    mv8.global().set("matches", matches).unwrap();
+    let result = mv8.eval::<_, Value>(Script {
+        source: "matches('^(a+)+$', 'a'.repeat(40) + '!')".to_owned(),
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+    match result {
+        Err(Error::Timeout) => {},
+        _ => panic!("unexpected result: {:?}", result),
+    }
+    assert!(mv8.regex_cache().test("^(a+)+$", "", "aaa").unwrap());
+}
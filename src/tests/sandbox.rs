@@ -0,0 +1,36 @@
+use crate::*;
+use std::string::String as StdString;
+
+#[test]
+fn disallow_code_generation() {
+    let mv8 = MiniV8::new_sandboxed(SandboxOptions::new().disallow_code_generation());
+    assert_eq!(mv8.eval::<_, f64>("1 + 2").unwrap(), 3.0);
+    for source in [
+        "eval('1')",
+        "Function('return 1')()",
+        "(() => {}).constructor('return 1')()",
+        "(async () => {}).constructor('return 1')",
+    ] {
+        let error = mv8.eval::<_, Value>(source).unwrap_err();
+        assert_eq!(error.js_error().unwrap().name, "EvalError", "{}", source);
+    }
+
+    // The default configuration is unaffected:
+    let mv8 = MiniV8::new();
+    assert_eq!(mv8.eval::<_, f64>("eval('1')").unwrap(), 1.0);
+    assert_eq!(mv8.eval::<_, f64>("Function('return 1')()").unwrap(), 1.0);
+}
+
+#[test]
+fn replace_nondeterminism() {
+    let options = SandboxOptions::new().date_now(|| 1234.0).math_random(|| 0.5);
+    let mv8 = MiniV8::builder().sandbox(options.clone()).build();
+    assert_eq!(mv8.eval::<_, f64>("Date.now()").unwrap(), 1234.0);
+    assert_eq!(mv8.eval::<_, f64>("Math.random() + Math.random()").unwrap(), 1.0);
+    assert!(mv8.eval::<_, f64>("eval('1')").is_ok());
+
+    // Replacements are skipped for objects that were left out of the global object:
+    let mv8 = MiniV8::builder().global_allowlist(&["Math"]).sandbox(options).build();
+    assert_eq!(mv8.eval::<_, f64>("Math.random()").unwrap(), 0.5);
+    assert_eq!(mv8.eval::<_, StdString>("typeof Date").unwrap(), "undefined");
+}
@@ -0,0 +1,71 @@
+use crate::*;
+
+#[test]
+fn eval_cached() {
+    let mv8 = MiniV8::new();
+    let _: () = mv8.eval("var counter = 0").unwrap();
+    for i in 1..=3 {
+        let value: u32 = mv8.eval_cached("++counter").unwrap();
+        assert_eq!(value, i);
+    }
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 2, misses: 1, size: 1 });
+
+    let value: u32 = mv8.eval_cached("counter * 10").unwrap();
+    assert_eq!(value, 30);
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 2, misses: 2, size: 2 });
+
+    mv8.clear_script_cache();
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 2, misses: 2, size: 0 });
+    let value: u32 = mv8.eval_cached("++counter").unwrap();
+    assert_eq!(value, 4);
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 2, misses: 3, size: 1 });
+}
+
+#[test]
+fn eval_cached_eviction() {
+    let mv8 = MiniV8::new();
+    mv8.set_script_cache_capacity(2);
+    let _: u32 = mv8.eval_cached("1").unwrap();
+    let _: u32 = mv8.eval_cached("2").unwrap();
+    let _: u32 = mv8.eval_cached("1").unwrap();
+    // The least recently used script ("2") is evicted:
+    let _: u32 = mv8.eval_cached("3").unwrap();
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 1, misses: 3, size: 2 });
+    let _: u32 = mv8.eval_cached("1").unwrap();
+    let _: u32 = mv8.eval_cached("2").unwrap();
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 2, misses: 4, size: 2 });
+
+    mv8.set_script_cache_capacity(0);
+    assert_eq!(mv8.script_cache_stats().size, 0);
+}
+
+#[test]
+fn eval_cached_syntax_error() {
+    let mv8 = MiniV8::new();
+    for _ in 0..2 {
+        match mv8.eval_cached::<Value>("1 +") {
+            Err(Error::Value(Value::Object(error))) => {
+                let name: String = error.get("name").unwrap();
+                assert_eq!(name.to_string(), "SyntaxError");
+            },
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 0, misses: 2, size: 0 });
+
+    // Runtime errors don't prevent caching:
+    assert!(mv8.eval_cached::<Value>("null.x").is_err());
+    assert!(mv8.eval_cached::<Value>("null.x").is_err());
+    assert_eq!(mv8.script_cache_stats(), ScriptCacheStats { hits: 1, misses: 3, size: 1 });
+}
+
+#[test]
+fn eval_cached_releases_isolate() {
+    let mv8 = MiniV8::new();
+    let _: u32 = mv8.eval_cached("1").unwrap();
+    assert!(mv8.regex_cache().test("a+", "", "caat").unwrap());
+    let handle = mv8.interrupt_handle();
+    // The cached handles don't keep the isolate alive:
+    drop(mv8);
+    assert!(!handle.terminate());
+}
@@ -0,0 +1,69 @@
+use crate::*;
+
+const LIBRARY: &str = "
+    var counter = 40;
+    globalThis.lib = { add(a, b) { return a + b; } };
+";
+
+#[test]
+fn round_trip() {
+    let snapshot = MiniV8::create_snapshot(LIBRARY).unwrap();
+
+    let mv8 = MiniV8::from_snapshot(&snapshot).unwrap();
+    let sum: f64 = mv8.eval("lib.add(counter, 2)").unwrap();
+    assert_eq!(sum, 42.0);
+    mv8.eval::<_, ()>("counter = 0").unwrap();
+
+    // Every `MiniV8` starts out from the snapshot's state:
+    let other = MiniV8::builder().max_heap_size(64 << 20).build_from_snapshot(&snapshot).unwrap();
+    assert_eq!(other.eval::<_, f64>("counter").unwrap(), 40.0);
+    drop(other);
+    assert_eq!(mv8.eval::<_, f64>("counter").unwrap(), 0.0);
+}
+
+#[test]
+fn init_source_error() {
+    match MiniV8::create_snapshot("throw new Error('boom')") {
+        Err(Error::SnapshotFailed(reason)) => assert!(reason.contains("boom"), "{}", reason),
+        _ => panic!("expected an error"),
+    }
+    assert!(MiniV8::create_snapshot("this is not javascript").is_err());
+}
+
+#[test]
+fn init_source_tampering() {
+    for source in ["delete globalThis.RegExp", "RegExp.prototype.test = () => true"] {
+        match MiniV8::create_snapshot(source) {
+            Err(Error::SnapshotFailed(reason)) => {
+                assert!(reason.contains("built-in"), "{}", reason);
+            },
+            _ => panic!("expected an error for {:?}", source),
+        }
+    }
+    // Tampering with other built-ins is fine:
+    let source = "Array.prototype.first = function () { return this[0]; }";
+    let snapshot = MiniV8::create_snapshot(source).unwrap();
+    let mv8 = MiniV8::from_snapshot(&snapshot).unwrap();
+    assert_eq!(mv8.eval::<_, f64>("[7, 8].first()").unwrap(), 7.0);
+}
+
+#[test]
+fn invalid_snapshot() {
+    let snapshot = MiniV8::create_snapshot(LIBRARY).unwrap();
+    assert!(matches!(MiniV8::from_snapshot(b""), Err(Error::InvalidSnapshot)));
+    assert!(matches!(MiniV8::from_snapshot(b"garbage"), Err(Error::InvalidSnapshot)));
+
+    // A snapshot from another V8 version:
+    let version = v8::V8::get_version().as_bytes();
+    let start = snapshot.windows(version.len()).position(|w| w == version).unwrap();
+    let mut foreign = snapshot.clone();
+    foreign[start] = b'0' + (foreign[start] - b'0' + 1) % 10;
+    assert!(matches!(MiniV8::from_snapshot(&foreign), Err(Error::InvalidSnapshot)));
+
+    // A corrupt or truncated snapshot:
+    let mut corrupt = snapshot.clone();
+    *corrupt.last_mut().unwrap() ^= 0xff;
+    assert!(matches!(MiniV8::from_snapshot(&corrupt), Err(Error::InvalidSnapshot)));
+    let truncated = &snapshot[..snapshot.len() - 1];
+    assert!(matches!(MiniV8::from_snapshot(truncated), Err(Error::InvalidSnapshot)));
+}
@@ -1,7 +1,155 @@
-use crate::mini_v8::MiniV8;
+use crate::*;
+use std::string::String as StdString;
 
 #[test]
 fn to_string() {
     let mv8 = MiniV8::new();
     assert_eq!(mv8.create_string("abc😊🈹").to_string(), "abc😊🈹".to_string());
 }
+
+#[test]
+fn string_builder() {
+    let mv8 = MiniV8::new();
+    let mut builder = mv8.create_string_builder();
+    builder.append_str("abc").unwrap();
+    builder.append_value(123).unwrap();
+    builder.append_value(Value::Null).unwrap();
+    builder.append_str("😊").unwrap();
+    assert_eq!(builder.finish().to_string(), "abc123null😊".to_string());
+    assert_eq!(mv8.create_string_builder().finish().to_string(), "".to_string());
+}
+
+#[test]
+fn string_builder_untampered() {
+    let mv8 = MiniV8::new();
+    let _: () = mv8.eval("String.prototype.concat = function() { return 'tampered'; }").unwrap();
+    let string = mv8.create_string_concat(vec!["a", "b", "c"]).unwrap();
+    assert_eq!(string.to_string(), "abc".to_string());
+}
+
+#[test]
+fn string_builder_many() {
+    let mv8 = MiniV8::new();
+    let mut builder = mv8.create_string_builder();
+    for i in 0..10_000 {
+        builder.append_value(i % 10).unwrap();
+    }
+    let string = builder.finish();
+    mv8.global().set("built", string).unwrap();
+    let len: usize = mv8.eval("built.length").unwrap();
+    assert_eq!(len, 10_000);
+    let tail: StdString = mv8.eval("built.slice(-3)").unwrap();
+    assert_eq!(tail, "789");
+}
+
+#[test]
+fn write_utf8_to() {
+    let mv8 = MiniV8::new();
+    // Surrogate pairs at every offset relative to the chunk boundaries, as well as 1-, 2- and
+    // 3-byte characters and an unpaired surrogate:
+    let source = "a😊b😊cd😊é🈹😊😊x😊";
+    let string: String = mv8.eval(format!("'{}' + '\\ud83d' + 'y'", source)).unwrap();
+    let expected = format!("{}\u{fffd}y", source);
+    for chunk_size in 6..20 {
+        let mut out = Vec::new();
+        let written = string.write_utf8_to(&mut out, chunk_size).unwrap();
+        assert_eq!(written, expected.len() as u64);
+        assert_eq!(StdString::from_utf8(out).unwrap(), expected);
+    }
+
+    let mut out = Vec::new();
+    assert_eq!(mv8.create_string("").write_utf8_to(&mut out, 6).unwrap(), 0);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn create_string_lossy() {
+    let mv8 = MiniV8::new();
+    let cases: &[&[u8]] = &[
+        b"",
+        b"plain",
+        "abc😊🈹".as_bytes(),
+        // Invalid bytes at the start, in the middle, and at the end:
+        b"\xffabc",
+        b"a\x80b",
+        b"abc\xc3",
+        // Truncated multi-byte sequences at the end:
+        b"abc\xf0\x9f\x98",
+        b"abc\xe2\x82",
+        // Overlong encodings, encoded surrogates, and code points beyond U+10FFFF:
+        b"\xc0\xafx",
+        b"\xed\xa0\x80x",
+        b"\xf4\x90\x80\x80x",
+        // A truncated sequence followed by a valid one:
+        b"\xf0\x9f\xf0\x9f\x98\x8a",
+    ];
+    for bytes in cases {
+        let string = mv8.create_string_lossy(bytes);
+        assert_eq!(string.to_string(), StdString::from_utf8_lossy(bytes), "{:?}", bytes);
+    }
+}
+
+#[test]
+fn create_string_latin1() {
+    let mv8 = MiniV8::new();
+    let bytes: Vec<u8> = (0..=255).collect();
+    let string = mv8.create_string_latin1(&bytes);
+    let expected: StdString = bytes.iter().map(|&b| b as char).collect();
+    assert_eq!(string.to_string(), expected);
+    mv8.global().set("latin1", string).unwrap();
+    let len: u32 = mv8.eval("latin1.length").unwrap();
+    assert_eq!(len, 256);
+    let code: u32 = mv8.eval("latin1.charCodeAt(0xe9)").unwrap();
+    assert_eq!(code, 0xe9);
+}
+
+#[test]
+fn utf16() {
+    let mv8 = MiniV8::new();
+    let string: String = mv8.eval("'a😊\\ud800b'").unwrap();
+    assert_eq!(string.len_utf16(), 5);
+    assert_eq!(string.to_utf16(), vec![0x61, 0xd83d, 0xde0a, 0xd800, 0x62]);
+    assert_eq!(string.to_rust_string_lossy(), "a😊\u{fffd}b");
+    assert!(matches!(string.to_rust_string(), Err(Error::FromJsConversionError { .. })));
+
+    // Strings round-trip through UTF-16 losslessly, unpaired surrogates included:
+    let copy = mv8.create_string_utf16(&string.to_utf16());
+    let check: Function = mv8.eval("(a, b) => a === b").unwrap();
+    assert!(check.call::<_, bool>((string, copy)).unwrap());
+    let lone_low = mv8.create_string_utf16(&[0xdc00]);
+    mv8.global().set("loneLow", lone_low).unwrap();
+    assert_eq!(mv8.eval::<_, u32>("loneLow.charCodeAt(0)").unwrap(), 0xdc00);
+
+    let valid = mv8.create_string_utf16(&"abc😊🈹".encode_utf16().collect::<Vec<_>>());
+    assert_eq!(valid.to_rust_string().unwrap(), "abc😊🈹");
+    assert_eq!(valid.len_utf16(), 7);
+    assert_eq!(mv8.create_string("").to_utf16(), Vec::<u16>::new());
+}
+
+#[test]
+fn os_string() {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    let mv8 = MiniV8::new();
+    let path = PathBuf::from("dir/file.txt");
+    let value = path.clone().to_value(&mv8).unwrap();
+    assert_eq!(value.as_string().unwrap().to_string(), "dir/file.txt");
+    assert_eq!(PathBuf::from_value(value, &mv8).unwrap(), path);
+    let os: OsString = mv8.eval("'caf\\u00e9'").unwrap();
+    assert_eq!(os, OsString::from("café"));
+
+    #[cfg(not(windows))]
+    {
+        let surrogate: Result<OsString> = mv8.eval("'\\ud800'");
+        assert!(matches!(surrogate, Err(Error::FromJsConversionError { .. })));
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+        let surrogate: OsString = mv8.eval("'\\ud800'").unwrap();
+        assert_eq!(surrogate.encode_wide().collect::<Vec<_>>(), vec![0xd800]);
+        let value = OsString::from_wide(&[0xdc00]).to_value(&mv8).unwrap();
+        assert_eq!(value.as_string().unwrap().to_utf16(), vec![0xdc00]);
+    }
+}
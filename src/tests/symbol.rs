@@ -0,0 +1,88 @@
+use crate::*;
+use std::string::String as StdString;
+
+#[test]
+fn symbol_for() {
+    let mv8 = MiniV8::new();
+    let from_script: Symbol = mv8.eval("Symbol.for('app.hook')").unwrap();
+    let from_rust = mv8.symbol_for("app.hook");
+    let check: Function = mv8.eval("(a, b) => a === b").unwrap();
+    let equal: bool = check.call((from_script, from_rust.clone())).unwrap();
+    assert!(equal);
+    assert_eq!(from_rust.description(), Some("app.hook".to_owned()));
+}
+
+#[test]
+fn symbol_key_for() {
+    let mv8 = MiniV8::new();
+    let registered = mv8.symbol_for("app.hook");
+    assert_eq!(mv8.symbol_key_for(&registered), Some("app.hook".to_owned()));
+    let unregistered: Symbol = mv8.eval("Symbol('app.hook')").unwrap();
+    assert_eq!(mv8.symbol_key_for(&unregistered), None);
+    let anonymous: Symbol = mv8.eval("Symbol()").unwrap();
+    assert_eq!(anonymous.description(), None);
+    assert_eq!(mv8.symbol_key_for(&anonymous), None);
+
+    // Scripts replacing `Symbol.keyFor` don't affect the lookup:
+    mv8.eval::<_, ()>("Symbol.keyFor = () => 'tampered'").unwrap();
+    assert_eq!(mv8.symbol_key_for(&registered), Some("app.hook".to_owned()));
+    assert_eq!(mv8.symbol_key_for(&unregistered), None);
+}
+
+#[test]
+fn property_keys() {
+    let mv8 = MiniV8::new();
+    let object: Object = mv8.eval("({ [Symbol.for('app.hook')]: 'hooked' })").unwrap();
+    let hook: StdString = object.get(mv8.symbol_for("app.hook")).unwrap();
+    assert_eq!(hook, "hooked");
+    object.set(mv8.symbol_for("app.other"), 1).unwrap();
+    mv8.global().set("object", object).unwrap();
+    let other: usize = mv8.eval("object[Symbol.for('app.other')]").unwrap();
+    assert_eq!(other, 1);
+}
+
+#[test]
+fn create_symbol() {
+    let mv8 = MiniV8::new();
+    let described = mv8.create_symbol(Some("app.id"));
+    assert_eq!(described.description(), Some("app.id".to_owned()));
+    assert_eq!(mv8.create_symbol(None).description(), None);
+    // Every created symbol is unique, and isn't registered:
+    let check: Function = mv8.eval("(a, b) => a === b").unwrap();
+    let equal: bool = check.call((described.clone(), mv8.create_symbol(Some("app.id")))).unwrap();
+    assert!(!equal);
+    assert_eq!(mv8.symbol_key_for(&described), None);
+
+    let object = mv8.create_object();
+    object.set(described.clone(), 1).unwrap();
+    assert!(object.has(described.clone()).unwrap());
+    assert!(!object.has("app.id").unwrap());
+    assert_eq!(object.get::<_, usize>(described.clone()).unwrap(), 1);
+    object.remove(described.clone()).unwrap();
+    assert!(!object.has(described.clone()).unwrap());
+
+    // Symbols can't be coerced to strings, which throws a `TypeError` in JavaScript:
+    let error = Value::Symbol(described).coerce_string(&mv8).unwrap_err();
+    assert_eq!(error.js_error().unwrap().name, "TypeError");
+}
+
+#[test]
+fn well_known_symbol() {
+    let mv8 = MiniV8::new();
+    let iterator = mv8.well_known_symbol(WellKnownSymbol::Iterator);
+    assert_eq!(iterator.description(), Some("Symbol.iterator".to_owned()));
+    let array: Array = mv8.eval("[1, 2]").unwrap();
+    let method: Function = array.into_object().get(iterator.clone()).unwrap();
+    let values: Function = mv8.eval("Array.prototype.values").unwrap();
+    let check: Function = mv8.eval("(a, b) => a === b").unwrap();
+    assert!(check.call::<_, bool>((method, values)).unwrap());
+    let from_script: Symbol = mv8.eval("Symbol.asyncIterator").unwrap();
+    let from_rust = mv8.well_known_symbol(WellKnownSymbol::AsyncIterator);
+    assert!(check.call::<_, bool>((from_script, from_rust)).unwrap());
+
+    let object = mv8.create_object();
+    object.set(mv8.well_known_symbol(WellKnownSymbol::ToStringTag), "Custom").unwrap();
+    mv8.global().set("object", object).unwrap();
+    let tag: StdString = mv8.eval("Object.prototype.toString.call(object)").unwrap();
+    assert_eq!(tag, "[object Custom]");
+}
@@ -0,0 +1,46 @@
+use crate::*;
+
+#[test]
+fn check_syntax() {
+    let mv8 = MiniV8::new();
+    assert_eq!(mv8.check_syntax("globalThis.ran = true; 1 + 1", None), Ok(()));
+    let ran: bool = mv8.eval("globalThis.ran === true").unwrap();
+    assert!(!ran);
+
+    let errors = mv8.check_syntax("let a = 1;\nlet b = (2 + ;\n", None).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "Unexpected token ';'");
+    assert_eq!(errors[0].line, 2);
+    assert_eq!(errors[0].start_column, 13);
+    assert_eq!(errors[0].end_column, 14);
+
+    // Module syntax is rejected in classic scripts:
+    assert!(mv8.check_syntax("export default 1;", None).is_err());
+}
+
+#[test]
+fn check_syntax_origin() {
+    let mv8 = MiniV8::new();
+    let origin = ScriptOrigin { name: "test.js".to_string(), line_offset: 10, column_offset: 0 };
+    let errors = mv8.check_syntax("\n\n)", Some(origin)).unwrap_err();
+    assert_eq!(errors[0].line, 13);
+}
+
+#[test]
+fn check_module_syntax() {
+    let mv8 = MiniV8::new();
+    assert_eq!(mv8.check_module_syntax("import x from 'missing'; export default x;", None), Ok(()));
+    assert_eq!(mv8.check_module_syntax("export default await 1;", None), Ok(()));
+    let errors = mv8.check_module_syntax("export default 1;\nexport default 2;", None).unwrap_err();
+    assert_eq!(errors[0].line, 2);
+    // Modules are always strict:
+    assert!(mv8.check_module_syntax("with ({}) {}", None).is_err());
+}
+
+#[test]
+fn check_syntax_isolated() {
+    let mv8 = MiniV8::new();
+    mv8.eval::<_, ()>("globalThis.Function = undefined").unwrap();
+    assert_eq!(mv8.check_syntax("function f() {}", None), Ok(()));
+    assert!(mv8.eval::<_, Value>("Function").unwrap().is_undefined());
+}
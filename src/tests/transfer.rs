@@ -0,0 +1,85 @@
+use crate::*;
+use std::string::String as StdString;
+
+#[test]
+fn structured_copy() {
+    let worker = MiniV8::new();
+    let parent = MiniV8::new();
+    let result: Value = worker.eval(r#"
+        const shared = { name: "shared", tag: Symbol.for("app.tag") };
+        const result = { list: [1, "two", shared, new Date(3)], shared, text: "\uD800" };
+        result.self = result;
+        result
+    "#).unwrap();
+
+    let copy = parent.transfer(&result).unwrap();
+    assert_eq!(copy.origin_context(), Some(parent.context_id()));
+    parent.global().set("copy", copy).unwrap();
+    let check: bool = parent.eval(r#"
+        copy.self === copy &&
+        copy.list[2] === copy.shared &&
+        copy.shared.name === "shared" &&
+        copy.shared.tag === Symbol.for("app.tag") &&
+        copy.list[0] === 1 && copy.list[1] === "two" &&
+        copy.list[3] instanceof Date && copy.list[3].getTime() === 3 &&
+        copy.text === "\uD800"
+    "#).unwrap();
+    assert!(check);
+
+    // Mutating the copy leaves the original untouched:
+    parent.eval::<_, Value>("copy.shared.name = 'changed'").unwrap();
+    let name: StdString = worker.eval("shared.name").unwrap();
+    assert_eq!(name, "shared");
+}
+
+#[test]
+fn sparse_array() {
+    let worker = MiniV8::new();
+    let parent = MiniV8::new();
+    let array: Value = worker.eval("const a = [1]; a.length = 2 ** 32 - 1; a[5] = 'five'; a")
+        .unwrap();
+    parent.global().set("copy", parent.transfer(&array).unwrap()).unwrap();
+    let check: bool = parent.eval(r#"
+        copy.length === 2 ** 32 - 1 && copy[0] === 1 && copy[5] === "five" && !(1 in copy)
+    "#).unwrap();
+    assert!(check);
+}
+
+#[test]
+fn depth_limit() {
+    let worker = MiniV8::new();
+    let parent = MiniV8::new();
+    let deep: Value = worker.eval("let o = {}; for (let i = 0; i < 100000; i++) o = { o }; o")
+        .unwrap();
+    match parent.transfer(&deep) {
+        Err(Error::DepthLimit { limit: 1000 }) => {},
+        _ => panic!("expected a depth limit error"),
+    }
+    let shallow: Value = worker.eval("let p = {}; for (let i = 0; i < 100; i++) p = [p]; p")
+        .unwrap();
+    assert!(parent.transfer(&shallow).unwrap().is_array());
+}
+
+#[test]
+fn untransferable() {
+    let worker = MiniV8::new();
+    let parent = MiniV8::new();
+    let function: Value = worker.eval("({ run() {} })").unwrap();
+    match parent.transfer(&function) {
+        Err(Error::ToJsConversionError { from: "Function", .. }) => {},
+        _ => panic!("expected a conversion error"),
+    }
+    let symbol: Value = worker.eval("Symbol('private')").unwrap();
+    match parent.transfer(&symbol) {
+        Err(Error::ToJsConversionError { from: "Symbol", .. }) => {},
+        _ => panic!("expected a conversion error"),
+    }
+}
+
+#[test]
+fn own_and_direct_values() {
+    let mv8 = MiniV8::new();
+    let object: Value = mv8.eval("({})").unwrap();
+    assert!(mv8.transfer(&object).unwrap().strict_eq(&object));
+    assert!(mv8.transfer(&Value::Number(1.5)).unwrap().strict_eq(&Value::Number(1.5)));
+}
@@ -1,4 +1,5 @@
 use crate::*;
+use std::time::{Duration, Instant};
 
 #[test]
 fn coerce_boolean() {
@@ -37,3 +38,160 @@ fn coerce_string() {
     assert_string_eq(&mv8, Value::String(mv8.create_string("abc")), "abc");
     assert_string_eq(&mv8, Value::Object(mv8.create_object()), "[object Object]");
 }
+
+#[test]
+fn summarize() {
+    let mv8 = MiniV8::new();
+    let value: Value = mv8.eval(r#"
+        globalThis.getterCalls = 0;
+        const value = {
+            name: 'x'.repeat(1000),
+            tags: [1, 'two', null, [3], { four: 4 }],
+            nested: { deeper: { deepest: true } },
+            run: function run() {},
+            proxy: new Proxy({}, { ownKeys() { getterCalls++; return []; } }),
+        };
+        Object.defineProperty(value, 'lazy', { enumerable: true, get() { getterCalls++; } });
+        value
+    "#).unwrap();
+
+    let options = SummaryOptions {
+        max_depth: 2,
+        max_breadth: 4,
+        max_string_len: 3,
+        max_bytes: 4096,
+    };
+    let summary = value.summarize(options);
+    let string = |prefix: &str, len| ValueSummary::String { prefix: prefix.to_owned(), len };
+    assert_eq!(summary, ValueSummary::Object {
+        len: 6,
+        properties: vec![
+            ("name".to_owned(), string("xxx", 1000)),
+            ("tags".to_owned(), ValueSummary::Array {
+                len: 5,
+                elements: vec![
+                    ValueSummary::Number(1.0),
+                    string("two", 3),
+                    ValueSummary::Null,
+                    ValueSummary::Truncated,
+                ],
+            }),
+            ("nested".to_owned(), ValueSummary::Object {
+                len: 1,
+                properties: vec![("deeper".to_owned(), ValueSummary::Truncated)],
+            }),
+            ("run".to_owned(), ValueSummary::Function { name: "run".to_owned() }),
+        ],
+    });
+    let calls: usize = mv8.global().get("getterCalls").unwrap();
+    assert_eq!(calls, 0);
+
+    let options = SummaryOptions { max_breadth: 8, ..options };
+    match value.summarize(options) {
+        ValueSummary::Object { properties, .. } => {
+            assert_eq!(properties[4], ("proxy".to_owned(), ValueSummary::Proxy));
+            assert_eq!(properties[5], ("lazy".to_owned(), ValueSummary::Accessor));
+        },
+        other => panic!("unexpected summary: {:?}", other),
+    }
+
+    // The byte budget bounds the summary of even a huge value:
+    let huge: Value = mv8.eval(r#"
+        Array.from({ length: 100000 }, (_, i) => ({ i, s: 'x'.repeat(100) }))
+    "#).unwrap();
+    let options = SummaryOptions { max_breadth: usize::MAX, max_string_len: 100, ..options };
+    match huge.summarize(SummaryOptions { max_bytes: 1024, ..options }) {
+        ValueSummary::Array { len, elements } => {
+            assert_eq!(len, 100000);
+            assert!(elements.len() < 100);
+            assert_eq!(elements.last(), Some(&ValueSummary::Truncated));
+        },
+        other => panic!("unexpected summary: {:?}", other),
+    }
+    assert_eq!(Value::Number(1.0).summarize(options), ValueSummary::Number(1.0));
+
+    let symbols: Value = mv8.eval("[Symbol('tag'), Symbol()]").unwrap();
+    assert_eq!(symbols.summarize(options), ValueSummary::Array {
+        len: 2,
+        elements: vec![ValueSummary::Symbol(Some("tag".to_owned())), ValueSummary::Symbol(None)],
+    });
+}
+
+#[test]
+fn summarize_large() {
+    let mv8 = MiniV8::new();
+    // Roughly 10 MB: a 4 MB string and 100,000 objects holding 60-character strings.
+    let large: Value = mv8.eval(r#"({
+        text: 'x'.repeat(4 << 20),
+        items: Array.from({ length: 100000 }, (_, i) => ({ i, s: 'y'.repeat(60) })),
+    })"#).unwrap();
+    let options = SummaryOptions::default();
+    let start = Instant::now();
+    for _ in 0..100 {
+        let summary = large.summarize(options);
+        match summary {
+            ValueSummary::Object { len: 2, ref properties } => {
+                let (_, text) = &properties[0];
+                assert_eq!(text, &ValueSummary::String {
+                    prefix: "x".repeat(options.max_string_len),
+                    len: 4 << 20,
+                });
+                match &properties[1].1 {
+                    ValueSummary::Array { len: 100000, elements } => {
+                        assert_eq!(elements.len(), options.max_breadth);
+                    },
+                    other => panic!("unexpected summary: {:?}", other),
+                }
+            },
+            other => panic!("unexpected summary: {:?}", other),
+        }
+    }
+    // Summarizing only reads as much of the value as the summary holds, so its cost doesn't grow
+    // with the size of the value:
+    assert!(start.elapsed() < Duration::from_secs(1), "took {:?}", start.elapsed());
+}
+
+#[test]
+fn strict_eq() {
+    let mv8 = MiniV8::new();
+    let object: Value = mv8.eval("globalThis.shared = {}").unwrap();
+    let same: Value = mv8.eval("shared").unwrap();
+    let other: Value = mv8.eval("({})").unwrap();
+    assert!(object.strict_eq(&same));
+    assert!(!object.strict_eq(&other));
+
+    let a: Value = mv8.eval("'abc'").unwrap();
+    let b = Value::String(mv8.create_string("abc"));
+    assert!(a.strict_eq(&b));
+    assert!(!a.strict_eq(&Value::String(mv8.create_string("abd"))));
+    assert!(!a.strict_eq(&Value::Number(0.0)));
+
+    assert!(!Value::Number(f64::NAN).strict_eq(&Value::Number(f64::NAN)));
+    assert!(Value::Number(0.0).strict_eq(&Value::Number(-0.0)));
+    assert!(Value::Undefined.strict_eq(&Value::Undefined));
+    assert!(!Value::Undefined.strict_eq(&Value::Null));
+
+    let symbol: Value = mv8.eval("Symbol('s')").unwrap();
+    assert!(symbol.strict_eq(&symbol.clone()));
+    let other_symbol: Value = mv8.eval("Symbol('s')").unwrap();
+    assert!(!symbol.strict_eq(&other_symbol));
+
+    // References from different contexts are never equal:
+    let other_mv8 = MiniV8::new();
+    let elsewhere = Value::String(other_mv8.create_string("abc"));
+    assert!(!a.strict_eq(&elsewhere));
+}
+
+#[test]
+fn type_of() {
+    let mv8 = MiniV8::new();
+    let sources = [
+        "undefined", "null", "true", "1.5", "new Date(0)", "'s'", "Symbol()", "[]", "(() => {})",
+        "({})", "new Proxy(function() {}, {})",
+    ];
+    for source in sources {
+        let value: Value = mv8.eval(source).unwrap();
+        let expected: std::string::String = mv8.eval(format!("typeof ({})", source)).unwrap();
+        assert_eq!(value.type_of(), expected, "{}", source);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::string::String as StdString;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Document {
+    title: StdString,
+    version: u32,
+    ratio: f64,
+    draft: bool,
+    tags: Vec<StdString>,
+    author: Option<Author>,
+    reviewer: Option<Author>,
+    sections: BTreeMap<StdString, Section>,
+    status: Status,
+    history: Vec<Status>,
+    point: (i32, i32),
+    id: Id,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Author {
+    name: StdString,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Section {
+    lines: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Id(u32);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Status {
+    Draft,
+    Published(u32),
+    Moved(StdString, u32),
+    Archived { reason: StdString },
+}
+
+fn document() -> Document {
+    let mut sections = BTreeMap::new();
+    sections.insert("intro".to_string(), Section { lines: 3 });
+    Document {
+        title: "Title".to_string(),
+        version: 2,
+        ratio: 0.5,
+        draft: false,
+        tags: vec!["a".to_string(), "b".to_string()],
+        author: Some(Author { name: "Author".to_string() }),
+        reviewer: None,
+        sections,
+        status: Status::Archived { reason: "old".to_string() },
+        history: vec![Status::Draft, Status::Published(1), Status::Moved("x".to_string(), 2)],
+        point: (-1, 1),
+        id: Id(7),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mv8 = MiniV8::new();
+    let value = mv8.to_value_serde(&document()).unwrap();
+    mv8.global().set("doc", value.clone()).unwrap();
+    let json: StdString = mv8.eval("JSON.stringify(doc)").unwrap();
+    assert_eq!(json, concat!(
+        r#"{"title":"Title","version":2,"ratio":0.5,"draft":false,"tags":["a","b"],"#,
+        r#""author":{"name":"Author"},"reviewer":null,"sections":{"intro":{"lines":3}},"#,
+        r#""status":{"Archived":{"reason":"old"}},"#,
+        r#""history":["Draft",{"Published":1},{"Moved":["x",2]}],"point":[-1,1],"id":7}"#,
+    ));
+    let document2: Document = mv8.from_value_serde(value).unwrap();
+    assert_eq!(document2, document());
+}
+
+#[test]
+fn from_js() {
+    let mv8 = MiniV8::new();
+    let value = mv8.eval(r#"({
+        title: "From JS", version: 1, ratio: 1, draft: true, tags: [],
+        author: undefined, sections: {}, status: "Draft", history: [],
+        point: [0, 0], id: 1, extra: "ignored",
+    })"#).unwrap();
+    let document: Document = mv8.from_value_serde(value).unwrap();
+    assert_eq!(document.title, "From JS");
+    assert_eq!(document.ratio, 1.0);
+    assert_eq!(document.author, None);
+    assert_eq!(document.reviewer, None);
+    assert_eq!(document.status, Status::Draft);
+
+    let map: HashMap<u32, StdString> = mv8.from_value_serde(mv8.eval("({ 1: 'a', 20: 'b' })")
+        .unwrap()).unwrap();
+    assert_eq!(map.get(&20).map(StdString::as_str), Some("b"));
+    let map: BTreeMap<StdString, StdString> = mv8.from_value_serde(mv8.eval("({ 1: 'a', b: 'b' })")
+        .unwrap()).unwrap();
+    assert_eq!(map.get("1").map(StdString::as_str), Some("a"));
+    assert_eq!(map.get("b").map(StdString::as_str), Some("b"));
+    let wrong = mv8.from_value_serde::<Vec<u32>>(mv8.eval("[1, 'two']").unwrap());
+    assert!(wrong.is_err());
+    let wrong = mv8.from_value_serde::<u32>(mv8.eval("1.5").unwrap());
+    assert!(wrong.is_err());
+}
+
+#[test]
+fn cycles() {
+    let mv8 = MiniV8::new();
+    let value = mv8.eval("const a = { name: 'a' }; a.self = a; a").unwrap();
+    let result = mv8.from_value_serde::<HashMap<StdString, serde::de::IgnoredAny>>(value);
+    assert!(matches!(result, Err(Error::Serde(_))));
+    let value = mv8.eval("const b = []; b.push(b); b").unwrap();
+    assert!(mv8.from_value_serde::<Vec<serde::de::IgnoredAny>>(value).is_err());
+
+    // Values that are shared without being cyclic are fine:
+    let value = mv8.eval("const c = { lines: 1 }; ({ x: c, y: c })").unwrap();
+    let map: BTreeMap<StdString, Section> = mv8.from_value_serde(value).unwrap();
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn serialize_errors() {
+    let mv8 = MiniV8::new();
+    assert!(mv8.to_value_serde(&(1u64 << 53)).is_err());
+    assert!(mv8.to_value_serde(&((1u64 << 53) - 1)).is_ok());
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 1);
+    assert!(mv8.to_value_serde(&map).is_err());
+}
@@ -0,0 +1,124 @@
+use crate::*;
+use std::collections::HashMap;
+
+// The deepest nesting of arrays and objects within a value that `MiniV8::transfer` copies.
+const MAX_DEPTH: usize = 1000;
+
+impl MiniV8 {
+    /// Copies a value that belongs to another `MiniV8` (see `Value::origin_context`) into this one,
+    /// e.g. to hand the result of a script run in a worker context to a parent context. Values
+    /// that already belong to this `MiniV8`, and values that aren't references (e.g. numbers and
+    /// dates), are returned as they are.
+    ///
+    /// Strings are copied exactly (including lone surrogates), and symbols registered with
+    /// `Symbol.for` become the symbol registered with the same key in this `MiniV8`. Arrays and
+    /// other objects are copied into arrays (of the same length, keeping holes) and plain objects
+    /// with the same own enumerable string-keyed properties (getters are invoked, and prototypes
+    /// are not copied). Sharing is preserved: an object that is reachable more than once,
+    /// including through a cycle, is copied once, and every reference to it refers to the copy.
+    ///
+    /// Functions and unregistered symbols can't be copied, and result in a
+    /// `Error::ToJsConversionError`. Arrays and objects nested more than 1000 levels deep result
+    /// in a `Error::DepthLimit`.
+    pub fn transfer(&self, value: &Value) -> Result<Value> {
+        match value.origin_context() {
+            Some(origin) if origin != self.context_id() => {
+                let mut transfer =
+                    Transfer { mv8: self, copies: HashMap::new(), pending: Vec::new() };
+                let copy = transfer.value(value, 0)?;
+                while let Some((original, copy, depth)) = transfer.pending.pop() {
+                    transfer.properties(&original, &copy, depth)?;
+                }
+                Ok(copy)
+            },
+            _ => Ok(value.clone()),
+        }
+    }
+}
+
+struct Transfer<'a> {
+    mv8: &'a MiniV8,
+    // Copies of the objects transferred so far, keyed by the identity hash of the original (which
+    // isn't unique, hence the buckets that are searched with `strict_eq`):
+    copies: HashMap<i32, Vec<(Value, Value)>>,
+    // Objects that have been copied, but whose properties have yet to be, along with their depth.
+    // Copying properties from this list rather than recursively keeps deeply nested values from
+    // exhausting the native stack:
+    pending: Vec<(Object, Object, usize)>,
+}
+
+impl<'a> Transfer<'a> {
+    fn value(&mut self, value: &Value, depth: usize) -> Result<Value> {
+        Ok(match value {
+            Value::Undefined | Value::Null | Value::Boolean(_) | Value::Number(_) |
+            Value::Date(_) => value.clone(),
+            Value::String(string) => {
+                Value::String(self.mv8.create_string_utf16(&string.to_utf16()))
+            },
+            Value::Symbol(symbol) => match symbol.mv8.symbol_key_for(symbol) {
+                Some(key) => Value::Symbol(self.mv8.symbol_for(&key)),
+                None => return Err(Error::ToJsConversionError { from: "Symbol", to: "value" }),
+            },
+            Value::Function(_) => {
+                return Err(Error::ToJsConversionError { from: "Function", to: "value" });
+            },
+            Value::Array(_) | Value::Object(_) => {
+                let hash = identity_hash(value);
+                if let Some(copy) = self.copy_of(hash, value) {
+                    return Ok(copy);
+                }
+                if depth > MAX_DEPTH {
+                    return Err(Error::DepthLimit { limit: MAX_DEPTH });
+                }
+                let (original, copy, object) = match value {
+                    Value::Array(array) => {
+                        let copy = self.mv8.create_array();
+                        let object = copy.clone().into_object();
+                        object.set("length", array.len())?;
+                        (array.clone().into_object(), Value::Array(copy), object)
+                    },
+                    Value::Object(object) => {
+                        let copy = self.mv8.create_object();
+                        (object.clone(), Value::Object(copy.clone()), copy)
+                    },
+                    _ => unreachable!(),
+                };
+                self.insert(hash, value, copy.clone());
+                self.pending.push((original, object, depth));
+                copy
+            },
+        })
+    }
+
+    // Copies the properties of `original` (found at `depth`) to `copy`. Arrays are copied by their
+    // own keys too, so that sparse arrays don't cost time in proportion to their length.
+    fn properties(&mut self, original: &Object, copy: &Object, depth: usize) -> Result<()> {
+        for key in original.keys(false)?.elements::<Value>() {
+            let key = key?;
+            let property: Value = original.get(key.clone())?;
+            copy.set(self.value(&key, depth)?, self.value(&property, depth + 1)?)?;
+        }
+        Ok(())
+    }
+
+    fn copy_of(&self, hash: i32, value: &Value) -> Option<Value> {
+        let bucket = self.copies.get(&hash)?;
+        bucket.iter().find(|(original, _)| original.strict_eq(value)).map(|(_, copy)| copy.clone())
+    }
+
+    fn insert(&mut self, hash: i32, value: &Value, copy: Value) {
+        self.copies.entry(hash).or_default().push((value.clone(), copy));
+    }
+}
+
+fn identity_hash(value: &Value) -> i32 {
+    match value {
+        Value::Array(Array { mv8, handle }) => {
+            mv8.scope(|scope| v8::Local::new(scope, handle.clone()).get_identity_hash().get())
+        },
+        Value::Object(Object { mv8, handle }) => {
+            mv8.scope(|scope| v8::Local::new(scope, handle.clone()).get_identity_hash().get())
+        },
+        _ => unreachable!(),
+    }
+}
@@ -1,14 +1,16 @@
 use crate::*;
+use crate::conversion::system_time_from_date;
 use std::iter::FromIterator;
 use std::ops::{Deref, DerefMut};
+use std::time::{Duration, SystemTime};
 use std::{fmt, slice, vec};
 
 /// A JavaScript value.
 ///
 /// `Value`s can either hold direct values (undefined, null, booleans, numbers, dates) or references
-/// (strings, arrays, functions, other objects). Cloning values (via Rust's `Clone`) of the direct
-/// types defers to Rust's `Copy`, while cloning values of the referential types results in a simple
-/// reference clone similar to JavaScript's own "by-reference" semantics.
+/// (strings, symbols, arrays, functions, other objects). Cloning values (via Rust's `Clone`) of the
+/// direct types defers to Rust's `Copy`, while cloning values of the referential types results in a
+/// simple reference clone similar to JavaScript's own "by-reference" semantics.
 #[derive(Clone)]
 pub enum Value {
     /// The JavaScript value `undefined`.
@@ -23,6 +25,8 @@ pub enum Value {
     Date(f64),
     /// An immutable JavaScript string, managed by V8.
     String(String),
+    /// Reference to a JavaScript symbol.
+    Symbol(Symbol),
     /// Reference to a JavaScript arrray.
     Array(Array),
     /// Reference to a JavaScript function.
@@ -63,6 +67,11 @@ impl Value {
         if let Value::String(_) = *self { true } else { false }
     }
 
+    /// Returns `true` if this is a `Value::Symbol`, `false` otherwise.
+    pub fn is_symbol(&self) -> bool {
+        if let Value::Symbol(_) = *self { true } else { false }
+    }
+
     /// Returns `true` if this is a `Value::Array`, `false` otherwise.
     pub fn is_array(&self) -> bool {
         if let Value::Array(_) = *self { true } else { false }
@@ -103,11 +112,22 @@ impl Value {
         if let Value::Date(value) = *self { Some(value) } else { None }
     }
 
+    /// Returns `Some` if this is a valid `Value::Date` (which may be before the epoch), `None`
+    /// otherwise. Invalid dates (e.g. `new Date(NaN)`) are `Value::Date`s that hold NaN.
+    pub fn as_system_time(&self) -> Option<SystemTime> {
+        self.as_date().and_then(system_time_from_date)
+    }
+
     /// Returns `Some` if this is a `Value::String`, `None` otherwise.
     pub fn as_string(&self) -> Option<&String> {
         if let Value::String(ref value) = *self { Some(value) } else { None }
     }
 
+    /// Returns `Some` if this is a `Value::Symbol`, `None` otherwise.
+    pub fn as_symbol(&self) -> Option<&Symbol> {
+        if let Value::Symbol(ref value) = *self { Some(value) } else { None }
+    }
+
     /// Returns `Some` if this is a `Value::Array`, `None` otherwise.
     pub fn as_array(&self) -> Option<&Array> {
         if let Value::Array(ref value) = *self { Some(value) } else { None }
@@ -123,6 +143,55 @@ impl Value {
         if let Value::Object(ref value) = *self { Some(value) } else { None }
     }
 
+    /// Returns the id of the context (see `MiniV8::context_id`) that this value belongs to, or
+    /// `None` if this value isn't a reference to a JavaScript value, and so belongs to no context
+    /// in particular.
+    pub fn origin_context(&self) -> Option<u64> {
+        self.mini_v8().map(MiniV8::context_id)
+    }
+
+    /// Returns `true` if this value is strictly equal to `other`, like JavaScript's `===`
+    /// operator: References are equal if they refer to the same JavaScript value (strings are
+    /// compared by content), `NaN` is not equal to itself, and `0` is equal to `-0`.
+    ///
+    /// `Value::Date` holds a copy of the date's time rather than a reference to the JavaScript
+    /// `Date` object, so dates are compared by time. References that belong to different contexts
+    /// (see `Value::origin_context`) are never equal.
+    pub fn strict_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) | (Value::Null, Value::Null) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) | (Value::Date(a), Value::Date(b)) => a == b,
+            (Value::String(_), Value::String(_)) |
+            (Value::Symbol(_), Value::Symbol(_)) |
+            (Value::Array(_), Value::Array(_)) |
+            (Value::Function(_), Value::Function(_)) |
+            (Value::Object(_), Value::Object(_)) => {
+                if self.origin_context() != other.origin_context() {
+                    return false;
+                }
+                self.mini_v8().unwrap().scope(|scope| {
+                    self.to_v8_value(scope).strict_equals(other.to_v8_value(scope))
+                })
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns the type of this value as reported by JavaScript's `typeof` operator, e.g.
+    /// `"object"` for `null`, dates and arrays, and `"function"` for functions.
+    pub fn type_of(&self) -> &'static str {
+        match *self {
+            Value::Undefined => "undefined",
+            Value::Null | Value::Date(_) | Value::Array(_) | Value::Object(_) => "object",
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Symbol(_) => "symbol",
+            Value::Function(_) => "function",
+        }
+    }
+
     /// A wrapper around `FromValue::from_value`.
     pub fn into<T: FromValue>(self, mv8: &MiniV8) -> Result< T> {
         T::from_value(self, mv8)
@@ -130,6 +199,9 @@ impl Value {
 
     /// Coerces a value to a boolean. Returns `true` if the value is "truthy", `false` otherwise.
     pub fn coerce_boolean(&self, mv8: &MiniV8) -> bool {
+        // Coercing to a boolean runs no JavaScript, so a value that belongs to another `MiniV8` is
+        // coerced with that one:
+        let mv8 = self.mini_v8().unwrap_or(mv8);
         match self {
             &Value::Boolean(b) => b,
             value => mv8.scope(|scope| value.to_v8_value(scope).boolean_value(scope)),
@@ -144,10 +216,10 @@ impl Value {
     pub fn coerce_number(&self, mv8: &MiniV8) -> Result<f64> {
         match self {
             &Value::Number(n) => Ok(n),
-            value => mv8.try_catch(|scope| {
+            value => mv8.assert_owns(value).and_then(|_| mv8.try_catch(|scope| {
                 let maybe = value.to_v8_value(scope).to_number(scope);
                 mv8.exception(scope).map(|_| maybe.unwrap().value())
-            }),
+            })),
         }
     }
 
@@ -157,13 +229,25 @@ impl Value {
     pub fn coerce_string(&self, mv8: &MiniV8) -> Result<String> {
         match self {
             &Value::String(ref s) => Ok(s.clone()),
-            value => mv8.try_catch(|scope| {
+            value => mv8.assert_owns(value).and_then(|_| mv8.try_catch(|scope| {
                 let maybe = value.to_v8_value(scope).to_string(scope);
                 mv8.exception(scope).map(|_| String {
                     mv8: mv8.clone(),
                     handle: v8::Global::new(scope, maybe.unwrap()),
                 })
-            }),
+            })),
+        }
+    }
+
+    fn mini_v8(&self) -> Option<&MiniV8> {
+        match self {
+            Value::Undefined | Value::Null | Value::Boolean(_) | Value::Number(_) |
+            Value::Date(_) => None,
+            Value::Array(Array { mv8, .. }) |
+            Value::Function(Function { mv8, .. }) |
+            Value::Object(Object { mv8, .. }) |
+            Value::String(String { mv8, .. }) |
+            Value::Symbol(Symbol { mv8, .. }) => Some(mv8),
         }
     }
 
@@ -178,6 +262,7 @@ impl Value {
             Value::Array(_) => "array",
             Value::Object(_) => "object",
             Value::String(_) => "string",
+            Value::Symbol(_) => "symbol",
         }
     }
 
@@ -203,6 +288,10 @@ impl Value {
             let value: v8::Local<v8::String> = value.try_into().unwrap();
             let handle = v8::Global::new(scope, value);
             Value::String(String { mv8: mv8.clone(), handle })
+        } else if value.is_symbol() {
+            let value: v8::Local<v8::Symbol> = value.try_into().unwrap();
+            let handle = v8::Global::new(scope, value);
+            Value::Symbol(Symbol { mv8: mv8.clone(), handle })
         } else if value.is_array() {
             let value: v8::Local<v8::Array> = value.try_into().unwrap();
             let handle = v8::Global::new(scope, value);
@@ -233,6 +322,7 @@ impl Value {
             Value::Array(v) => v8::Local::new(scope, v.handle.clone()).into(),
             Value::Object(v) => v8::Local::new(scope, v.handle.clone()).into(),
             Value::String(v) => v8::Local::new(scope, v.handle.clone()).into(),
+            Value::Symbol(v) => v8::Local::new(scope, v.handle.clone()).into(),
         }
     }
 }
@@ -246,6 +336,7 @@ impl fmt::Debug for Value {
             Value::Number(n) => write!(f, "{}", n),
             Value::Date(d) => write!(f, "date:{}", d),
             Value::String(s) => write!(f, "{:?}", s),
+            Value::Symbol(s) => write!(f, "{:?}", s),
             Value::Array(a) => write!(f, "{:?}", a),
             Value::Function(u) => write!(f, "{:?}", u),
             Value::Object(o) => write!(f, "{:?}", o),
@@ -403,3 +494,31 @@ impl<T> DerefMut for Variadic<T> {
         &mut self.0
     }
 }
+
+/// Wraps an integer that is converted from a JavaScript number without checking that the number
+/// fits, as opted out of the checked conversion of the integer types' own `FromValue`. The number
+/// is coerced as usual, and then truncated towards zero and saturated at the bounds of the type,
+/// with NaN converting to zero (like Rust's `as` casts).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lossy<T>(pub T);
+
+impl<T> Lossy<T> {
+    /// Consumes the wrapper and returns the integer.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Wraps a duration that is converted to and from a JavaScript number of milliseconds (e.g. for
+/// timeouts), rather than to and from a `Date` after the epoch, as `Duration` itself is.
+/// Sub-millisecond precision is kept as the number's fractional part. Only finite, non-negative
+/// numbers can be converted from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Millis(pub Duration);
+
+impl Millis {
+    /// Consumes the wrapper and returns the duration.
+    pub fn into_inner(self) -> Duration {
+        self.0
+    }
+}
@@ -0,0 +1,663 @@
+use crate::*;
+use serde::de::{self, IntoDeserializer, Unexpected};
+use serde::ser::{self, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::string::String as StdString;
+
+// The largest integer that JavaScript numbers represent exactly (`Number.MAX_SAFE_INTEGER`).
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+impl MiniV8 {
+    /// Converts any value that implements `serde::Serialize` to a JavaScript value.
+    ///
+    /// Maps and structs become plain objects, sequences and tuples become arrays, `None` and `()`
+    /// become `null`, and byte buffers (e.g. with `serde_bytes`) become `Uint8Array`s. Enums are
+    /// represented like `serde_json` does by default: unit variants as strings, and other
+    /// variants as objects with the variant name as their only key.
+    ///
+    /// Returns an error if a map key isn't a string or a number, or if an integer can't be
+    /// represented exactly as a JavaScript number (i.e. its magnitude exceeds 2^53-1).
+    pub fn to_value_serde<T: Serialize + ?Sized>(&self, value: &T) -> Result<Value> {
+        value.serialize(Serializer { mv8: self })
+    }
+
+    /// Converts a JavaScript value to any type that implements `serde::de::DeserializeOwned`,
+    /// reading the representations produced by `MiniV8::to_value_serde`.
+    ///
+    /// Objects are read through their own enumerable string-keyed properties (like
+    /// `Object.keys`), and `undefined` is read like `null`. Returns an error if the value doesn't
+    /// have the expected shape, or if an array or object contains itself.
+    pub fn from_value_serde<T: de::DeserializeOwned>(&self, value: Value) -> Result<T> {
+        self.assert_owns(&value)?;
+        let ancestors = RefCell::new(Vec::new());
+        T::deserialize(Deserializer { mv8: self, value, ancestors: &ancestors })
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error::Serde(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error::Serde(msg.to_string())
+    }
+}
+
+fn custom(msg: &str) -> Error {
+    Error::Serde(msg.to_owned())
+}
+
+struct Serializer<'a> {
+    mv8: &'a MiniV8,
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeArray<'a>;
+    type SerializeTuple = SerializeArray<'a>;
+    type SerializeTupleStruct = SerializeArray<'a>;
+    type SerializeTupleVariant = SerializeTupleVariant<'a>;
+    type SerializeMap = SerializeObject<'a>;
+    type SerializeStruct = SerializeObject<'a>;
+    type SerializeStructVariant = SerializeStructVariant<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        if v.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(Error::ToJsConversionError { from: "i64", to: "number" });
+        }
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        if v > MAX_SAFE_INTEGER {
+            return Err(Error::ToJsConversionError { from: "u64", to: "number" });
+        }
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::Number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        Ok(Value::String(self.mv8.create_string(v.encode_utf8(&mut [0; 4]))))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        Ok(Value::String(self.mv8.create_string(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        Bytes::from(v).to_value(self.mv8)
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value> {
+        let object = self.mv8.create_object();
+        object.set(variant, value.serialize(Serializer { mv8: self.mv8 })?)?;
+        Ok(Value::Object(object))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeArray<'a>> {
+        Ok(SerializeArray { mv8: self.mv8, array: self.mv8.create_array() })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant<'a>> {
+        Ok(SerializeTupleVariant { variant, array: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject<'a>> {
+        Ok(SerializeObject { mv8: self.mv8, object: self.mv8.create_object(), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject<'a>> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant<'a>> {
+        Ok(SerializeStructVariant { variant, object: self.serialize_map(Some(len))? })
+    }
+}
+
+struct SerializeArray<'a> {
+    mv8: &'a MiniV8,
+    array: Array,
+}
+
+impl<'a> ser::SerializeSeq for SerializeArray<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.array.push(value.serialize(Serializer { mv8: self.mv8 })?)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Array(self.array))
+    }
+}
+
+impl<'a> ser::SerializeTuple for SerializeArray<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SerializeArray<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeTupleVariant<'a> {
+    variant: &'static str,
+    array: SerializeArray<'a>,
+}
+
+impl<'a> ser::SerializeTupleVariant for SerializeTupleVariant<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(&mut self.array, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let object = self.array.mv8.create_object();
+        object.set(self.variant, self.array.array)?;
+        Ok(Value::Object(object))
+    }
+}
+
+struct SerializeObject<'a> {
+    mv8: &'a MiniV8,
+    object: Object,
+    key: Option<Value>,
+}
+
+impl<'a> ser::SerializeMap for SerializeObject<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(Serializer { mv8: self.mv8 })? {
+            key @ Value::String(_) | key @ Value::Number(_) => {
+                self.key = Some(key);
+                Ok(())
+            },
+            _ => Err(custom("map key must be a string or a number")),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().expect("serialize_value called before serialize_key");
+        self.object.set(key, value.serialize(Serializer { mv8: self.mv8 })?)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Object(self.object))
+    }
+}
+
+impl<'a> ser::SerializeStruct for SerializeObject<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.object.set(key, value.serialize(Serializer { mv8: self.mv8 })?)
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Object(self.object))
+    }
+}
+
+struct SerializeStructVariant<'a> {
+    variant: &'static str,
+    object: SerializeObject<'a>,
+}
+
+impl<'a> ser::SerializeStructVariant for SerializeStructVariant<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(&mut self.object, key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let object = self.object.mv8.create_object();
+        object.set(self.variant, self.object.object)?;
+        Ok(Value::Object(object))
+    }
+}
+
+// The arrays and objects that are being deserialized, from the outermost to the innermost, which
+// are tracked to detect values that contain themselves.
+type Ancestors = RefCell<Vec<v8::Global<v8::Object>>>;
+
+struct Deserializer<'a> {
+    mv8: &'a MiniV8,
+    value: Value,
+    ancestors: &'a Ancestors,
+}
+
+impl<'a> Deserializer<'a> {
+    fn nested(&self, value: Value) -> Deserializer<'a> {
+        Deserializer { mv8: self.mv8, value, ancestors: self.ancestors }
+    }
+
+    // Deserializes the contents of an array or object with `f`, returning an error if the array
+    // or object is already being deserialized further out.
+    fn enter<T>(&self, object: Object, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if self.ancestors.borrow().contains(&object.handle) {
+            return Err(custom("cannot deserialize a value that contains itself"));
+        }
+        self.ancestors.borrow_mut().push(object.handle);
+        let result = f();
+        self.ancestors.borrow_mut().pop();
+        result
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Undefined | Value::Null => visitor.visit_unit(),
+            Value::Boolean(b) => visitor.visit_bool(b),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER as f64 => {
+                if n >= 0.0 { visitor.visit_u64(n as u64) } else { visitor.visit_i64(n as i64) }
+            },
+            Value::Number(n) | Value::Date(n) => visitor.visit_f64(n),
+            Value::String(ref s) => visitor.visit_string(s.to_string()),
+            Value::Symbol(_) => Err(de::Error::invalid_type(Unexpected::Other("symbol"), &visitor)),
+            Value::Function(_) => {
+                Err(de::Error::invalid_type(Unexpected::Other("function"), &visitor))
+            },
+            Value::Array(ref array) => {
+                let len = array.len();
+                let access = SeqAccess { de: &self, array: array.clone(), index: 0, len };
+                self.enter(array.clone().into_object(), || visitor.visit_seq(access))
+            },
+            Value::Object(ref object) => {
+                let keys = object.keys(false)?;
+                let access = MapAccess {
+                    de: &self,
+                    object: object.clone(),
+                    len: keys.len(),
+                    keys,
+                    index: 0,
+                    key: None,
+                };
+                self.enter(object.clone(), || visitor.visit_map(access))
+            },
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Undefined | Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Bytes::from_value(self.value.clone(), self.mv8) {
+            Ok(bytes) => visitor.visit_byte_buf(bytes.into_vec()),
+            Err(_) => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.value {
+            Value::String(ref variant) => {
+                visitor.visit_enum(variant.to_string().into_deserializer())
+            },
+            Value::Object(ref object) => {
+                let keys: Vec<StdString> = object.keys(false)?.elements().collect::<Result<_>>()?;
+                let variant = match <[StdString; 1]>::try_from(keys) {
+                    Ok([variant]) => variant,
+                    Err(_) => return Err(custom("enum object must have exactly one key")),
+                };
+                let value = object.get(variant.as_str())?;
+                let access = EnumAccess { de: self.nested(value), variant };
+                self.enter(object.clone(), || visitor.visit_enum(access))
+            },
+            _ => Err(de::Error::invalid_type(Unexpected::Other(self.value.type_name()), &visitor)),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit unit_struct seq
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess<'d, 'a> {
+    de: &'d Deserializer<'a>,
+    array: Array,
+    index: u32,
+    len: u32,
+}
+
+impl<'de, 'd, 'a> de::SeqAccess<'de> for SeqAccess<'d, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let value = self.array.get(self.index)?;
+        self.index += 1;
+        seed.deserialize(self.de.nested(value)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+struct MapAccess<'d, 'a> {
+    de: &'d Deserializer<'a>,
+    object: Object,
+    keys: Array,
+    len: u32,
+    index: u32,
+    key: Option<Value>,
+}
+
+impl<'de, 'd, 'a> de::MapAccess<'de> for MapAccess<'d, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let key: Value = self.keys.get(self.index)?;
+        self.index += 1;
+        self.key = Some(key.clone());
+        seed.deserialize(MapKey(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let key = self.key.take().expect("next_value_seed called before next_key_seed");
+        let value = self.object.get(key)?;
+        seed.deserialize(self.de.nested(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+// Deserializes a property key, which V8 reports as either a string or (for integer keys) a
+// number. String keys are parsed when integers are expected, e.g. for `HashMap<u32, _>`, and
+// integer keys are formatted when strings are expected, e.g. for `HashMap<String, _>`.
+struct MapKey(Value);
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                let key = match self.0 {
+                    Value::String(ref s) => s.to_string(),
+                    _ => return self.deserialize_any(visitor),
+                };
+                match key.parse() {
+                    Ok(n) => visitor.$visit(n),
+                    Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&key), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for MapKey {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Number(n) if n >= 0.0 => visitor.visit_u64(n as u64),
+            Value::Number(n) => visitor.visit_i64(n as i64),
+            Value::String(s) => visitor.visit_string(s.to_string()),
+            value => Err(de::Error::invalid_type(Unexpected::Other(value.type_name()), &visitor)),
+        }
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Number(n) => visitor.visit_string(n.to_string()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char bytes byte_buf option unit unit_struct seq tuple tuple_struct
+        map struct enum ignored_any
+    }
+}
+
+struct EnumAccess<'a> {
+    de: Deserializer<'a>,
+    variant: StdString,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+    type Error = Error;
+    type Variant = Deserializer<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Deserializer<'a>)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant: de::value::StringDeserializer<Error> = self.variant.into_deserializer();
+        let variant = seed.deserialize(variant)?;
+        Ok((variant, self.de))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Deserializer<'a> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            Value::Undefined | Value::Null => Ok(()),
+            _ => Err(custom("expected null for a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,101 @@
+// The derive macros refer to `::mini_v8`, so they are tested from outside of the crate.
+
+#![cfg(feature = "derive")]
+
+extern crate mini_v8;
+
+use mini_v8::{Error, FromValue, MiniV8, ToValue};
+
+#[derive(ToValue, FromValue, Debug, PartialEq)]
+struct Point<T> {
+    x: T,
+    y: T,
+    label: Option<String>,
+}
+
+#[test]
+fn to_value() {
+    let mv8 = MiniV8::new();
+    let point = Point { x: 1.0, y: 2.0, label: Some("origin".to_owned()) };
+    mv8.global().set("point", point).unwrap();
+    let json: String = mv8.eval("JSON.stringify(point)").unwrap();
+    assert_eq!(json, r#"{"x":1,"y":2,"label":"origin"}"#);
+    let point = Point { x: 1, y: 2, label: None };
+    mv8.global().set("point", point).unwrap();
+    assert!(mv8.eval::<_, bool>("point.label === null").unwrap());
+}
+
+#[test]
+fn from_value() {
+    let mv8 = MiniV8::new();
+    let point: Point<u32> = mv8.eval("({ x: 3, y: 4 })").unwrap();
+    assert_eq!(point, Point { x: 3, y: 4, label: None });
+    let point: Point<u32> = mv8.eval("({ x: 3, y: 4, label: 'a', extra: true })").unwrap();
+    assert_eq!(point, Point { x: 3, y: 4, label: Some("a".to_owned()) });
+
+    // Conversion errors name the field:
+    let missing = mv8.eval::<_, Point<u32>>("({ x: 3 })").unwrap_err();
+    let expected = matches!(missing,
+        Error::FromJsConversionError { from: "undefined", to: "Point.y" });
+    assert!(expected, "{:?}", missing);
+    let invalid = mv8.eval::<_, Point<u32>>("({ x: 3.5, y: 4 })").unwrap_err();
+    let expected = matches!(invalid,
+        Error::FromJsConversionError { from: "number", to: "Point.x" });
+    assert!(expected, "{:?}", invalid);
+    let not_object = mv8.eval::<_, Point<u32>>("3").unwrap_err();
+    assert!(matches!(not_object, Error::FromJsConversionError { from: "number", to: "Point" }));
+
+    // Errors thrown while reading properties are passed through:
+    let throwing = mv8.eval::<_, Point<u32>>("({ get x() { throw new Error('boom'); } })");
+    assert!(matches!(throwing, Err(Error::Value(_))));
+}
+
+#[derive(ToValue, FromValue, Debug, PartialEq)]
+struct Segment {
+    start: Point<u32>,
+    end: Point<u32>,
+}
+
+#[test]
+fn nested() {
+    let mv8 = MiniV8::new();
+    let segment: Segment = mv8.eval("({ start: { x: 0, y: 0 }, end: { x: 1, y: 2 } })").unwrap();
+    assert_eq!(segment.end, Point { x: 1, y: 2, label: None });
+
+    // Conversion errors name the innermost field:
+    let invalid = mv8.eval::<_, Segment>("({ start: { x: 0, y: 0 }, end: { x: 1, y: -2 } })");
+    let invalid = invalid.unwrap_err();
+    let expected = matches!(invalid,
+        Error::FromJsConversionError { from: "number", to: "Point.y" });
+    assert!(expected, "{:?}", invalid);
+    // Nested structs that aren't objects at all are named by the outer field:
+    let not_object = mv8.eval::<_, Segment>("({ start: { x: 0, y: 0 }, end: 3 })").unwrap_err();
+    let expected = matches!(not_object,
+        Error::FromJsConversionError { from: "number", to: "Segment.end" });
+    assert!(expected, "{:?}", not_object);
+}
+
+#[derive(ToValue, FromValue, Debug, PartialEq)]
+struct Meters(f64);
+
+#[derive(ToValue, FromValue, Debug, PartialEq)]
+struct Count(u32);
+
+#[derive(ToValue, FromValue, Debug, PartialEq)]
+struct Wrapper<T>(Vec<T>);
+
+#[test]
+fn newtype() {
+    let mv8 = MiniV8::new();
+    mv8.global().set("distance", Meters(2.5)).unwrap();
+    assert!(mv8.eval::<_, bool>("distance === 2.5").unwrap());
+    let distance: Meters = mv8.eval("4").unwrap();
+    assert_eq!(distance, Meters(4.0));
+    let wrapper: Wrapper<u32> = mv8.eval("[1, 2]").unwrap();
+    assert_eq!(wrapper, Wrapper(vec![1, 2]));
+
+    // Conversion errors are those of the field's type:
+    let invalid = mv8.eval::<_, Count>("1.5").unwrap_err();
+    assert!(matches!(invalid, Error::FromJsConversionError { from: "number", to: "u32" }));
+    assert_eq!(mv8.eval::<_, Count>("3").unwrap(), Count(3));
+}
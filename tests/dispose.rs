@@ -0,0 +1,37 @@
+// V8 can only be disposed of once per process, so this runs as its own test binary.
+
+extern crate mini_v8;
+
+use mini_v8::{Error, MiniV8};
+use std::panic;
+use std::thread;
+
+#[test]
+fn dispose() {
+    let handles: Vec<_> = (0..4).map(|i| thread::spawn(move || {
+        let mv8 = MiniV8::new();
+        let result: usize = mv8.eval(format!("{} * 2", i)).unwrap();
+        result
+    })).collect();
+    let results: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(results, vec![0, 2, 4, 6]);
+
+    // Disposing while a `MiniV8` (or any value created by one) is alive is refused:
+    let mv8 = MiniV8::new();
+    let object = mv8.create_object();
+    drop(mv8);
+    assert!(panic::catch_unwind(mini_v8::dispose).is_err());
+    drop(object);
+
+    mini_v8::dispose();
+    mini_v8::dispose();
+
+    // V8 can't be reinitialized, which is reported the same way every time:
+    assert!(matches!(MiniV8::builder().try_build(), Err(Error::Disposed)));
+    assert!(matches!(MiniV8::create_snapshot(""), Err(Error::Disposed)));
+    for _ in 0..2 {
+        let payload = panic::catch_unwind(MiniV8::new).err().unwrap();
+        let message = *payload.downcast::<String>().unwrap();
+        assert_eq!(message, "cannot create a MiniV8 after V8 has been disposed");
+    }
+}